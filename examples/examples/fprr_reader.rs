@@ -12,7 +12,7 @@ use helpers::format_optional_value;
 const SRC_PATH: &str = "resources/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1km_Prr60lv_Fper10min_FH01-06_grib2.bin";
 #[rustfmt::skip]
 const DST_PATH: &str = "resources/dst/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1km_Prr60lv_Fper10min_FH01-06_grib2.csv";
-/// cspell: enable
+// cspell: enable
 
 fn main() -> anyhow::Result<()> {
     let reader = FPrrReader::new(SRC_PATH)?;
@@ -25,7 +25,7 @@ fn main() -> anyhow::Result<()> {
     writer.write_all(b"lon,lat,hour1,hour2,hour3,hour4,hour5,hour6\n")?;
     for record in reader.value_iter() {
         if should_write(&record) {
-            let lon = record.lon as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
             let lat = record.lat as f64 / 1e6;
             writer.write_fmt(format_args!(
                 "{lon:.6},{lat:.6},{},{},{},{},{},{}\n",
@@ -15,7 +15,7 @@ const DST_ALL_PATH: &str = "resources/dst/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1
 const DST_TANK1_PATH: &str = "resources/dst/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1km_Prr60lv_Fper10min_FH01-06_grib2_tank1.csv";
 #[rustfmt::skip]
 const DST_TANK2_PATH: &str = "resources/dst/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1km_Prr60lv_Fper10min_FH01-06_grib2_tank2.csv";
-/// cspell: enable
+// cspell: enable
 
 fn main() -> anyhow::Result<()> {
     let mut reader = FPswReader::new(SRC_PATH, ForecastRange::Hours6)?;
@@ -31,27 +31,20 @@ fn write_file(reader: &mut FPswReader, tank: PswTank, dst_path: &str) -> anyhow:
     writer.write_all(b"lon,lat,hour1,hour2,hour3,hour4,hour5,hour6\n")?;
     for record in reader.value_iter(tank) {
         if should_write(&record) {
-            let lon = record.lon as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
             let lat = record.lat as f64 / 1e6;
-            writer.write_fmt(format_args!(
-                "{lon:.6},{lat:.6},{},{},{},{},{},{}\n",
-                format_optional_value(record.hour1),
-                format_optional_value(record.hour2),
-                format_optional_value(record.hour3),
-                format_optional_value(record.hour4),
-                format_optional_value(record.hour5),
-                format_optional_value(record.hour6)
-            ))?;
+            let hours = record
+                .hours()
+                .iter()
+                .map(|value| format_optional_value(*value))
+                .collect::<Vec<_>>()
+                .join(",");
+            writer.write_fmt(format_args!("{lon:.6},{lat:.6},{hours}\n"))?;
         }
     }
     writer.flush().map_err(|e| anyhow!(e))
 }
 
 fn should_write(record: &FPswIndex) -> bool {
-    record.hour1.is_some()
-        || record.hour2.is_some()
-        || record.hour3.is_some()
-        || record.hour4.is_some()
-        || record.hour5.is_some()
-        || record.hour6.is_some()
+    record.hours().iter().any(|value| value.is_some())
 }
@@ -12,7 +12,7 @@ use helpers::grib2::should_write_record;
 const SRC_PATH: &str = "resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin";
 #[rustfmt::skip]
 const DST_PATH: &str = "resources/dst/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2_by_grib2reader.csv";
-/// cspell: enable
+// cspell: enable
 
 fn main() -> anyhow::Result<()> {
     let mut reader = Grib2Reader::new(SRC_PATH)?;
@@ -10,7 +10,7 @@ use helpers::{buf_writer, should_write_record};
 const SRC_PATH: &str = "resources/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_ANAL_grib2.bin";
 #[rustfmt::skip]
 const DST_PATH: &str = "resources/dst/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_ANAL_grib2.csv";
-/// cspell: enable
+// cspell: enable
 
 fn main() -> anyhow::Result<()> {
     let mut reader = LwjmReader::new(SRC_PATH, false)?;
@@ -19,7 +19,7 @@ fn main() -> anyhow::Result<()> {
     writer.write_all(b"lon,lat,value\n")?;
     for record in iter.flatten() {
         if should_write_record(&record) {
-            let lon = record.lon as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
             let lat = record.lat as f64 / 1e6;
             if let Some(value) = record.value {
                 writer.write_fmt(format_args!("{lon:.6},{lat:.6},{value}\n"))?;
@@ -17,7 +17,7 @@ const DST_HOUR1_PATH: &str = "resources/dst/Z__C_RJTD_20180706095000_MET_INF_Jdo
 const DST_HOUR2_PATH: &str = "resources/dst/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_FH00-03_grib2_hour2.csv";
 #[rustfmt::skip]
 const DST_HOUR3_PATH: &str = "resources/dst/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_FH00-03_grib2_hour3.csv";
-/// cspell: enable
+// cspell: enable
 
 fn main() -> anyhow::Result<()> {
     let mut reader = LwjmReader::new(SRC_PATH, true)?;
@@ -44,7 +44,7 @@ fn output_judgement<R: Read + Seek>(
     writer.write_all(b"lon,lat,value\n")?;
     for record in iter.flatten() {
         if should_write_record(&record) {
-            let lon = record.lon as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
             let lat = record.lat as f64 / 1e6;
             if let Some(value) = record.value {
                 writer.write_fmt(format_args!("{lon:.6},{lat:.6},{value}\n"))?;
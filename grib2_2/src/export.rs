@@ -0,0 +1,598 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::Write;
+
+use crate::readers::Grib2Record;
+use crate::{Grib2Error, Grib2Result};
+
+/// 座標を出力するときの小数点以下の桁数の既定値
+///
+/// 1e-6度単位で記録されている緯度と経度を、情報を失わずに出力できる桁数である。
+pub const DEFAULT_COORD_DECIMALS: usize = 6;
+
+/// レコードを`lon,lat,value`形式のCSVとして書き込む。
+///
+/// ヘッダーを書き込んだ後、値を持つレコードのみを1行ずつ書き込む。
+/// 緯度と経度は度単位に変換して、小数点以下`coord_decimals`桁で出力する。
+/// 1km格子であれば小数点以下3桁で十分に格子を識別できるため、桁数を減らすことで出力サイズを
+/// 削減できる。
+///
+/// # 引数
+///
+/// * `iter` - レコードを反復処理するイテレーター
+/// * `writer` - ライター
+/// * `coord_decimals` - 座標の小数点以下の桁数
+///
+/// # 戻り値
+///
+/// * 書き込んだレコードの数
+pub fn write_csv<I, V, W>(iter: I, writer: &mut W, coord_decimals: usize) -> Grib2Result<usize>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy + Display,
+    W: Write,
+{
+    writer
+        .write_all(b"lon,lat,value\n")
+        .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+    let mut number_of_writes = 0;
+    for record in iter {
+        let record = record?;
+        if let Some(value) = record.value {
+            let lat = record.lat as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
+            writer
+                .write_fmt(format_args!(
+                    "{lon:.coord_decimals$},{lat:.coord_decimals$},{value}\n"
+                ))
+                .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+            number_of_writes += 1;
+        }
+    }
+
+    Ok(number_of_writes)
+}
+
+/// レコードを1行1つのJSONオブジェクト（NDJSON）として書き込む。
+///
+/// 値を持つレコードのみを、`{"lat":..,"lon":..,"value":..}`形式で1行ずつ書き込む。
+/// 緯度と経度は度単位に変換して、小数点以下`coord_decimals`桁で出力する。
+/// レコードは読み込んだ順に書き込むため、格子全体をメモリに保持しない。
+///
+/// # 引数
+///
+/// * `iter` - レコードを反復処理するイテレーター
+/// * `writer` - ライター
+/// * `coord_decimals` - 座標の小数点以下の桁数
+///
+/// # 戻り値
+///
+/// * 書き込んだレコードの数
+pub fn write_ndjson<I, V, W>(iter: I, writer: &mut W, coord_decimals: usize) -> Grib2Result<usize>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy + Display,
+    W: Write,
+{
+    let mut number_of_writes = 0;
+    for record in iter {
+        let record = record?;
+        if let Some(value) = record.value {
+            let lat = record.lat as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
+            writer
+                .write_fmt(format_args!(
+                    "{{\"lat\":{lat:.coord_decimals$},\"lon\":{lon:.coord_decimals$},\"value\":{value}}}\n"
+                ))
+                .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+            number_of_writes += 1;
+        }
+    }
+
+    Ok(number_of_writes)
+}
+
+/// レコードを`POINT(lon lat)`形式のWKTと値をタブで区切った形式で書き込む。
+///
+/// 値を持つレコードのみを1行ずつ書き込む。ヘッダーは書き込まないため、PostGISなどの
+/// データベースに`COPY`で一括登録できる。
+/// 緯度と経度は度単位に変換して、小数点以下`DEFAULT_COORD_DECIMALS`桁で出力する。
+///
+/// # 引数
+///
+/// * `iter` - レコードを反復処理するイテレーター
+/// * `writer` - ライター
+///
+/// # 戻り値
+///
+/// * 書き込んだレコードの数
+pub fn write_wkt<I, V, W>(iter: I, writer: &mut W) -> Grib2Result<usize>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy + Display,
+    W: Write,
+{
+    let mut number_of_writes = 0;
+    for record in iter {
+        let record = record?;
+        if let Some(value) = record.value {
+            let lat = record.lat as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
+            writer
+                .write_fmt(format_args!(
+                    "POINT({lon:.DEFAULT_COORD_DECIMALS$} {lat:.DEFAULT_COORD_DECIMALS$})\t{value}\n"
+                ))
+                .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+            number_of_writes += 1;
+        }
+    }
+
+    Ok(number_of_writes)
+}
+
+/// レコードを座標をキーとしたハッシュマップに格納する。
+///
+/// キーは`(緯度, 経度)`（1e-6度単位、南緯は負の値）のタプルで、値を持たない格子点も`None`として
+/// 格納する。
+/// 任意のリーダーのイテレーターから、座標で値を検索するためのマップを作成できる。
+///
+/// # 引数
+///
+/// * `iter` - レコードを反復処理するイテレーター
+///
+/// # 戻り値
+///
+/// * 座標をキーとしたハッシュマップ
+pub fn collect_map<I, V>(iter: I) -> Grib2Result<HashMap<(i32, u32), Option<V>>>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy,
+{
+    let mut map = HashMap::with_capacity(iter.size_hint().0);
+    for record in iter {
+        let record = record?;
+        map.insert((record.lat, record.lon), record.value);
+    }
+
+    Ok(map)
+}
+
+/// 複数の格子の値を、1つの座標につき1行の横持ちのCSVとして書き込む。
+///
+/// `iter`が返すレコードの座標と値を先頭の列とし、`columns`に格納された値をその後ろの列として
+/// `lon,lat,<headers[0]>,<headers[1]>,...`形式で書き込む。
+/// `columns`の各要素は、`iter`と同じ順番で格子点の値を格納していなければならない。
+/// 値を持たないセルは空とし、すべての列が値を持たない座標は書き込まない。
+///
+/// # 引数
+///
+/// * `iter` - 先頭の列のレコードを反復処理するイテレーター
+/// * `columns` - 2列目以降の格子の値を格納したベクターのスライス
+/// * `headers` - 値の列のヘッダー（要素数は`columns`の要素数 + 1）
+/// * `writer` - ライター
+/// * `coord_decimals` - 座標の小数点以下の桁数
+///
+/// # 戻り値
+///
+/// * 書き込んだ行の数
+pub fn write_wide_csv<I, V, W>(
+    iter: I,
+    columns: &[Vec<Option<V>>],
+    headers: &[&str],
+    writer: &mut W,
+    coord_decimals: usize,
+) -> Grib2Result<usize>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy + Display,
+    W: Write,
+{
+    if headers.len() != columns.len() + 1 {
+        return Err(Grib2Error::RuntimeError(
+            format!(
+                "ヘッダーの数({})が値の列の数({})と一致しません。",
+                headers.len(),
+                columns.len() + 1
+            )
+            .into(),
+        ));
+    }
+    writer
+        .write_fmt(format_args!("lon,lat,{}\n", headers.join(",")))
+        .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+    let cell = |value: Option<V>| value.map(|v| v.to_string()).unwrap_or_default();
+    let mut number_of_reads = 0;
+    let mut number_of_writes = 0;
+    for (index, record) in iter.enumerate() {
+        let record = record?;
+        number_of_reads += 1;
+        let mut values = Vec::with_capacity(columns.len() + 1);
+        values.push(record.value);
+        for column in columns {
+            let value = column.get(index).ok_or_else(|| {
+                Grib2Error::RuntimeError(
+                    "列に格納された格子点の数がレコードの数より少ないです。".into(),
+                )
+            })?;
+            values.push(*value);
+        }
+        if values.iter().all(|v| v.is_none()) {
+            continue;
+        }
+        let lat = record.lat as f64 / 1e6;
+        let lon = record.lon_i32() as f64 / 1e6;
+        let cells = values.into_iter().map(cell).collect::<Vec<_>>().join(",");
+        writer
+            .write_fmt(format_args!(
+                "{lon:.coord_decimals$},{lat:.coord_decimals$},{cells}\n"
+            ))
+            .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+        number_of_writes += 1;
+    }
+    if columns.iter().any(|c| c.len() != number_of_reads) {
+        return Err(Grib2Error::GridMismatch {
+            field: "number_of_points",
+        });
+    }
+
+    Ok(number_of_writes)
+}
+
+/// PNG画像を出力するときのカラーマップ
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colormap {
+    /// 最小値を黒、最大値を白とするグレースケール
+    Grayscale,
+    /// 最小値を青、最大値を赤とし、シアン、緑、黄を経由する虹色
+    Rainbow,
+}
+
+#[cfg(feature = "image")]
+impl Colormap {
+    /// 0以上1以下に正規化した値を色に変換する。
+    ///
+    /// # 引数
+    ///
+    /// * `t` - 0以上1以下に正規化した値
+    ///
+    /// # 戻り値
+    ///
+    /// * RGBの色
+    fn color(&self, t: f64) -> [u8; 3] {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Colormap::Grayscale => {
+                let v = (t * 255.0).round() as u8;
+                [v, v, v]
+            }
+            Colormap::Rainbow => {
+                const STOPS: [[f64; 3]; 5] = [
+                    [0.0, 0.0, 255.0],
+                    [0.0, 255.0, 255.0],
+                    [0.0, 255.0, 0.0],
+                    [255.0, 255.0, 0.0],
+                    [255.0, 0.0, 0.0],
+                ];
+                let position = t * (STOPS.len() - 1) as f64;
+                let index = (position.floor() as usize).min(STOPS.len() - 2);
+                let ratio = position - index as f64;
+                let (from, to) = (STOPS[index], STOPS[index + 1]);
+                [0, 1, 2].map(|i| (from[i] + (to[i] - from[i]) * ratio).round() as u8)
+            }
+        }
+    }
+}
+
+/// レコードを、カラーマップで着色したPNG画像として書き込む。
+///
+/// レコードは格子の走査順（北西端から東方向、行ごとに南方向）に並んでいることを前提に、
+/// 1つの格子点を1画素として描画する。値を持たない格子点の画素は透明にする。
+/// 値には`decimal_scale`を乗じた物理値を`value_range`の範囲で正規化して色を割り当てる。
+/// 格子の大きさは第3節の`number_of_along_lat_points`と`number_of_along_lon_points`、
+/// 物理値の範囲は第5節の`value_range`、尺度は第5節の`decimal_scale`から取得できる。
+///
+/// # 引数
+///
+/// * `iter` - レコードを反復処理するイテレーター
+/// * `grid_shape` - 緯線に沿った格子点数（画像の幅）と経線に沿った格子点数（画像の高さ）の組
+/// * `value_range` - 物理値の最小値と最大値の組
+/// * `decimal_scale` - データ代表値を物理値に変換する尺度
+/// * `colormap` - カラーマップ
+/// * `path` - 書き込むPNGファイルのパス
+///
+/// # 戻り値
+///
+/// * 値を持つ格子点の数
+#[cfg(feature = "image")]
+pub fn write_png<I, V, P>(
+    iter: I,
+    grid_shape: (u32, u32),
+    value_range: (f64, f64),
+    decimal_scale: f64,
+    colormap: Colormap,
+    path: P,
+) -> Grib2Result<usize>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy + Into<f64>,
+    P: AsRef<std::path::Path>,
+{
+    let (width, height) = grid_shape;
+    let (min, max) = value_range;
+    let span = max - min;
+    let number_of_pixels = width as usize * height as usize;
+    let mut image = image::RgbaImage::new(width, height);
+    let mut number_of_reads = 0;
+    let mut number_of_values = 0;
+    for record in iter {
+        let record = record?;
+        if number_of_pixels <= number_of_reads {
+            return Err(Grib2Error::GridMismatch {
+                field: "number_of_points",
+            });
+        }
+        if let Some(value) = record.value {
+            let physical = value.into() * decimal_scale;
+            let t = if 0.0 < span {
+                (physical - min) / span
+            } else {
+                0.0
+            };
+            let [r, g, b] = colormap.color(t);
+            let x = (number_of_reads % width as usize) as u32;
+            let y = (number_of_reads / width as usize) as u32;
+            image.put_pixel(x, y, image::Rgba([r, g, b, 255]));
+            number_of_values += 1;
+        }
+        number_of_reads += 1;
+    }
+    if number_of_reads != number_of_pixels {
+        return Err(Grib2Error::GridMismatch {
+            field: "number_of_points",
+        });
+    }
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+
+    Ok(number_of_values)
+}
+
+/// レコードを`lon`、`lat`、`value`列を持つpolarsの`DataFrame`に変換する。
+///
+/// 値を持つレコードのみを、読み込んだ順に1行として格納する。各列のデータ型は次の通りである。
+///
+/// * `lon` - `Float64`、西経を負とする度単位の経度
+/// * `lat` - `Float64`、南緯を負とする度単位の緯度
+/// * `value` - `Int32`、尺度因子を適用する前のデータ代表値
+///
+/// データ代表値は、符号の有無によらず情報を失わずに格納できる`Int32`とする。物理値が
+/// 必要な場合は、第5節の`decimal_scale`を`value`列に乗じる。
+///
+/// # 引数
+///
+/// * `iter` - レコードを反復処理するイテレーター
+///
+/// # 戻り値
+///
+/// * `DataFrame`
+#[cfg(feature = "polars")]
+pub fn to_polars<I, V>(iter: I) -> Grib2Result<polars::frame::DataFrame>
+where
+    I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    V: Clone + Copy + Into<i32>,
+{
+    use polars::prelude::Column;
+
+    let mut lons = Vec::with_capacity(iter.size_hint().0);
+    let mut lats = Vec::with_capacity(iter.size_hint().0);
+    let mut values = Vec::with_capacity(iter.size_hint().0);
+    for record in iter {
+        let record = record?;
+        if let Some(value) = record.value {
+            lons.push(record.lon_i32() as f64 / 1e6);
+            lats.push(record.lat as f64 / 1e6);
+            values.push(value.into());
+        }
+    }
+
+    polars::frame::DataFrame::new(vec![
+        Column::new("lon".into(), lons),
+        Column::new("lat".into(), lats),
+        Column::new("value".into(), values),
+    ])
+    .map_err(|e| Grib2Error::Unexpected(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        collect_map, write_csv, write_ndjson, write_wide_csv, write_wkt, DEFAULT_COORD_DECIMALS,
+    };
+    use crate::readers::Grib2Record;
+    use crate::Grib2Result;
+
+    fn records() -> Vec<Grib2Result<Grib2Record<u16>>> {
+        vec![
+            Ok(Grib2Record {
+                lat: 35_000_000,
+                lon: 139_000_000,
+                value: Some(10u16),
+            }),
+            Ok(Grib2Record {
+                lat: 35_000_000,
+                lon: 139_012_500,
+                value: None,
+            }),
+            Ok(Grib2Record {
+                lat: 35_000_000,
+                lon: 139_025_000,
+                value: Some(20u16),
+            }),
+        ]
+    }
+
+    #[test]
+    fn write_ndjson_ok() {
+        let mut buf = vec![];
+        let number_of_writes =
+            write_ndjson(records().into_iter(), &mut buf, DEFAULT_COORD_DECIMALS).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert_eq!(2, number_of_writes);
+        assert_eq!(2, output.lines().count());
+        assert_eq!(
+            "{\"lat\":35.000000,\"lon\":139.000000,\"value\":10}",
+            output.lines().next().unwrap()
+        );
+    }
+
+    #[test]
+    fn collect_map_ok() {
+        let map = collect_map(records().into_iter()).unwrap();
+
+        assert_eq!(records().len(), map.len());
+        assert_eq!(Some(&Some(10)), map.get(&(35_000_000, 139_000_000)));
+        assert_eq!(Some(&None), map.get(&(35_000_000, 139_012_500)));
+    }
+
+    #[test]
+    fn write_wkt_ok() {
+        let mut buf = vec![];
+        let number_of_writes = write_wkt(records().into_iter(), &mut buf).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert_eq!(2, number_of_writes);
+        assert_eq!(
+            vec![
+                "POINT(139.000000 35.000000)\t10",
+                "POINT(139.025000 35.000000)\t20"
+            ],
+            output.lines().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn write_csv_with_coord_decimals_ok() {
+        let mut buf3 = vec![];
+        write_csv(records().into_iter(), &mut buf3, 3).unwrap();
+        let output3 = String::from_utf8(buf3).unwrap();
+        let mut buf6 = vec![];
+        write_csv(records().into_iter(), &mut buf6, 6).unwrap();
+        let output6 = String::from_utf8(buf6).unwrap();
+
+        assert_eq!(
+            vec!["lon,lat,value", "139.000,35.000,10", "139.025,35.000,20"],
+            output3.lines().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                "lon,lat,value",
+                "139.000000,35.000000,10",
+                "139.025000,35.000000,20"
+            ],
+            output6.lines().collect::<Vec<_>>()
+        );
+        assert!(output3.len() < output6.len());
+    }
+
+    #[test]
+    fn write_wide_csv_ok() {
+        let columns = vec![vec![Some(3), None, None], vec![None, None, Some(5)]];
+        let mut buf = vec![];
+        let written = write_wide_csv(
+            records().into_iter(),
+            &columns,
+            &["a", "b", "c"],
+            &mut buf,
+            DEFAULT_COORD_DECIMALS,
+        )
+        .unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(2, written);
+        assert_eq!("lon,lat,a,b,c", lines[0]);
+        assert!(lines.iter().all(|l| l.split(',').count() == 5));
+        assert_eq!(written, lines.len() - 1);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn write_png_ok() {
+        use super::{write_png, Colormap};
+
+        let path = std::env::temp_dir().join(format!("write_png_ok_{}.png", std::process::id()));
+        let mut values = records();
+        values.extend(records());
+        let number_of_values = write_png(
+            values.into_iter(),
+            (3, 2),
+            (0.0, 20.0),
+            1.0,
+            Colormap::Rainbow,
+            &path,
+        )
+        .unwrap();
+        let image = image::open(&path).unwrap().into_rgba8();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(4, number_of_values);
+        assert_eq!((3, 2), image.dimensions());
+        assert_eq!(0, image.get_pixel(1, 0)[3]);
+        assert_eq!(&image::Rgba([255, 0, 0, 255]), image.get_pixel(2, 1));
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn write_png_with_short_grid_err() {
+        use super::{write_png, Colormap};
+
+        let path = std::env::temp_dir().join(format!(
+            "write_png_with_short_grid_err_{}.png",
+            std::process::id()
+        ));
+        let result = write_png(
+            records().into_iter(),
+            (3, 2),
+            (0.0, 20.0),
+            1.0,
+            Colormap::Grayscale,
+            &path,
+        );
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "polars")]
+    #[test]
+    fn to_polars_ok() {
+        use super::to_polars;
+        use polars::prelude::DataType;
+
+        let number_of_presents = records()
+            .iter()
+            .filter(|r| r.as_ref().unwrap().value.is_some())
+            .count();
+        let df = to_polars(records().into_iter()).unwrap();
+
+        assert_eq!(number_of_presents, df.height());
+        assert_eq!(&DataType::Float64, df.column("lon").unwrap().dtype());
+        assert_eq!(&DataType::Float64, df.column("lat").unwrap().dtype());
+        assert_eq!(&DataType::Int32, df.column("value").unwrap().dtype());
+        assert_eq!(Some(20), df.column("value").unwrap().i32().unwrap().get(1));
+    }
+
+    #[test]
+    fn write_wide_csv_with_short_column_err() {
+        let columns = vec![vec![Some(3)]];
+        let mut buf = vec![];
+        let result = write_wide_csv(
+            records().into_iter(),
+            &columns,
+            &["a", "b"],
+            &mut buf,
+            DEFAULT_COORD_DECIMALS,
+        );
+        assert!(result.is_err());
+    }
+}
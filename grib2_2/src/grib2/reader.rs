@@ -3,8 +3,13 @@ use std::io::{BufReader, Read, Seek};
 use std::path::Path;
 
 use num_format::{Locale, ToFormattedString as _};
+use time::OffsetDateTime;
 
-use crate::readers::records::expand_run_length;
+use crate::readers::records::{expand_run_length, validate_level_values};
+use crate::readers::utils::{
+    peek_section_number, read_available_bytes, sign_magnitude_to_i32, validate_section_order,
+};
+use crate::readers::ReaderOptions;
 use crate::{Grib2Error, Grib2Result};
 
 use super::sections::{
@@ -12,9 +17,9 @@ use super::sections::{
 };
 
 /// GRIB2ファイルリーダー
-pub struct Grib2Reader {
+pub struct Grib2Reader<R = File> {
     /// ファイルリーダー
-    reader: BufReader<File>,
+    reader: BufReader<R>,
     /// 第0節:指示節
     pub section0: Section0,
     /// 第1節:識別節
@@ -33,6 +38,63 @@ pub struct Grib2Reader {
     pub section7: Section7,
     /// 第8節: 終端節
     pub section8: Section8,
+    /// 2組目以降の第4節から第7節までの節
+    pub subsequent_products: Vec<ProductSections>,
+    /// 記録されている順に格納した、節番号、節の開始位置及び節の長さ（バイト数）
+    section_layout: Vec<(u8, usize, usize)>,
+}
+
+/// 第4節から第7節までの節
+pub struct ProductSections {
+    /// 第3節:格子系定義節（プロダクトの直前に記録されている場合）
+    pub section3: Option<Section3>,
+    /// 第4節:プロダクト定義節
+    pub section4: Section4,
+    /// 第5節:資料表現節
+    pub section5: Section5,
+    /// 第6節:ビットマップ節
+    pub section6: Section6,
+    /// 第7節:資料節
+    pub section7: Section7,
+}
+
+impl ProductSections {
+    /// 第4節から第7節までの節を読み込む。
+    ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `layout` - 読み込んだ節の節番号、開始位置及び長さを追加するベクター
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 第4節から第7節までの節
+    fn from_reader<R: Read + Seek>(
+        reader: &mut BufReader<R>,
+        layout: &mut Vec<(u8, usize, usize)>,
+        options: ReaderOptions,
+    ) -> Grib2Result<Self> {
+        let skip = options.skip_unknown_templates;
+        let section3 = match peek_section_number(reader)? {
+            3 => Some(read_section(reader, 3, layout, Section3::from_reader)?),
+            _ => None,
+        };
+        let section4 = read_section(reader, 4, layout, |r| Section4::from_reader(r, skip))?;
+        let section5 = read_section(reader, 5, layout, |r| Section5::from_reader(r, skip))?;
+        let section6 = read_section(reader, 6, layout, Section6::from_reader)?;
+        let section7 = read_section(reader, 7, layout, Section7::from_reader)?;
+
+        Ok(Self {
+            section3,
+            section4,
+            section5,
+            section6,
+            section7,
+        })
+    }
 }
 
 impl Grib2Reader {
@@ -44,6 +106,18 @@ impl Grib2Reader {
     ///
     /// # GRIB2リーダー
     pub fn new<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        Self::with_options(path, ReaderOptions::default())
+    }
+
+    /// オプションを指定してGRIB2ファイルを開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 開くGRIB2ファイルのパス。
+    /// * `options` - リーダーのオプション
+    ///
+    /// # GRIB2リーダー
+    pub fn with_options<P: AsRef<Path>>(path: P, options: ReaderOptions) -> Grib2Result<Self> {
         let path = path.as_ref();
         if !path.is_file() {
             return Err(Grib2Error::FileDoesNotExist);
@@ -52,16 +126,72 @@ impl Grib2Reader {
             .read(true)
             .open(path)
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
-        let mut reader = BufReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
-        let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2::from_reader(&mut reader)?;
-        let section3 = Section3::from_reader(&mut reader)?;
-        let section4 = Section4::from_reader(&mut reader)?;
-        let section5 = Section5::from_reader(&mut reader)?;
-        let section6 = Section6::from_reader(&mut reader)?;
-        let section7 = Section7::from_reader(&mut reader)?;
-        let section8 = Section8::from_reader(&mut reader)?;
+
+        Self::from_buf_reader_with_options(BufReader::new(file), options)
+    }
+}
+
+impl<R> Grib2Reader<R>
+where
+    R: Read + Seek,
+{
+    /// 開かれているリーダーからGRIB2を読み込む。
+    ///
+    /// ファイル管理機構などが開いた`BufReader`を、ファイルを開き直さずに利用できる。
+    /// リーダーの現在の位置から第0節を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2を読み込むリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// * GRIB2リーダー
+    pub fn from_buf_reader(reader: BufReader<R>) -> Grib2Result<Self> {
+        Self::from_buf_reader_with_options(reader, ReaderOptions::default())
+    }
+
+    /// オプションを指定して、開かれているリーダーからGRIB2を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2を読み込むリーダー
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * GRIB2リーダー
+    pub fn from_buf_reader_with_options(
+        mut reader: BufReader<R>,
+        options: ReaderOptions,
+    ) -> Grib2Result<Self> {
+        let skip = options.skip_unknown_templates;
+        let r = &mut reader;
+        let mut layout = vec![];
+        let section0 = read_section(r, 0, &mut layout, |r| {
+            Section0::from_reader(r, options.max_leading_bytes)
+        })?;
+        // 節の配置には、`GRIB`の前に記録されている余分なバイトを含めない
+        if let Some((_, start, bytes)) = layout.first_mut() {
+            *start += section0.offset;
+            *bytes -= section0.offset;
+        }
+        validate_section_order(r)?;
+        let section1 = read_section(r, 1, &mut layout, Section1::from_reader)?;
+        let section2 = read_section(r, 2, &mut layout, Section2::from_reader)?;
+        let section3 = read_section(r, 3, &mut layout, Section3::from_reader)?;
+        let section4 = read_section(r, 4, &mut layout, |r| Section4::from_reader(r, skip))?;
+        let section5 = read_section(r, 5, &mut layout, |r| Section5::from_reader(r, skip))?;
+        let section6 = read_section(r, 6, &mut layout, Section6::from_reader)?;
+        let section7 = read_section(r, 7, &mut layout, Section7::from_reader)?;
+        // 第8節が現れるまで、第4節から第7節までの節（直前の第3節を含む）を繰り返し読み込む
+        let mut subsequent_products = vec![];
+        while !is_section8_next(r, options.allow_missing_end_marker)? {
+            subsequent_products.push(ProductSections::from_reader(r, &mut layout, options)?);
+        }
+        let section8 = read_section(r, 8, &mut layout, |r| {
+            Section8::from_reader(r, options.allow_missing_end_marker)
+        })?;
 
         Ok(Self {
             reader,
@@ -74,6 +204,70 @@ impl Grib2Reader {
             section6,
             section7,
             section8,
+            subsequent_products,
+            section_layout: layout,
+        })
+    }
+
+    /// GRIB2ファイルに記録されている節の配置を、記録されている順に返す。
+    ///
+    /// 節の開始位置は、リーダーの先頭からのバイト数である。第2節のように記録されていない
+    /// 節は含まない。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節番号、節の開始位置及び節の長さ（バイト数）の組を格納したスライス
+    pub fn section_layout(&self) -> &[(u8, usize, usize)] {
+        &self.section_layout
+    }
+
+    /// GRIB2ファイルに記録されているプロダクト定義テンプレート番号と資料表現テンプレート番号の組を、
+    /// 記録されている順に返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * プロダクト定義テンプレート番号と資料表現テンプレート番号の組を格納したベクター
+    pub fn template_summary(&self) -> Vec<(u16, u16)> {
+        let first = (
+            self.section4.template_number(),
+            self.section5.template_number(),
+        );
+        std::iter::once(first)
+            .chain(
+                self.subsequent_products
+                    .iter()
+                    .map(|p| (p.section4.template_number(), p.section5.template_number())),
+            )
+            .collect()
+    }
+
+    /// GRIB2ファイルのヘッダーを、平坦な構造体にまとめて返す。
+    ///
+    /// 最初のプロダクト（第4節から第7節）の情報を格納する。
+    ///
+    /// # 戻り値
+    ///
+    /// * GRIB2ファイルのメタデータ
+    pub fn metadata(&self) -> Grib2Result<Grib2Metadata> {
+        let (parameter_category, parameter_number) = self.section4.parameter()?;
+
+        Ok(Grib2Metadata {
+            referenced_at: self.section1.referenced_at,
+            center: self.section1.center,
+            sub_center: self.section1.sub_center,
+            number_of_points: self.section3.number_of_points()?,
+            lat_max: self.section3.lat_of_first_grid_point()?,
+            lat_min: self.section3.lat_of_last_grid_point()?,
+            lon_min: self.section3.lon_of_first_grid_point()?,
+            lon_max: self.section3.lon_of_last_grid_point()?,
+            lat_inc: self.section3.j_direction_increment()?,
+            lon_inc: self.section3.i_direction_increment()?,
+            parameter_category,
+            parameter_number,
+            valid_at: self.section4.valid_at(self.section1.referenced_at)?,
+            grid_definition_template_number: self.section3.template_number(),
+            product_definition_template_number: self.section4.template_number(),
+            data_representation_template_number: self.section5.template_number(),
         })
     }
 
@@ -82,28 +276,280 @@ impl Grib2Reader {
     /// # 戻り値
     ///
     /// * GRIB2のレコードを反復処理するイテレーター
-    pub fn record_iter(&mut self) -> Grib2Result<Grib2RecordIter<'_, File>> {
-        Grib2RecordIterBuilder::new()
-            .reader(&mut self.reader)
-            .run_length_position(self.section7.run_length_position()?)
-            .run_length_bytes(self.section7.run_length_bytes()?)
-            .number_of_points(self.section3.number_of_points()?)
-            .lat_max(self.section3.lat_of_first_grid_point()?)
-            .lon_min(self.section3.lon_of_first_grid_point()?)
-            .lon_max(self.section3.lon_of_last_grid_point()?)
-            .lat_inc(self.section3.j_direction_increment()?)
-            .lon_inc(self.section3.i_direction_increment()?)
-            .nbit(self.section5.bit_per_value()? as u16)
-            .maxv(self.section5.max_level_value()?)
-            .level_values(self.section5.level_values()?)
-            .build()
+    pub fn record_iter(&mut self) -> Grib2Result<Grib2RecordIter<'_, R>> {
+        build_record_iter(
+            &mut self.reader,
+            &self.section3,
+            &self.section5,
+            &self.section7,
+        )
     }
+
+    /// GRIB2ファイルに記録されているすべてのプロダクト（第4節から第7節）を、記録されている順に
+    /// 反復処理するイテレーターを返す。
+    ///
+    /// 各プロダクトのレコードを反復処理するイテレーターはファイルリーダーを共有するため、
+    /// `Grib2ProductIter::next_product`で次のプロダクトを取得する前に、前のプロダクトを
+    /// 破棄する必要がある。
+    ///
+    /// # 戻り値
+    ///
+    /// * プロダクトを反復処理するイテレーター
+    pub fn products_iter(&mut self) -> Grib2ProductIter<'_, R> {
+        Grib2ProductIter {
+            grib2: self,
+            index: 0,
+        }
+    }
+}
+
+/// GRIB2ファイルに記録されているプロダクトを反復処理するイテレーター
+///
+/// プロダクトのレコードイテレーターがファイルリーダーを可変で借用するため、`Iterator`
+/// トレイトではなく`next_product`メソッドでプロダクトを返す。
+pub struct Grib2ProductIter<'a, R = File> {
+    /// GRIB2ファイルリーダー
+    grib2: &'a mut Grib2Reader<R>,
+    /// 次に返すプロダクトのインデックス
+    index: usize,
+}
+
+impl<'a, R> Grib2ProductIter<'a, R>
+where
+    R: Read + Seek,
+{
+    /// 次のプロダクトを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 次のプロダクト、すべてのプロダクトを返した場合は`None`
+    pub fn next_product(&mut self) -> Option<Grib2Result<Grib2Product<'_, R>>> {
+        let grib2 = &mut *self.grib2;
+        let (section3, section4, section5, section7) = match self.index {
+            0 => (
+                &grib2.section3,
+                &grib2.section4,
+                &grib2.section5,
+                &grib2.section7,
+            ),
+            index => {
+                let product = grib2.subsequent_products.get(index - 1)?;
+                (
+                    product.section3.as_ref().unwrap_or(&grib2.section3),
+                    &product.section4,
+                    &product.section5,
+                    &product.section7,
+                )
+            }
+        };
+        self.index += 1;
+        let records = match build_record_iter(&mut grib2.reader, section3, section5, section7) {
+            Ok(records) => records,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(Ok(Grib2Product {
+            section3,
+            section4,
+            section5,
+            section7,
+            records,
+        }))
+    }
+
+    /// 残りのプロダクトの数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 残りのプロダクトの数
+    pub fn remaining(&self) -> usize {
+        (self.grib2.subsequent_products.len() + 1).saturating_sub(self.index)
+    }
+}
+
+/// GRIB2ファイルに記録されているプロダクト
+pub struct Grib2Product<'a, R = File>
+where
+    R: Read,
+{
+    /// 第3節:格子系定義節
+    ///
+    /// プロダクトの直前に第3節が記録されていない場合は、最初に記録されている第3節である。
+    pub section3: &'a Section3,
+    /// 第4節:プロダクト定義節
+    pub section4: &'a Section4,
+    /// 第5節:資料表現節
+    pub section5: &'a Section5,
+    /// 第7節:資料節
+    pub section7: &'a Section7,
+    /// プロダクトのレコードを反復処理するイテレーター
+    pub records: Grib2RecordIter<'a, R>,
+}
+
+/// 第3節、第5節及び第7節から、レコードを反復処理するイテレーターを構築する。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `section3` - 第3節:格子系定義節
+/// * `section5` - 第5節:資料表現節
+/// * `section7` - 第7節:資料節
+///
+/// # 戻り値
+///
+/// * GRIB2のレコードを反復処理するイテレーター
+fn build_record_iter<'a, R: Read + Seek>(
+    reader: &'a mut BufReader<R>,
+    section3: &Section3,
+    section5: &'a Section5,
+    section7: &Section7,
+) -> Grib2Result<Grib2RecordIter<'a, R>> {
+    Grib2RecordIterBuilder::new()
+        .reader(reader)
+        .run_length_position(section7.run_length_position()?)
+        .run_length_bytes(section7.run_length_bytes()?)
+        .number_of_points(section3.number_of_points()?)
+        .lat_max(section3.lat_of_first_grid_point()?)
+        .lon_min(section3.lon_of_first_grid_point()?)
+        .lon_max(section3.lon_of_last_grid_point()?)
+        .lat_inc(section3.j_direction_increment()?)
+        .lon_inc(section3.i_direction_increment()?)
+        .nbit(section5.bit_per_value()? as u16)
+        .maxv(section5.max_level_value()?)
+        .level_values(section5.level_values()?)
+        .build()
+}
+
+/// 節を読み込み、その節の節番号、開始位置及び長さを`layout`に追加する。
+///
+/// 長さが0バイトの節は、`layout`に追加しない。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `section_number` - 読み込む節の節番号
+/// * `layout` - 節番号、開始位置及び長さを追加するベクター
+/// * `read` - 節を読み込む関数
+///
+/// # 戻り値
+///
+/// * 読み込んだ節
+fn read_section<R, T, F>(
+    reader: &mut BufReader<R>,
+    section_number: u8,
+    layout: &mut Vec<(u8, usize, usize)>,
+    read: F,
+) -> Grib2Result<T>
+where
+    R: Read + Seek,
+    F: FnOnce(&mut BufReader<R>) -> Grib2Result<T>,
+{
+    let start = stream_position(reader)?;
+    let section = read(reader)?;
+    let end = stream_position(reader)?;
+    if start < end {
+        layout.push((section_number, start, end - start));
+    }
+
+    Ok(section)
+}
+
+/// リーダーの現在の位置を返す。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+///
+/// # 戻り値
+///
+/// * リーダーの先頭からのバイト数
+fn stream_position<R: Seek>(reader: &mut BufReader<R>) -> Grib2Result<usize> {
+    reader
+        .stream_position()
+        .map(|position| position as usize)
+        .map_err(|_| Grib2Error::ReadError("ファイルの読み込み位置の取得に失敗しました。".into()))
+}
+
+/// ファイルポインターの位置に第8節の終端マーカーが記録されているかを確認する。
+///
+/// ファイルポインターの位置は変更しない。`allow_missing_end_marker`が`true`の場合、
+/// ファイルの終端までのバイト数が終端マーカーのバイト数に満たなければ、第8節として扱う。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `allow_missing_end_marker` - 終端マーカーが記録されていなくてもエラーにしない場合は`true`
+///
+/// # 戻り値
+///
+/// * 第8節の終端マーカーが記録されている場合は`true`
+fn is_section8_next<R: Read + Seek>(
+    reader: &mut BufReader<R>,
+    allow_missing_end_marker: bool,
+) -> Grib2Result<bool> {
+    let mut marker = [0u8; 4];
+    let read_bytes = read_available_bytes(reader, "第8節:終端マーカー", &mut marker)?;
+    reader.seek_relative(-(read_bytes as i64)).map_err(|_| {
+        Grib2Error::ReadError("第8節:終端マーカーの読み戻しに失敗しました。".into())
+    })?;
+    if read_bytes < marker.len() {
+        if allow_missing_end_marker {
+            return Ok(true);
+        }
+        return Err(Grib2Error::ReadError(
+            "第8節:終端マーカーの読み込みに失敗しました。".into(),
+        ));
+    }
+
+    Ok(&marker == b"7777")
+}
+
+/// GRIB2ファイルのメタデータ
+///
+/// `serde`機能を有効にした場合はシリアライズでき、日時はRFC 3339形式の文字列で出力する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Grib2Metadata {
+    /// 資料の参照時刻（世界標準時）
+    #[cfg_attr(feature = "serde", serde(with = "time::serde::rfc3339"))]
+    pub referenced_at: OffsetDateTime,
+    /// 作成中枢の識別
+    pub center: u16,
+    /// 作成副中枢
+    pub sub_center: u16,
+    /// 資料点数
+    pub number_of_points: u32,
+    /// 1e-6度単位の最大緯度（最初の格子点の緯度）
+    pub lat_max: u32,
+    /// 1e-6度単位の最小緯度（最後の格子点の緯度）
+    pub lat_min: u32,
+    /// 1e-6度単位の最小経度（最初の格子点の経度）
+    pub lon_min: u32,
+    /// 1e-6度単位の最大経度（最後の格子点の経度）
+    pub lon_max: u32,
+    /// 1e-6度単位の緯度方向の増分
+    pub lat_inc: u32,
+    /// 1e-6度単位の経度方向の増分
+    pub lon_inc: u32,
+    /// パラメータカテゴリー
+    pub parameter_category: u8,
+    /// パラメータ番号
+    pub parameter_number: u8,
+    /// プロダクトが有効な日時（世界標準時）
+    #[cfg_attr(feature = "serde", serde(with = "time::serde::rfc3339"))]
+    pub valid_at: OffsetDateTime,
+    /// 格子系定義テンプレート番号
+    pub grid_definition_template_number: u16,
+    /// プロダクト定義テンプレート番号
+    pub product_definition_template_number: u16,
+    /// 資料表現テンプレート番号
+    pub data_representation_template_number: u16,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Grib2Record {
-    /// 1e-6度単位の緯度
-    pub lat: u32,
+    /// 南緯を負の値とする1e-6度単位の緯度
+    pub lat: i32,
     /// 1e-6度単位の経度
     pub lon: u32,
     /// 値を表現するバイト列
@@ -136,8 +582,8 @@ where
     level_values: &'a [[u8; 2]],
     /// ランレングス圧縮符号を読み込んだバイト数
     read_bytes: usize,
-    /// 現在の緯度（1e-6度単位）
-    current_lat: u32,
+    /// 現在の緯度（南緯を負の値とする1e-6度単位）
+    current_lat: i32,
     /// 現在の経度（1e-6度単位）
     current_lon: u32,
     /// 現在のレベル値
@@ -178,8 +624,8 @@ where
     /// * ランレングス符号を記録したベクター
     fn retrieve_run_length(&mut self) -> Grib2Result<Vec<u16>> {
         let mut run_length: Vec<u16> = vec![];
-        if self.last_run_length.is_some() {
-            run_length.push(self.last_run_length.unwrap());
+        if let Some(last_run_length) = self.last_run_length.take() {
+            run_length.push(last_run_length);
         }
         while self.read_bytes < self.total_bytes {
             let value = self.read_u8()? as u16;
@@ -203,7 +649,11 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         // 現在値返却回数が0かつ、読み込んだバイト数がランレングス圧縮符号列を記録しているバイト数に達している場合は終了
-        if self.returning_times == 0 && self.total_bytes <= self.read_bytes {
+        // 最後のランレングス圧縮符号が1バイトのセットの場合は、そのセットを展開するまで終了しない
+        if self.returning_times == 0
+            && self.total_bytes <= self.read_bytes
+            && self.last_run_length.is_none()
+        {
             if self.number_of_reads == self.number_of_points {
                 return None;
             } else {
@@ -230,8 +680,9 @@ where
             let (level, times) = expand_run_length(&run_length.unwrap(), self.maxv, self.lngu);
             // 現在のレベル値、物理値及び返却回数を更新
             self.current_level = level;
+            // 欠測を示す値（すべてのビットが1）が記録されたレベル値は、値を持たない
             self.current_value = if 0 < level {
-                Some(self.level_values[level as usize - 1])
+                Some(self.level_values[level as usize - 1]).filter(|v| *v != [0xFF, 0xFF])
             } else {
                 None
             };
@@ -249,7 +700,7 @@ where
         // 格子を移動
         self.current_lon += self.lon_inc;
         if self.lon_max < self.current_lon {
-            self.current_lat -= self.lat_inc;
+            self.current_lat = (self.current_lat as i64 - self.lat_inc as i64) as i32;
             self.current_lon = self.lon_min;
         }
         // 読み込んだ座標数をインクリメント
@@ -414,6 +865,7 @@ where
         let level_values = self.level_values.ok_or_else(|| {
             Grib2Error::RuntimeError("レベル別物理値が設定されていません。".into())
         })?;
+        validate_level_values(level_values.len(), maxv)?;
 
         // ランレングス圧縮符号列の開始位置にファイルポインターを移動
         reader
@@ -440,7 +892,8 @@ where
             lngu: 2u16.pow(nbit as u32) - 1 - maxv,
             level_values,
             read_bytes: 0,
-            current_lat: lat_max,
+            // 緯度は最上位ビットを符号とする符号と絶対値で記録されている
+            current_lat: sign_magnitude_to_i32(lat_max),
             current_lon: lon_min,
             current_level: 0,
             current_value: None,
@@ -450,3 +903,313 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{BufReader, Cursor};
+
+    use super::{Grib2Reader, Grib2RecordIterBuilder};
+    use crate::readers::ReaderOptions;
+    use crate::Grib2Error;
+
+    const FPSW_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807152000_SRF_GPV_Ggis1km_Psw_Fper10min_FH01-06_grib2.bin"
+    );
+
+    #[test]
+    fn record_iter_expands_last_single_byte_set() {
+        // nbit = 4、maxv = 10で、最後のセットが1バイトのレベル値3のみで構成される
+        let run_length = vec![3u8, 9, 12, 6, 4, 15, 2, 1, 0, 13, 12, 2, 3];
+        let run_length_bytes = run_length.len();
+        let level_values = (1..=10u16).map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+        let mut reader = BufReader::new(Cursor::new(run_length));
+        let records = Grib2RecordIterBuilder::new()
+            .reader(&mut reader)
+            .run_length_position(0)
+            .run_length_bytes(run_length_bytes)
+            .number_of_points(21)
+            .lat_max(35_000_000)
+            .lon_min(139_000_000)
+            .lon_max(139_006_000)
+            .lat_inc(1_000)
+            .lon_inc(1_000)
+            .nbit(4)
+            .maxv(10)
+            .level_values(&level_values)
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(21, records.len());
+        assert_eq!(Some(3u16.to_be_bytes()), records[20].value);
+        assert_eq!(Some(2u16.to_be_bytes()), records[19].value);
+    }
+
+    #[test]
+    fn template_summary_of_fpsw_ok() {
+        let reader = Grib2Reader::new(FPSW_FILE).unwrap();
+        let summary = reader.template_summary();
+        // 1時間から6時間までの予想値を、全タンク、第1タンク及び第2タンク別に記録
+        assert_eq!(18, summary.len());
+        assert!(summary.iter().all(|&pair| pair == (0, 200)));
+    }
+
+    #[test]
+    fn metadata_ok() {
+        let reader = Grib2Reader::new(FPSW_FILE).unwrap();
+        let metadata = reader.metadata().unwrap();
+
+        assert_eq!(reader.section1.referenced_at, metadata.referenced_at);
+        assert_eq!(
+            reader.section3.number_of_points().unwrap(),
+            metadata.number_of_points
+        );
+        assert_eq!(
+            reader.section3.lat_of_first_grid_point().unwrap(),
+            metadata.lat_max
+        );
+        assert_eq!(
+            reader.section3.lat_of_last_grid_point().unwrap(),
+            metadata.lat_min
+        );
+        assert_eq!(
+            reader.section3.lon_of_first_grid_point().unwrap(),
+            metadata.lon_min
+        );
+        assert_eq!(
+            reader.section3.lon_of_last_grid_point().unwrap(),
+            metadata.lon_max
+        );
+        assert_eq!(
+            (0, 0, 200),
+            (
+                metadata.grid_definition_template_number,
+                metadata.product_definition_template_number,
+                metadata.data_representation_template_number,
+            )
+        );
+        // 最初のプロダクトは1時間後の予想値
+        assert_eq!(
+            metadata.referenced_at + time::Duration::hours(1),
+            metadata.valid_at
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_metadata_ok() {
+        let metadata = Grib2Reader::new(FPSW_FILE).unwrap().metadata().unwrap();
+        let json = serde_json::to_value(metadata).unwrap();
+
+        assert_eq!("2017-08-07T15:20:00Z", json["referenced_at"]);
+        assert_eq!("2017-08-07T16:20:00Z", json["valid_at"]);
+        assert_eq!(metadata.number_of_points, json["number_of_points"]);
+        assert_eq!(200, json["data_representation_template_number"]);
+    }
+
+    #[test]
+    fn section_layout_ok() {
+        let reader = Grib2Reader::new(FPSW_FILE).unwrap();
+        let layout = reader.section_layout();
+
+        // 第0節、第1節、第3節、18組の第4節から第7節、第8節（第2節は記録されていない）
+        assert_eq!(3 + 18 * 4 + 1, layout.len());
+        assert_eq!(Some(&(0, 0, 16)), layout.first());
+        assert_eq!(8, layout.last().unwrap().0);
+        // 節は隙間なく連続して記録されている
+        let mut expected_start = 0;
+        for &(_, start, bytes) in layout {
+            assert_eq!(expected_start, start);
+            expected_start += bytes;
+        }
+        assert_eq!(reader.section0.total_bytes, expected_start);
+    }
+
+    #[test]
+    fn with_options_skips_leading_junk() {
+        let path = std::env::temp_dir().join(format!("grib2_2_grib2_junk_{}", std::process::id()));
+        let mut bytes = b"junkjunk".to_vec();
+        bytes.extend(fs::read(FPSW_FILE).unwrap());
+        fs::write(&path, bytes).unwrap();
+        let default = Grib2Reader::new(&path);
+        let options = ReaderOptions::new().max_leading_bytes(8);
+        let reader = Grib2Reader::with_options(&path, options);
+        fs::remove_file(&path).unwrap();
+
+        assert!(default.is_err());
+        let mut reader = reader.unwrap();
+        let mut expected = Grib2Reader::new(FPSW_FILE).unwrap();
+        assert_eq!(8, reader.section0.offset);
+        // 節の開始位置は、余分なバイトの分だけずれる
+        assert_eq!(Some(&(0, 8, 16)), reader.section_layout().first());
+        for (actual, expected) in reader
+            .section_layout()
+            .iter()
+            .zip(expected.section_layout())
+        {
+            assert_eq!((expected.0, expected.1 + 8, expected.2), *actual);
+        }
+        let values = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        let expected = expected
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn allow_missing_end_marker_ok() {
+        let path = std::env::temp_dir().join(format!(
+            "grib2_2_grib2_missing_end_marker_{}",
+            std::process::id()
+        ));
+        let mut bytes = fs::read(FPSW_FILE).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        fs::write(&path, bytes).unwrap();
+        let default = Grib2Reader::new(&path);
+        let options = ReaderOptions::new().allow_missing_end_marker(true);
+        let lenient = Grib2Reader::with_options(&path, options);
+        fs::remove_file(path).unwrap();
+
+        assert!(default.is_err());
+        let lenient = lenient.unwrap();
+        assert!(!lenient.section8.has_end_marker());
+        // 第8節を除くすべての節を読み込める
+        assert_eq!(18, lenient.template_summary().len());
+        assert_eq!(7, lenient.section_layout().last().unwrap().0);
+    }
+
+    #[test]
+    fn products_iter_of_fpsw_ok() {
+        let mut reader = Grib2Reader::new(FPSW_FILE).unwrap();
+        let summary = reader.template_summary();
+        let mut products = reader.products_iter();
+        assert_eq!(18, products.remaining());
+
+        let mut templates = vec![];
+        while let Some(product) = products.next_product() {
+            let mut product = product.unwrap();
+            templates.push((
+                product.section4.template_number(),
+                product.section5.template_number(),
+            ));
+            // 各プロダクトのレコードを、北西端の格子点から読み込める
+            let record = product.records.next().unwrap().unwrap();
+            assert!(record.lat > 0 && record.lon > 0);
+        }
+        assert_eq!(summary, templates);
+        assert_eq!(0, products.remaining());
+        assert!(products.next_product().is_none());
+    }
+
+    #[test]
+    fn products_iter_with_product_section3_ok() {
+        let reader = Grib2Reader::new(FPSW_FILE).unwrap();
+        let layout = reader.section_layout().to_vec();
+        let &(_, section3_start, section3_bytes) =
+            layout.iter().find(|(number, _, _)| *number == 3).unwrap();
+        // 2組目の第4節の位置
+        let &(_, position, _) = layout
+            .iter()
+            .filter(|(number, _, _)| *number == 4)
+            .nth(1)
+            .unwrap();
+        // 最初の格子点の緯度を1度南に移動した第3節を、2組目のプロダクトの直前に挿入
+        let mut bytes = fs::read(FPSW_FILE).unwrap();
+        let mut section3 = bytes[section3_start..section3_start + section3_bytes].to_vec();
+        let lat = u32::from_be_bytes(section3[46..50].try_into().unwrap()) - 1_000_000;
+        section3[46..50].copy_from_slice(&lat.to_be_bytes());
+        bytes.splice(position..position, section3);
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+
+        let mut reader = Grib2Reader::from_buf_reader(BufReader::new(Cursor::new(bytes))).unwrap();
+        assert_eq!(18, reader.template_summary().len());
+        assert!(reader.subsequent_products[0].section3.is_some());
+        assert!(reader.subsequent_products[1].section3.is_none());
+        let live_lat = reader.section3.lat_of_first_grid_point().unwrap();
+        let mut products = reader.products_iter();
+        let mut lats = vec![];
+        while let Some(product) = products.next_product() {
+            let mut product = product.unwrap();
+            let record = product.records.next().unwrap().unwrap();
+            assert_eq!(
+                product.section3.lat_of_first_grid_point().unwrap() as i32,
+                record.lat
+            );
+            lats.push(record.lat);
+        }
+        let live_lat = live_lat as i32;
+        assert_eq!(live_lat, lats[0]);
+        assert_eq!(live_lat - 1_000_000, lats[1]);
+        assert_eq!(live_lat, lats[2]);
+    }
+
+    #[test]
+    fn from_buf_reader_ok() {
+        let bytes = fs::read(FPSW_FILE).unwrap();
+        let mut reader = Grib2Reader::from_buf_reader(BufReader::new(Cursor::new(bytes))).unwrap();
+        let expected = Grib2Reader::new(FPSW_FILE).unwrap();
+
+        assert_eq!(expected.template_summary(), reader.template_summary());
+        assert!(reader.record_iter().unwrap().next().unwrap().is_ok());
+    }
+
+    /// 最初に記録されている指定した節のテンプレート番号を書き換えたバイト列を返す。
+    fn replace_template_number(section_number: u8, template_number: u16) -> Vec<u8> {
+        let reader = Grib2Reader::new(FPSW_FILE).unwrap();
+        let &(_, start, _) = reader
+            .section_layout()
+            .iter()
+            .find(|(number, _, _)| *number == section_number)
+            .unwrap();
+        // 第4節はテンプレート直後の座標値の数（2バイト）、第5節は全資料点の数（4バイト）の後に
+        // テンプレート番号が記録されている
+        let offset = start + if section_number == 4 { 7 } else { 9 };
+        let mut bytes = fs::read(FPSW_FILE).unwrap();
+        bytes[offset..offset + 2].copy_from_slice(&template_number.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn skip_unknown_section4_template_ok() {
+        let bytes = replace_template_number(4, 60000);
+        assert!(matches!(
+            Grib2Reader::from_buf_reader(BufReader::new(Cursor::new(bytes.clone()))),
+            Err(Grib2Error::NotImplemented(_))
+        ));
+
+        let options = ReaderOptions::new().skip_unknown_templates(true);
+        let mut reader =
+            Grib2Reader::from_buf_reader_with_options(BufReader::new(Cursor::new(bytes)), options)
+                .unwrap();
+        // 読み飛ばした後も第8節まで読み込めている
+        assert_eq!(18, reader.template_summary().len());
+        assert_eq!((60000, 200), reader.template_summary()[0]);
+        assert_eq!(Some(8), reader.section_layout().last().map(|l| l.0));
+        assert!(reader.section4.parameter().is_err());
+        // 第3節、第5節及び第7節から資料を復号できる
+        let number_of_points = reader.section3.number_of_points().unwrap() as usize;
+        assert_eq!(number_of_points, reader.record_iter().unwrap().count());
+    }
+
+    #[test]
+    fn skip_unknown_section5_template_ok() {
+        let bytes = replace_template_number(5, 60000);
+        let options = ReaderOptions::new().skip_unknown_templates(true);
+        let mut reader =
+            Grib2Reader::from_buf_reader_with_options(BufReader::new(Cursor::new(bytes)), options)
+                .unwrap();
+        assert_eq!((0, 60000), reader.template_summary()[0]);
+        // 第5節を読み飛ばした場合は資料を復号できない
+        assert!(reader.record_iter().is_err());
+    }
+}
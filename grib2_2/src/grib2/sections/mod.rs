@@ -11,9 +11,9 @@ mod section8;
 pub use section0::Section0;
 pub use section1::Section1;
 pub use section2::Section2;
-pub use section3::Section3;
-pub use section4::Section4;
-pub use section5::Section5;
+pub use section3::{Section3, Section3_0, Section3_10, Section3_40};
+pub use section4::{Section4, SkippedSection4};
+pub use section5::{Section5, SkippedSection5};
 pub use section6::Section6;
 pub use section7::Section7;
 pub use section8::Section8;
@@ -1,7 +1,7 @@
 use std::io::{BufReader, Read};
 
-use crate::readers::utils::{read_bytes, read_u64, read_u8};
-use crate::{Grib2Error, Grib2Result};
+use crate::readers::utils::{read_bytes, read_u64, read_u8, skip_to_grib};
+use crate::Grib2Result;
 
 /// 第0節:指示節
 pub struct Section0 {
@@ -15,26 +15,30 @@ pub struct Section0 {
     pub editions: u8,
     /// GRIB報全体のバイト数
     pub total_bytes: usize,
+    /// 読み込みを開始した位置から`GRIB`が記録されている位置までのバイト数
+    pub offset: usize,
 }
 
 impl Section0 {
     /// 第0節:指示節を読み込む。
     ///
+    /// 読み込みを開始した位置から最大`max_offset`バイトまで`GRIB`を探し、見つかった位置から
+    /// 第0節を読み込む。
+    ///
     /// # 引数
     ///
     /// * `reader` - GRIB2ファイルリーダー
+    /// * `max_offset` - `GRIB`を探す最大のバイト数
     ///
     /// # 戻り値
     ///
     /// * 第0節:指示節
-    pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+    pub(crate) fn from_reader<R: Read>(
+        reader: &mut BufReader<R>,
+        max_offset: usize,
+    ) -> Grib2Result<Self> {
         // GRIB: 4バイト
-        let grib = read_bytes(reader, "第0節:GRIB", 4)?;
-        if grib != b"GRIB" {
-            return Err(Grib2Error::ReadError(
-                "第0節:GRIBは、ASCIIバイト表現で`GRIB`を記録していなければなりません。".into(),
-            ));
-        }
+        let offset = skip_to_grib(reader, max_offset)?;
         // 保留: 2バイト
         let reserved = read_bytes(reader, "第0節:保留", 2)?;
         // 資料分野: 1バイト
@@ -45,11 +49,12 @@ impl Section0 {
         let total_bytes = read_u64(reader, "第0節:GRIB報全体の長さ")? as usize;
 
         Ok(Self {
-            grib: grib.try_into().unwrap(),
+            grib: *b"GRIB",
             reserved: reserved.try_into().unwrap(),
             field,
             editions,
             total_bytes,
+            offset,
         })
     }
 }
@@ -2,7 +2,7 @@ use std::io::{BufReader, Read};
 
 use time::OffsetDateTime;
 
-use crate::readers::utils::{read_date_time, read_u16, read_u32, read_u8, validate_u8};
+use crate::readers::utils::{read_bytes, read_date_time, read_u16, read_u32, read_u8, validate_u8};
 use crate::Grib2Result;
 
 /// 第1節:識別節
@@ -60,6 +60,10 @@ impl Section1 {
         let production_status_of_processed_data = read_u8(reader, "第1節:作成ステータス")?;
         // 資料の種類
         let type_of_processed_data = read_u8(reader, "第1節:資料の種類")?;
+        // 標準で定義されている21バイトを超えるバイトを読み飛ばす
+        if 21 < section_bytes {
+            read_bytes(reader, "第1節:予約", section_bytes - 21)?;
+        }
 
         Ok(Self {
             section_bytes,
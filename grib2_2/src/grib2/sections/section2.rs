@@ -1,13 +1,20 @@
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Read, Seek};
 
-use crate::Grib2Result;
+use crate::readers::utils::{peek_section_number, read_bytes, read_u32, validate_u8};
+use crate::{Grib2Error, Grib2Result};
 
 /// 第2節:地域使用節（不使用）
-pub struct Section2;
+pub struct Section2 {
+    /// 節の長さ（バイト数）。第2節が記録されていない場合は0
+    pub section_bytes: usize,
+}
 
 impl Section2 {
     /// GRIB2ファイルから第2節:地域使用節を読み込む。
     ///
+    /// 次に記録されている節の節番号が2の場合は節を読み飛ばし、それ以外の場合はファイル
+    /// ポインターを移動しない。
+    ///
     /// # 引数
     ///
     /// * `reader` - GRIB2ファイルリーダー
@@ -15,7 +22,20 @@ impl Section2 {
     /// # 戻り値
     ///
     /// * 第2節:地域使用節
-    pub(crate) fn from_reader<R: Read>(_reader: &mut BufReader<R>) -> Grib2Result<Self> {
-        Ok(Self)
+    pub(crate) fn from_reader<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+        if peek_section_number(reader)? != 2 {
+            return Ok(Self { section_bytes: 0 });
+        }
+        // 節の長さ: 4バイト
+        let section_bytes = read_u32(reader, "第2節:節の長さ")? as usize;
+        // 節番号: 1バイト
+        validate_u8(reader, 2, "第2節:節番号")?;
+        // 地域使用: 節の長さ - 5バイト
+        let remaining = section_bytes.checked_sub(5).ok_or_else(|| {
+            Grib2Error::ReadError(format!("第2節:節の長さ({section_bytes})が不正です。").into())
+        })?;
+        read_bytes(reader, "第2節:地域使用", remaining)?;
+
+        Ok(Self { section_bytes })
     }
 }
@@ -1,12 +1,16 @@
 use std::io::{BufReader, Read};
 
-use crate::readers::utils::{read_u16, read_u32, read_u8, validate_u8};
+use crate::readers::utils::{read_i32, read_u16, read_u32, read_u8, validate_u8};
 use crate::{Grib2Error, Grib2Result};
 
 /// 第3節:格子系定義節
 pub enum Section3 {
     /// テンプレート3.0
     Template3_0(Section3_0),
+    /// テンプレート3.10
+    Template3_10(Section3_10),
+    /// テンプレート3.40
+    Template3_40(Section3_40),
 }
 
 impl Section3 {
@@ -38,6 +42,8 @@ impl Section3 {
         let grid_definition_template_number = read_u16(reader, "第3節:格子系定義テンプレート番号")?;
         match grid_definition_template_number {
             0 => read_section3_0(reader, section_bytes, section_number, source_of_grid_definition, number_of_data_points, number_of_octets_for_number_of_points, description_of_number_of_points, grid_definition_template_number),
+            10 => read_section3_10(reader, section_bytes, section_number, source_of_grid_definition, number_of_data_points, number_of_octets_for_number_of_points, description_of_number_of_points, grid_definition_template_number),
+            40 => read_section3_40(reader, section_bytes, section_number, source_of_grid_definition, number_of_data_points, number_of_octets_for_number_of_points, description_of_number_of_points, grid_definition_template_number),
             _ => Err(Grib2Error::NotImplemented(format!("第３節の格子系定義テンプレート番号`{grid_definition_template_number}`は未実装です。").into())),
         }
     }
@@ -50,6 +56,8 @@ impl Section3 {
     pub fn number_of_points(&self) -> Grib2Result<u32> {
         match self {
             Self::Template3_0(s) => Ok(s.number_of_points),
+            Self::Template3_10(s) => Ok(s.number_of_points),
+            Self::Template3_40(s) => Ok(s.number_of_points),
             //_ => Err(Grib2Error::RuntimeError(
             //    format!("{self}は資料点数を記録していません。").into(),
             //)),
@@ -64,12 +72,56 @@ impl Section3 {
     pub fn lat_of_first_grid_point(&self) -> Grib2Result<u32> {
         match self {
             Self::Template3_0(s) => Ok(s.lat_of_first_grid_point),
+            Self::Template3_10(s) => u32::try_from(s.lat_of_first_grid_point).map_err(|_| {
+                Grib2Error::RuntimeError(
+                    format!("{self}の最初の格子点の緯度は南緯であるため、返せません。").into(),
+                )
+            }),
+            Self::Template3_40(s) => u32::try_from(s.lat_of_first_grid_point).map_err(|_| {
+                Grib2Error::RuntimeError(
+                    format!("{self}の最初の格子点の緯度は南緯であるため、返せません。").into(),
+                )
+            }),
             //_ => Err(Grib2Error::RuntimeError(
             //    format!("{self}は最初の格子点の緯度を記録していません。").into(),
             //)),
         }
     }
 
+    /// 格子系定義テンプレート番号を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 格子系定義テンプレート番号
+    pub fn template_number(&self) -> u16 {
+        match self {
+            Self::Template3_0(s) => s.grid_definition_template_number,
+            Self::Template3_10(s) => s.grid_definition_template_number,
+            Self::Template3_40(s) => s.grid_definition_template_number,
+        }
+    }
+
+    /// 最後の格子点の緯度（1e-6度単位）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 最後の格子点の緯度（1e-6度単位）
+    pub fn lat_of_last_grid_point(&self) -> Grib2Result<u32> {
+        match self {
+            Self::Template3_0(s) => Ok(s.lat_of_last_grid_point),
+            Self::Template3_10(s) => u32::try_from(s.lat_of_last_grid_point).map_err(|_| {
+                Grib2Error::RuntimeError(
+                    format!("{self}の最後の格子点の緯度は南緯であるため、返せません。").into(),
+                )
+            }),
+            Self::Template3_40(s) => u32::try_from(s.lat_of_last_grid_point).map_err(|_| {
+                Grib2Error::RuntimeError(
+                    format!("{self}の最後の格子点の緯度は南緯であるため、返せません。").into(),
+                )
+            }),
+        }
+    }
+
     /// 最初の格子点の経度（1e-6度単位）を返す。
     ///
     /// # 戻り値
@@ -78,6 +130,8 @@ impl Section3 {
     pub fn lon_of_first_grid_point(&self) -> Grib2Result<u32> {
         match self {
             Self::Template3_0(s) => Ok(s.lon_of_first_grid_point),
+            Self::Template3_10(s) => Ok(s.lon_of_first_grid_point),
+            Self::Template3_40(s) => Ok(s.lon_of_first_grid_point),
             //_ => Err(Grib2Error::RuntimeError(
             //    format!("{self}は最初の格子点の経度を記録していません。").into(),
             //)),
@@ -92,6 +146,8 @@ impl Section3 {
     pub fn lon_of_last_grid_point(&self) -> Grib2Result<u32> {
         match self {
             Self::Template3_0(s) => Ok(s.lon_of_last_grid_point),
+            Self::Template3_10(s) => Ok(s.lon_of_last_grid_point),
+            Self::Template3_40(s) => Ok(s.lon_of_last_grid_point),
             //_ => Err(Grib2Error::RuntimeError(
             //    format!("{self}は最初の格子点の経度を記録していません。").into(),
             //)),
@@ -106,6 +162,10 @@ impl Section3 {
     pub fn i_direction_increment(&self) -> Grib2Result<u32> {
         match self {
             Self::Template3_0(s) => Ok(s.i_direction_increment),
+            Self::Template3_10(_) => Err(Grib2Error::RuntimeError(
+                format!("{self}はi方向の増分を度単位で記録していません。").into(),
+            )),
+            Self::Template3_40(s) => Ok(s.i_direction_increment),
             //_ => Err(Grib2Error::RuntimeError(
             //    format!("{self}はi方向の増分値を記録していません。").into(),
             //)),
@@ -120,6 +180,12 @@ impl Section3 {
     pub fn j_direction_increment(&self) -> Grib2Result<u32> {
         match self {
             Self::Template3_0(s) => Ok(s.j_direction_increment),
+            Self::Template3_10(_) => Err(Grib2Error::RuntimeError(
+                format!("{self}はj方向の増分を度単位で記録していません。").into(),
+            )),
+            Self::Template3_40(_) => Err(Grib2Error::RuntimeError(
+                format!("{self}はj方向の増分値を記録していません。").into(),
+            )),
             //_ => Err(Grib2Error::RuntimeError(
             //    format!("{self}はj方向の増分値を記録していません。").into(),
             //)),
@@ -131,6 +197,8 @@ impl std::fmt::Display for Section3 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
             Section3::Template3_0(_) => write!(f, "第3節テンプレート3.0"),
+            Section3::Template3_10(_) => write!(f, "第3節テンプレート3.10"),
+            Section3::Template3_40(_) => write!(f, "第3節テンプレート3.40"),
         }
     }
 }
@@ -290,3 +358,524 @@ fn read_section3_0<R: Read>(
         scanning_mode,
     }))
 }
+
+/// 第3節:格子系定義節（テンプレート3.10: メルカトル図法）
+///
+/// 格子点の間隔は、緯度及び経度ではなく、地球が投影面と交差する緯度（LaD）における
+/// 距離（1e-3メートル単位）で記録される。
+pub struct Section3_10 {
+    /// 節の長さ
+    pub section_bytes: usize,
+    /// 節番号
+    pub section_number: u8,
+    /// 格子系定義の出典
+    pub source_of_grid_definition: u8,
+    /// 資料点数
+    pub number_of_points: u32,
+    /// 格子点数を定義するリストのオクテット数
+    pub number_of_octets_for_number_of_points: u8,
+    /// 格子点数を定義するリストの説明
+    pub description_of_number_of_points: u8,
+    /// 格子系定義テンプレート番号
+    pub grid_definition_template_number: u16,
+    /// 地球の形状
+    pub shape_of_earth: u8,
+    /// 地球球体の半径の尺度因子
+    pub scale_factor_of_radius_of_spherical_earth: u8,
+    /// 地球球体の尺度付き半径
+    pub scaled_value_of_radius_of_spherical_earth: u32,
+    /// 地球回転楕円体の長軸の尺度因子
+    pub scale_factor_of_earth_major_axis: u8,
+    /// 地球回転楕円体の長軸の尺度付きの長さ
+    pub scaled_value_of_earth_major_axis: u32,
+    /// 地球回転楕円体の短軸の尺度因子
+    pub scale_factor_of_earth_minor_axis: u8,
+    /// 地球回転楕円体の短軸の尺度付きの長さ
+    pub scaled_value_of_earth_minor_axis: u32,
+    /// 緯線に沿った格子点数
+    pub number_of_along_lat_points: u32,
+    /// 経線に沿った格子点数
+    pub number_of_along_lon_points: u32,
+    /// 最初の格子点の緯度（1e-6度単位、南緯は負）
+    pub lat_of_first_grid_point: i32,
+    /// 最初の格子点の経度（1e-6度単位）
+    pub lon_of_first_grid_point: u32,
+    /// 分解能及び成分フラグ
+    pub resolution_and_component_flags: u8,
+    /// 地球が投影面と交差する緯度（1e-6度単位、南緯は負）
+    pub lat_of_intersection: i32,
+    /// 最後の格子点の緯度（1e-6度単位、南緯は負）
+    pub lat_of_last_grid_point: i32,
+    /// 最後の格子点の経度（1e-6度単位）
+    pub lon_of_last_grid_point: u32,
+    /// 走査モード
+    pub scanning_mode: u8,
+    /// i方向と赤道がなす角度（1e-6度単位）
+    pub orientation_of_grid: u32,
+    /// i方向（経度方向）の格子間隔（1e-3メートル単位）
+    pub i_direction_grid_length: u32,
+    /// j方向（緯度方向）の格子間隔（1e-3メートル単位）
+    pub j_direction_grid_length: u32,
+}
+
+impl Section3_10 {
+    /// 地球球体の半径（メートル）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 地球球体の半径（メートル）
+    pub fn earth_radius(&self) -> Grib2Result<f64> {
+        match self.shape_of_earth {
+            0 => Ok(6_367_470.0),
+            1 => Ok(self.scaled_value_of_radius_of_spherical_earth as f64
+                / 10f64.powi(self.scale_factor_of_radius_of_spherical_earth as i32)),
+            6 => Ok(6_371_229.0),
+            8 => Ok(6_371_200.0),
+            shape => Err(Grib2Error::NotImplemented(
+                format!("地球の形状`{shape}`は球体でないため、地球の半径を返せません。").into(),
+            )),
+        }
+    }
+
+    /// 最初の格子点と最後の格子点をメルカトル図法で投影した座標から、格子の範囲を返す。
+    ///
+    /// 座標は、地球が投影面と交差する緯度（LaD）で縮尺が正しくなる投影面上の
+    /// メートル単位の座標である。
+    ///
+    /// # 戻り値
+    ///
+    /// * 格子の範囲（x座標の最小値、y座標の最小値、x座標の最大値、y座標の最大値）
+    pub fn extent(&self) -> Grib2Result<(f64, f64, f64, f64)> {
+        let radius =
+            self.earth_radius()? * (self.lat_of_intersection as f64 * 1e-6).to_radians().cos();
+        let project = |lat: i32, lon: f64| {
+            let lat = (lat as f64 * 1e-6).to_radians();
+            let x = radius * (lon * 1e-6).to_radians();
+            let y = radius * (std::f64::consts::FRAC_PI_4 + lat / 2.0).tan().ln();
+            (x, y)
+        };
+        let lon_first = self.lon_of_first_grid_point as f64;
+        // 最後の格子点が経度180度を越える場合は、最初の格子点より東側になるように補正
+        let mut lon_last = self.lon_of_last_grid_point as f64;
+        if lon_last < lon_first {
+            lon_last += 360_000_000.0;
+        }
+        let (x1, y1) = project(self.lat_of_first_grid_point, lon_first);
+        let (x2, y2) = project(self.lat_of_last_grid_point, lon_last);
+
+        Ok((x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)))
+    }
+}
+
+/// GRIB2ファイルから第3節:格子系定義節（テンプレート3.10）を読み込む。
+///
+/// # 引数
+///
+/// * `reader` - ファイルリーダー
+/// * `section_bytes` - 節の長さ
+/// * `section_number` - 節番号
+/// * `source_of_grid_definition` - 格子系定義の出典
+/// * `number_of_points` - 格子点数
+/// * `number_of_octets_for_number_of_points` - 格子点数を定義するリストのオクテット数
+/// * `description_of_number_of_points` - 格子点数を定義するリストの説明
+/// * `grid_definition_template_number` - 格子系定義テンプレート番号
+///
+/// # 戻り値
+///
+/// * `Section3::Template3_10`
+#[allow(clippy::too_many_arguments)]
+fn read_section3_10<R: Read>(
+    reader: &mut BufReader<R>,
+    section_bytes: usize,
+    section_number: u8,
+    source_of_grid_definition: u8,
+    number_of_points: u32,
+    number_of_octets_for_number_of_points: u8,
+    description_of_number_of_points: u8,
+    grid_definition_template_number: u16,
+) -> Grib2Result<Section3> {
+    // 地球の形状: 1バイト
+    let shape_of_earth = read_u8(reader, "第3節:地球の形状")?;
+    // 地球球体の半径の尺度因子: 1バイト
+    let scale_factor_of_radius_of_spherical_earth =
+        read_u8(reader, "第3節:地球球体の半径の尺度因子")?;
+    // 地球球体の尺度付き半径: 4バイト
+    let scaled_value_of_radius_of_spherical_earth =
+        read_u32(reader, "第3節:地球球体の尺度付き半径")?;
+    // 地球回転楕円体の長軸の尺度因子: 1バイト
+    let scale_factor_of_earth_major_axis = read_u8(reader, "第3節:地球回転楕円体の長軸の尺度因子")?;
+    // 地球回転楕円体の長軸の尺度付きの長さ: 4バイト
+    let scaled_value_of_earth_major_axis =
+        read_u32(reader, "第3節:地球回転楕円体の長軸の尺度付きの長さ")?;
+    // 地球回転楕円体の短軸の尺度因子: 1バイト
+    let scale_factor_of_earth_minor_axis = read_u8(reader, "第3節:地球回転楕円体の短軸の尺度因子")?;
+    // 地球回転楕円体の短軸の尺度付きの長さ: 4バイト
+    let scaled_value_of_earth_minor_axis =
+        read_u32(reader, "第3節:地球回転楕円体の短軸の尺度付きの長さ")?;
+    // 緯線に沿った格子点数: 4バイト
+    let number_of_along_lat_points = read_u32(reader, "第3節:緯線に沿った格子点数")?;
+    // 経線に沿った格子点数: 4バイト
+    let number_of_along_lon_points = read_u32(reader, "第3節:経線に沿った格子点数")?;
+    // 最初の格子点の緯度（1e-6度単位）: 4バイト
+    let lat_of_first_grid_point = read_i32(reader, "第3節:最初の格子点の緯度")?;
+    // 最初の格子点の経度（1e-6度単位）: 4バイト
+    let lon_of_first_grid_point = read_u32(reader, "第3節:最初の格子点の経度")?;
+    // 分解能及び成分フラグ: 1バイト
+    let resolution_and_component_flags = read_u8(reader, "第3節:分解能及び成分フラグ")?;
+    // 地球が投影面と交差する緯度（1e-6度単位）: 4バイト
+    let lat_of_intersection = read_i32(reader, "第3節:地球が投影面と交差する緯度")?;
+    // 最後の格子点の緯度（1e-6度単位）: 4バイト
+    let lat_of_last_grid_point = read_i32(reader, "第3節:最後の格子点の緯度")?;
+    // 最後の格子点の経度（1e-6度単位）: 4バイト
+    let lon_of_last_grid_point = read_u32(reader, "第3節:最後の格子点の経度")?;
+    // 走査モード: 1バイト
+    let scanning_mode = read_u8(reader, "第3節:走査モード")?;
+    // i方向と赤道がなす角度（1e-6度単位）: 4バイト
+    let orientation_of_grid = read_u32(reader, "第3節:i方向と赤道がなす角度")?;
+    // i方向（経度方向）の格子間隔（1e-3メートル単位）: 4バイト
+    let i_direction_grid_length = read_u32(reader, "第3節:i方向の格子間隔")?;
+    // j方向（緯度方向）の格子間隔（1e-3メートル単位）: 4バイト
+    let j_direction_grid_length = read_u32(reader, "第3節:j方向の格子間隔")?;
+
+    Ok(Section3::Template3_10(Section3_10 {
+        section_bytes,
+        section_number,
+        source_of_grid_definition,
+        number_of_points,
+        number_of_octets_for_number_of_points,
+        description_of_number_of_points,
+        grid_definition_template_number,
+        shape_of_earth,
+        scale_factor_of_radius_of_spherical_earth,
+        scaled_value_of_radius_of_spherical_earth,
+        scale_factor_of_earth_major_axis,
+        scaled_value_of_earth_major_axis,
+        scale_factor_of_earth_minor_axis,
+        scaled_value_of_earth_minor_axis,
+        number_of_along_lat_points,
+        number_of_along_lon_points,
+        lat_of_first_grid_point,
+        lon_of_first_grid_point,
+        resolution_and_component_flags,
+        lat_of_intersection,
+        lat_of_last_grid_point,
+        lon_of_last_grid_point,
+        scanning_mode,
+        orientation_of_grid,
+        i_direction_grid_length,
+        j_direction_grid_length,
+    }))
+}
+
+/// 第3節:格子系定義節（テンプレート3.40: ガウス緯度経度格子）
+///
+/// テンプレート3.0の「j方向（緯度方向）の増分」の代わりに「極と赤道間の緯線の数」を記録する。
+/// ガウス格子の緯線は等間隔でないため、このクレートは格子点の値を展開できず、格子系の定義と
+/// 資料点数のみを提供する。
+pub struct Section3_40 {
+    /// 節の長さ
+    pub section_bytes: usize,
+    /// 節番号
+    pub section_number: u8,
+    /// 格子系定義の出典
+    pub source_of_grid_definition: u8,
+    /// 資料点数
+    pub number_of_points: u32,
+    /// 格子点数を定義するリストのオクテット数
+    pub number_of_octets_for_number_of_points: u8,
+    /// 格子点数を定義するリストの説明
+    pub description_of_number_of_points: u8,
+    /// 格子系定義テンプレート番号
+    pub grid_definition_template_number: u16,
+    /// 地球の形状
+    pub shape_of_earth: u8,
+    /// 地球球体の半径の尺度因子
+    pub scale_factor_of_radius_of_spherical_earth: u8,
+    /// 地球球体の尺度付き半径
+    pub scaled_value_of_radius_of_spherical_earth: u32,
+    /// 地球回転楕円体の長軸の尺度因子
+    pub scale_factor_of_earth_major_axis: u8,
+    /// 地球回転楕円体の長軸の尺度付きの長さ
+    pub scaled_value_of_earth_major_axis: u32,
+    /// 地球回転楕円体の短軸の尺度因子
+    pub scale_factor_of_earth_minor_axis: u8,
+    /// 地球回転楕円体の短軸の尺度付きの長さ
+    pub scaled_value_of_earth_minor_axis: u32,
+    /// 緯線に沿った格子点数
+    pub number_of_along_lat_points: u32,
+    /// 経線に沿った格子点数
+    pub number_of_along_lon_points: u32,
+    /// 原作成領域の基本角
+    pub basic_angle_of_initial_product_domain: u32,
+    /// 端点の経度及び緯度並びに方向増分の定義に使われる基本角の細分
+    pub subdivisions_of_basic_angle: u32,
+    /// 最初の格子点の緯度（1e-6度単位、南緯は負）
+    pub lat_of_first_grid_point: i32,
+    /// 最初の格子点の経度（1e-6度単位）
+    pub lon_of_first_grid_point: u32,
+    /// 分解能及び成分フラグ
+    pub resolution_and_component_flags: u8,
+    /// 最後の格子点の緯度（1e-6度単位、南緯は負）
+    pub lat_of_last_grid_point: i32,
+    /// 最後の格子点の経度（1e-6度単位）
+    pub lon_of_last_grid_point: u32,
+    /// i方向（経度方向）の増分（1e-6度単位）
+    pub i_direction_increment: u32,
+    /// 極と赤道間の緯線の数
+    pub number_of_parallels_between_pole_and_equator: u32,
+    /// 走査モード
+    pub scanning_mode: u8,
+}
+
+/// GRIB2ファイルから第3節:格子系定義節（テンプレート3.40）を読み込む。
+///
+/// # 引数
+///
+/// * `reader` - ファイルリーダー
+/// * `section_bytes` - 節の長さ
+/// * `section_number` - 節番号
+/// * `source_of_grid_definition` - 格子系定義の出典
+/// * `number_of_points` - 格子点数
+/// * `number_of_octets_for_number_of_points` - 格子点数を定義するリストのオクテット数
+/// * `description_of_number_of_points` - 格子点数を定義するリストの説明
+/// * `grid_definition_template_number` - 格子系定義テンプレート番号
+///
+/// # 戻り値
+///
+/// * `Section3::Template3_40`
+#[allow(clippy::too_many_arguments)]
+fn read_section3_40<R: Read>(
+    reader: &mut BufReader<R>,
+    section_bytes: usize,
+    section_number: u8,
+    source_of_grid_definition: u8,
+    number_of_points: u32,
+    number_of_octets_for_number_of_points: u8,
+    description_of_number_of_points: u8,
+    grid_definition_template_number: u16,
+) -> Grib2Result<Section3> {
+    // 地球の形状: 1バイト
+    let shape_of_earth = read_u8(reader, "第3節:地球の形状")?;
+    // 地球球体の半径の尺度因子: 1バイト
+    let scale_factor_of_radius_of_spherical_earth =
+        read_u8(reader, "第3節:地球球体の半径の尺度因子")?;
+    // 地球球体の尺度付き半径: 4バイト
+    let scaled_value_of_radius_of_spherical_earth =
+        read_u32(reader, "第3節:地球球体の尺度付き半径")?;
+    // 地球回転楕円体の長軸の尺度因子: 1バイト
+    let scale_factor_of_earth_major_axis = read_u8(reader, "第3節:地球回転楕円体の長軸の尺度因子")?;
+    // 地球回転楕円体の長軸の尺度付きの長さ: 4バイト
+    let scaled_value_of_earth_major_axis =
+        read_u32(reader, "第3節:地球回転楕円体の長軸の尺度付きの長さ")?;
+    // 地球回転楕円体の短軸の尺度因子: 1バイト
+    let scale_factor_of_earth_minor_axis = read_u8(reader, "第3節:地球回転楕円体の短軸の尺度因子")?;
+    // 地球回転楕円体の短軸の尺度付きの長さ: 4バイト
+    let scaled_value_of_earth_minor_axis =
+        read_u32(reader, "第3節:地球回転楕円体の短軸の尺度付きの長さ")?;
+    // 緯線に沿った格子点数: 4バイト
+    let number_of_along_lat_points = read_u32(reader, "第3節:緯線に沿った格子点数")?;
+    // 経線に沿った格子点数: 4バイト
+    let number_of_along_lon_points = read_u32(reader, "第3節:経線に沿った格子点数")?;
+    // 原作成領域の基本角: 4バイト
+    let basic_angle_of_initial_product_domain = read_u32(reader, "第3節:原作成領域の基本角")?;
+    // 端点の経度及び緯度並びに方向増分の定義に使われる基本角の細分: 4バイト
+    let subdivisions_of_basic_angle =
+        read_u32(reader, "第3節:端点の経度及び緯度並びに方向増分の定義")?;
+    // 最初の格子点の緯度（1e-6度単位）: 4バイト
+    let lat_of_first_grid_point = read_i32(reader, "第3節:最初の格子点の緯度")?;
+    // 最初の格子点の経度（1e-6度単位）: 4バイト
+    let lon_of_first_grid_point = read_u32(reader, "第3節:最初の格子点の経度")?;
+    // 分解能及び成分フラグ: 1バイト
+    let resolution_and_component_flags = read_u8(reader, "第3節:分解能及び成分フラグ")?;
+    // 最後の格子点の緯度（1e-6度単位）: 4バイト
+    let lat_of_last_grid_point = read_i32(reader, "第3節:最後の格子点の緯度")?;
+    // 最後の格子点の経度（1e-6度単位）: 4バイト
+    let lon_of_last_grid_point = read_u32(reader, "第3節:最後の格子点の経度")?;
+    // i方向（経度方向）の増分（1e-6度単位）: 4バイト
+    let i_direction_increment = read_u32(reader, "第3節:i方向の増分")?;
+    // 極と赤道間の緯線の数: 4バイト
+    let number_of_parallels_between_pole_and_equator =
+        read_u32(reader, "第3節:極と赤道間の緯線の数")?;
+    // 走査モード: 1バイト
+    let scanning_mode = read_u8(reader, "第3節:走査モード")?;
+
+    Ok(Section3::Template3_40(Section3_40 {
+        section_bytes,
+        section_number,
+        source_of_grid_definition,
+        number_of_points,
+        number_of_octets_for_number_of_points,
+        description_of_number_of_points,
+        grid_definition_template_number,
+        shape_of_earth,
+        scale_factor_of_radius_of_spherical_earth,
+        scaled_value_of_radius_of_spherical_earth,
+        scale_factor_of_earth_major_axis,
+        scaled_value_of_earth_major_axis,
+        scale_factor_of_earth_minor_axis,
+        scaled_value_of_earth_minor_axis,
+        number_of_along_lat_points,
+        number_of_along_lon_points,
+        basic_angle_of_initial_product_domain,
+        subdivisions_of_basic_angle,
+        lat_of_first_grid_point,
+        lon_of_first_grid_point,
+        resolution_and_component_flags,
+        lat_of_last_grid_point,
+        lon_of_last_grid_point,
+        i_direction_increment,
+        number_of_parallels_between_pole_and_equator,
+        scanning_mode,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::Section3;
+
+    /// テンプレート3.10の第3節を記録したバイト列を返す。
+    ///
+    /// 北緯20度で投影面と交差するメルカトル図法で、北緯30度東経100度から北緯10度東経120度
+    /// までの格子（101 x 121）を定義する。
+    fn section3_10_bytes() -> Vec<u8> {
+        let mut bytes = vec![];
+        // 節の長さ、節番号、格子系定義の出典
+        bytes.extend(72u32.to_be_bytes());
+        bytes.extend([3, 0]);
+        // 資料点数、格子点数を定義するリストのオクテット数及び説明
+        bytes.extend((101u32 * 121).to_be_bytes());
+        bytes.extend([0, 0]);
+        // 格子系定義テンプレート番号
+        bytes.extend(10u16.to_be_bytes());
+        // 地球の形状、地球球体の半径
+        bytes.extend([6, 0]);
+        bytes.extend(0u32.to_be_bytes());
+        // 地球回転楕円体の長軸及び短軸
+        bytes.push(0);
+        bytes.extend(0u32.to_be_bytes());
+        bytes.push(0);
+        bytes.extend(0u32.to_be_bytes());
+        // 緯線及び経線に沿った格子点数
+        bytes.extend(101u32.to_be_bytes());
+        bytes.extend(121u32.to_be_bytes());
+        // 最初の格子点の緯度及び経度
+        bytes.extend(30_000_000u32.to_be_bytes());
+        bytes.extend(100_000_000u32.to_be_bytes());
+        // 分解能及び成分フラグ
+        bytes.push(48);
+        // 地球が投影面と交差する緯度
+        bytes.extend(20_000_000u32.to_be_bytes());
+        // 最後の格子点の緯度及び経度
+        bytes.extend(10_000_000u32.to_be_bytes());
+        bytes.extend(120_000_000u32.to_be_bytes());
+        // 走査モード、i方向と赤道がなす角度
+        bytes.push(0);
+        bytes.extend(0u32.to_be_bytes());
+        // i方向及びj方向の格子間隔
+        bytes.extend(20_898_561u32.to_be_bytes());
+        bytes.extend(18_653_504u32.to_be_bytes());
+
+        bytes
+    }
+
+    #[test]
+    fn read_section3_10_ok() {
+        let bytes = section3_10_bytes();
+        assert_eq!(72, bytes.len());
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3::from_reader(&mut reader).unwrap();
+
+        assert_eq!(101 * 121, section3.number_of_points().unwrap());
+        assert_eq!(30_000_000, section3.lat_of_first_grid_point().unwrap());
+        assert!(section3.i_direction_increment().is_err());
+        match section3 {
+            Section3::Template3_10(s) => {
+                assert_eq!(10, s.grid_definition_template_number);
+                assert_eq!(101, s.number_of_along_lat_points);
+                assert_eq!(121, s.number_of_along_lon_points);
+                assert_eq!(20_000_000, s.lat_of_intersection);
+                assert_eq!(10_000_000, s.lat_of_last_grid_point);
+                assert_eq!(120_000_000, s.lon_of_last_grid_point);
+                assert_eq!(0, s.orientation_of_grid);
+                assert_eq!(20_898_561, s.i_direction_grid_length);
+                assert_eq!(18_653_504, s.j_direction_grid_length);
+
+                let (min_x, min_y, max_x, max_y) = s.extent().unwrap();
+                assert!((min_x - 10_449_280.780).abs() < 1e-3);
+                assert!((min_y - 1_050_273.894).abs() < 1e-3);
+                assert!((max_x - 12_539_136.936).abs() < 1e-3);
+                assert!((max_y - 3_288_694.170).abs() < 1e-3);
+            }
+            _ => panic!("テンプレート3.10として読み込まれませんでした。"),
+        }
+    }
+
+    /// テンプレート3.40の第3節を記録したバイト列を返す。
+    ///
+    /// T62相当のガウス格子（192 x 94）を定義する。
+    fn section3_40_bytes() -> Vec<u8> {
+        let mut bytes = vec![];
+        // 節の長さ、節番号、格子系定義の出典
+        bytes.extend(72u32.to_be_bytes());
+        bytes.extend([3, 0]);
+        // 資料点数、格子点数を定義するリストのオクテット数及び説明
+        bytes.extend((192u32 * 94).to_be_bytes());
+        bytes.extend([0, 0]);
+        // 格子系定義テンプレート番号
+        bytes.extend(40u16.to_be_bytes());
+        // 地球の形状、地球球体の半径
+        bytes.extend([6, 0]);
+        bytes.extend(0u32.to_be_bytes());
+        // 地球回転楕円体の長軸及び短軸
+        bytes.push(0);
+        bytes.extend(0u32.to_be_bytes());
+        bytes.push(0);
+        bytes.extend(0u32.to_be_bytes());
+        // 緯線及び経線に沿った格子点数
+        bytes.extend(192u32.to_be_bytes());
+        bytes.extend(94u32.to_be_bytes());
+        // 原作成領域の基本角及びその細分
+        bytes.extend(0u32.to_be_bytes());
+        bytes.extend(u32::MAX.to_be_bytes());
+        // 最初の格子点の緯度及び経度
+        bytes.extend(88_542_000u32.to_be_bytes());
+        bytes.extend(0u32.to_be_bytes());
+        // 分解能及び成分フラグ
+        bytes.push(48);
+        // 最後の格子点の緯度（南緯は最上位ビットを立てる）及び経度
+        bytes.extend((88_542_000u32 | 0x8000_0000).to_be_bytes());
+        bytes.extend(358_125_000u32.to_be_bytes());
+        // i方向の増分、極と赤道間の緯線の数、走査モード
+        bytes.extend(1_875_000u32.to_be_bytes());
+        bytes.extend(47u32.to_be_bytes());
+        bytes.push(0);
+
+        bytes
+    }
+
+    #[test]
+    fn read_section3_40_ok() {
+        let bytes = section3_40_bytes();
+        assert_eq!(72, bytes.len());
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3::from_reader(&mut reader).unwrap();
+
+        assert_eq!(192 * 94, section3.number_of_points().unwrap());
+        assert!(section3.lat_of_first_grid_point().is_ok());
+        assert!(section3.j_direction_increment().is_err());
+        match section3 {
+            Section3::Template3_40(s) => {
+                assert_eq!(40, s.grid_definition_template_number);
+                assert_eq!(192, s.number_of_along_lat_points);
+                assert_eq!(94, s.number_of_along_lon_points);
+                assert_eq!(88_542_000, s.lat_of_first_grid_point);
+                assert_eq!(-88_542_000, s.lat_of_last_grid_point);
+                assert_eq!(1_875_000, s.i_direction_increment);
+                assert_eq!(47, s.number_of_parallels_between_pole_and_equator);
+            }
+            _ => panic!("テンプレート3.40として読み込まれませんでした。"),
+        }
+    }
+}
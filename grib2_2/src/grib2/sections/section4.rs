@@ -2,8 +2,9 @@ use std::io::{BufReader, Read};
 
 use time::OffsetDateTime;
 
+use crate::readers::sections::forecast_duration;
 use crate::readers::utils::{
-    read_date_time, read_i32, read_u16, read_u32, read_u64, read_u8, validate_u8,
+    read_bytes, read_date_time, read_i32, read_u16, read_u32, read_u64, read_u8, validate_u8,
 };
 use crate::{Grib2Error, Grib2Result};
 
@@ -13,19 +14,28 @@ pub enum Section4 {
     Template4_0(Section4_0),
     /// テンプレート4.50008
     Template4_50008(Section4_50008),
+    /// 未実装のテンプレートのため、節の長さに従って読み飛ばした第4節
+    Skipped(SkippedSection4),
 }
 
 impl Section4 {
     /// GRIB2ファイルから第4節:プロダクト定義節を読み込む。
     ///
+    /// `skip_unknown_template`が`true`の場合、未実装のテンプレートの第4節をエラーにせず、
+    /// 節の長さに従って読み飛ばす。
+    ///
     /// # 引数
     ///
     /// * `reader` - GRIB2ファイルリーダー
+    /// * `skip_unknown_template` - 未実装のテンプレートの節を読み飛ばす場合は`true`
     ///
     /// # 戻り値
     ///
     /// * 第4節:プロダクト定義節
-    pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+    pub(crate) fn from_reader<R: Read>(
+        reader: &mut BufReader<R>,
+        skip_unknown_template: bool,
+    ) -> Grib2Result<Self> {
         // 節の長さ: 4バイト
         let section_bytes = read_u32(reader, "第4節:節の長さ")? as usize;
         // 節番号: 1バイト
@@ -39,9 +49,111 @@ impl Section4 {
         match product_definition_template_number {
             0 => read_section4_0(reader, section_bytes, section_number, number_of_after_template_points, product_definition_template_number),
             50008 => read_section4_50008(reader, section_bytes, section_number, number_of_after_template_points, product_definition_template_number),
+            _ if skip_unknown_template => skip_section4(reader, section_bytes, section_number, product_definition_template_number),
             _ => Err(Grib2Error::NotImplemented(format!("第4節のプロダクト定義テンプレート番号`{product_definition_template_number}`は未実装です。").into())),
         }
     }
+
+    /// プロダクト定義テンプレート番号を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * プロダクト定義テンプレート番号
+    pub fn template_number(&self) -> u16 {
+        match self {
+            Self::Template4_0(s) => s.product_definition_template_number,
+            Self::Template4_50008(s) => s.product_definition_template_number,
+            Self::Skipped(s) => s.product_definition_template_number,
+        }
+    }
+
+    /// パラメータカテゴリーとパラメータ番号の組を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * パラメータカテゴリーとパラメータ番号の組
+    pub fn parameter(&self) -> Grib2Result<(u8, u8)> {
+        match self {
+            Self::Template4_0(s) => Ok((s.parameter_category, s.parameter_number)),
+            Self::Template4_50008(s) => Ok((s.parameter_category, s.parameter_number)),
+            Self::Skipped(s) => Err(s.not_read_error("パラメータ")),
+        }
+    }
+
+    /// プロダクトが有効な日時を返す。
+    ///
+    /// テンプレート4.0は参照時刻に予報時間を加えた日時を、テンプレート4.50008は全時間間隔の
+    /// 終了時を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `referenced_at` - 第1節に記録されている資料の参照時刻
+    ///
+    /// # 戻り値
+    ///
+    /// * プロダクトが有効な日時
+    pub fn valid_at(&self, referenced_at: OffsetDateTime) -> Grib2Result<OffsetDateTime> {
+        match self {
+            Self::Template4_0(s) => Ok(referenced_at
+                + forecast_duration(s.indicator_of_unit_of_time_range, s.forecast_time)?),
+            Self::Template4_50008(s) => Ok(s.end_of_all_time_intervals),
+            Self::Skipped(s) => Err(s.not_read_error("プロダクトが有効な日時")),
+        }
+    }
+}
+
+/// 未実装のテンプレートのため、節の長さに従って読み飛ばした第4節
+pub struct SkippedSection4 {
+    /// 節の長さ（バイト数）
+    pub section_bytes: usize,
+    /// 節番号
+    pub section_number: u8,
+    /// プロダクト定義テンプレート番号
+    pub product_definition_template_number: u16,
+}
+
+impl SkippedSection4 {
+    fn not_read_error(&self, name: &str) -> Grib2Error {
+        Grib2Error::RuntimeError(
+            format!(
+                "第4節のプロダクト定義テンプレート番号`{}`は読み飛ばしたため、{name}を取得できません。",
+                self.product_definition_template_number
+            )
+            .into(),
+        )
+    }
+}
+
+/// 未実装のテンプレートの第4節を、節の長さに従って読み飛ばす。
+///
+/// # 引数
+///
+/// * `reader` - ファイルリーダー
+/// * `section_bytes` - 節の長さ
+/// * `section_number` - 節番号
+/// * `product_definition_template_number` - プロダクト定義テンプレート番号
+///
+/// # 戻り値
+///
+/// * 第4節:プロダクト定義節
+fn skip_section4<R: Read>(
+    reader: &mut BufReader<R>,
+    section_bytes: usize,
+    section_number: u8,
+    product_definition_template_number: u16,
+) -> Grib2Result<Section4> {
+    // 節の長さ(4バイト)、節番号(1バイト)、テンプレート直後の座標値の数(2バイト)及び
+    // プロダクト定義テンプレート番号(2バイト)は読み込み済み
+    let remaining = section_bytes.checked_sub(9).ok_or_else(|| {
+        Grib2Error::ReadError(format!("第4節:節の長さ({section_bytes})が不正です。").into())
+    })?;
+    read_bytes(reader, "第4節:未実装のテンプレート", remaining)?;
+
+    Ok(Section4::Skipped(SkippedSection4 {
+        section_bytes,
+        section_number,
+        product_definition_template_number,
+    }))
 }
 
 pub struct Section4_0 {
@@ -1,25 +1,34 @@
 use std::io::{BufReader, Read, Seek};
 
-use crate::readers::utils::{read_u16, read_u32, read_u8, validate_u8};
+use crate::readers::utils::{read_bytes, read_i8, read_u16, read_u32, read_u8, validate_u8};
 use crate::{Grib2Error, Grib2Result};
 
 /// 第5節:資料表現節
 pub enum Section5 {
     /// テンプレート5.200
     Template5_200(Section5_200),
+    /// 未実装のテンプレートのため、節の長さに従って読み飛ばした第5節
+    Skipped(SkippedSection5),
 }
 
 impl Section5 {
-    // GRIB2ファイルから第5節:資料表現節を読み込む。
+    /// GRIB2ファイルから第5節:資料表現節を読み込む。
+    ///
+    /// `skip_unknown_template`が`true`の場合、未実装のテンプレートの第5節をエラーにせず、
+    /// 節の長さに従って読み飛ばす。
     ///
     /// # 引数
     ///
     /// * `reader` - GRIB2ファイルリーダー
+    /// * `skip_unknown_template` - 未実装のテンプレートの節を読み飛ばす場合は`true`
     ///
     /// # 戻り値
     ///
     /// * 第5節:資料表現節
-    pub(crate) fn from_reader<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+    pub(crate) fn from_reader<R: Read + Seek>(
+        reader: &mut BufReader<R>,
+        skip_unknown_template: bool,
+    ) -> Grib2Result<Self> {
         // 節の長さ: 4バイト
         let section_bytes = read_u32(reader, "第5節:節の長さ")? as usize;
         // 節番号: 1バイト
@@ -31,10 +40,38 @@ impl Section5 {
             read_u16(reader, "第5節:資料表現テンプレート番号")?;
         match data_representation_template_number {
             200 => read_section5_200(reader, section_bytes, section_number, number_of_points, data_representation_template_number),
+            _ if skip_unknown_template => skip_section5(reader, section_bytes, section_number, number_of_points, data_representation_template_number),
             _ => Err(Grib2Error::NotImplemented(format!("第5節の資料表現テンプレート番号`{data_representation_template_number}`は未実装です。").into())),
         }
     }
 
+    /// 資料表現テンプレート番号を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料表現テンプレート番号
+    pub fn template_number(&self) -> u16 {
+        match self {
+            Self::Template5_200(s) => s.data_representation_template_number,
+            Self::Skipped(s) => s.data_representation_template_number,
+        }
+    }
+
+    /// 参照値を返す。
+    ///
+    /// 参照値は、単純圧縮（テンプレート5.0）や複合圧縮（テンプレート5.2及び5.3）などの
+    /// テンプレートで記録される。ランレングス圧縮（テンプレート5.200）は参照値を記録しない
+    /// ため`None`を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 参照値
+    pub fn reference_value(&self) -> Option<f32> {
+        match self {
+            Self::Template5_200(_) | Self::Skipped(_) => None,
+        }
+    }
+
     /// 1データのビット数を返す。
     ///
     /// # 戻り値
@@ -43,9 +80,9 @@ impl Section5 {
     pub fn bit_per_value(&self) -> Grib2Result<u8> {
         match self {
             Self::Template5_200(s) => Ok(s.bits_per_value),
-            // _ => Err(Grib2Error::RuntimeError(
-            //     format!("{self}は1データのビット数を記録していません。").into(),
-            // )),
+            _ => Err(Grib2Error::RuntimeError(
+                format!("{self}は1データのビット数を記録していません。").into(),
+            )),
         }
     }
 
@@ -57,9 +94,9 @@ impl Section5 {
     pub fn max_level_value(&self) -> Grib2Result<u16> {
         match self {
             Self::Template5_200(s) => Ok(s.max_level_value),
-            // _ => Err(Grib2Error::RuntimeError(
-            //     format!("{self}は今回の圧縮に用いたレベルの最大値を記録していません。").into(),
-            // )),
+            _ => Err(Grib2Error::RuntimeError(
+                format!("{self}は今回の圧縮に用いたレベルの最大値を記録していません。").into(),
+            )),
         }
     }
 
@@ -71,9 +108,9 @@ impl Section5 {
     pub fn level_values(&self) -> Grib2Result<&[[u8; 2]]> {
         match self {
             Self::Template5_200(s) => Ok(&s.level_values),
-            // _ => Err(Grib2Error::RuntimeError(
-            //     format!("{self}は今回の圧縮に用いたレベルの最大値を記録していません。").into(),
-            // )),
+            _ => Err(Grib2Error::RuntimeError(
+                format!("{self}はレベル別物理値を記録していません。").into(),
+            )),
         }
     }
 }
@@ -82,9 +119,61 @@ impl std::fmt::Display for Section5 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Template5_200(_) => write!(f, "第5節テンプレート5.200"),
+            Self::Skipped(s) => write!(
+                f,
+                "読み飛ばした第5節テンプレート5.{}",
+                s.data_representation_template_number
+            ),
         }
     }
 }
+/// 未実装のテンプレートのため、節の長さに従って読み飛ばした第5節
+pub struct SkippedSection5 {
+    /// 節の長さ（バイト数）
+    pub section_bytes: usize,
+    /// 節番号
+    pub section_number: u8,
+    /// 全資料点の数
+    pub number_of_points: u32,
+    /// 資料表現テンプレート番号
+    pub data_representation_template_number: u16,
+}
+
+/// 未実装のテンプレートの第5節を、節の長さに従って読み飛ばす。
+///
+/// # 引数
+///
+/// * `reader` - ファイルリーダー
+/// * `section_bytes` - 節の長さ
+/// * `section_number` - 節番号
+/// * `number_of_points` - 全資料点の数
+/// * `data_representation_template_number` - 資料表現テンプレート番号
+///
+/// # 戻り値
+///
+/// * 第5節:資料表現節
+fn skip_section5<R: Read>(
+    reader: &mut BufReader<R>,
+    section_bytes: usize,
+    section_number: u8,
+    number_of_points: u32,
+    data_representation_template_number: u16,
+) -> Grib2Result<Section5> {
+    // 節の長さ(4バイト)、節番号(1バイト)、全資料点の数(4バイト)及び資料表現テンプレート
+    // 番号(2バイト)は読み込み済み
+    let remaining = section_bytes.checked_sub(11).ok_or_else(|| {
+        Grib2Error::ReadError(format!("第5節:節の長さ({section_bytes})が不正です。").into())
+    })?;
+    read_bytes(reader, "第5節:未実装のテンプレート", remaining)?;
+
+    Ok(Section5::Skipped(SkippedSection5 {
+        section_bytes,
+        section_number,
+        number_of_points,
+        data_representation_template_number,
+    }))
+}
+
 pub struct Section5_200 {
     /// 節の長さ（バイト数）
     pub section_bytes: usize,
@@ -101,7 +190,7 @@ pub struct Section5_200 {
     /// データの取り得るレベルの最大値
     pub number_of_level_values: u16,
     /// データ代表値の尺度因子
-    pub decimal_scale_factor: u8,
+    pub decimal_scale_factor: i8,
     /// レベル値と物理値(mm/h)の対応を格納するコレクション
     pub level_values: Vec<[u8; 2]>,
 }
@@ -120,7 +209,7 @@ fn read_section5_200<R: Read + Seek>(
     // データの取り得るレベルの最大値: 2バイト
     let number_of_level_values = read_u16(reader, "第5節:レベルの最大値")?;
     // データ代表値の尺度因子: 1バイト
-    let decimal_scale_factor = read_u8(reader, "第5節:データ代表値の尺度因子")?;
+    let decimal_scale_factor = read_i8(reader, "第5節:データ代表値の尺度因子")?;
     // テンプレート5.200のバイト数を計算
     // 4byte: 節の長さ
     // 1byte: 節番号
@@ -157,3 +246,20 @@ fn read_section5_200<R: Read + Seek>(
         level_values,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::grib2::reader::Grib2Reader;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    #[test]
+    fn reference_value_of_template5_200_is_none() {
+        let reader = Grib2Reader::new(PRR_FILE).unwrap();
+        assert_eq!(200, reader.section5.template_number());
+        assert_eq!(None, reader.section5.reference_value());
+    }
+}
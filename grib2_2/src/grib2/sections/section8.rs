@@ -1,6 +1,6 @@
 use std::io::{BufReader, Read};
 
-use crate::readers::utils::read_bytes;
+use crate::readers::utils::{read_available_bytes, read_bytes};
 use crate::{Grib2Error, Grib2Result};
 
 /// 第8節:終端節
@@ -15,14 +15,28 @@ const SECTION8_MARKER: &str = "7777";
 impl Section8 {
     /// 第8節:終端節を読み込む。
     ///
+    /// `allow_missing_end_marker`が`true`の場合、ダウンロードが途中で中断されたファイルなど、
+    /// 終端マーカーが記録されていないファイルでもエラーを返さない。この場合、終端マーカーとして
+    /// 読み込めたバイト列を記録し、`has_end_marker`は`false`を返す。
+    ///
     /// # 引数
     ///
     /// * `reader` - GRIB2リーダー
+    /// * `allow_missing_end_marker` - 終端マーカーが記録されていなくてもエラーにしない場合は`true`
     ///
     /// # 戻り値
     ///
     /// * 第8節:終端節
-    pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+    pub(crate) fn from_reader<R: Read>(
+        reader: &mut BufReader<R>,
+        allow_missing_end_marker: bool,
+    ) -> Grib2Result<Self> {
+        if allow_missing_end_marker {
+            // 読み込めたバイト列のみを終端マーカーとして記録
+            let mut marker = [0u8; 4];
+            read_available_bytes(reader, "第8節:終端マーカー", &mut marker)?;
+            return Ok(Self { marker });
+        }
         // 第8節:終端マーカー
         let marker = read_bytes(reader, "第8節:終端マーカー", 4)?;
         if marker != SECTION8_MARKER.as_bytes() {
@@ -39,4 +53,9 @@ impl Section8 {
             marker: marker.try_into().unwrap(),
         })
     }
+
+    /// 終端マーカーが正しく記録されているかを返す。
+    pub fn has_end_marker(&self) -> bool {
+        self.marker == SECTION8_MARKER.as_bytes()
+    }
 }
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+pub mod export;
 pub mod grib2;
 pub mod readers;
 
@@ -24,6 +25,12 @@ pub enum Grib2Error {
     #[error("{0}")]
     ConvertError(Cow<'static, str>),
 
+    /// 格子不一致エラー
+    ///
+    /// `field`には、一致しなかった格子の項目名を格納する。
+    #[error("格子の`{field}`が一致しません。")]
+    GridMismatch { field: &'static str },
+
     /// 未実装エラー
     #[error("{0}")]
     NotImplemented(Cow<'static, str>),
@@ -2,12 +2,15 @@ use std::fs::{File, OpenOptions};
 use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
-use crate::readers::records::Grib2RecordIterBuilder;
+use crate::readers::records::{lon_to_i32, Grib2RecordIterBuilder};
 use crate::readers::sections::{
-    Section0, Section1, Section2, Section3_0, Section4_50009, Section5_200u16, Section6,
-    Section7_200, Section8,
+    describe, Section0, Section1, Section2, Section3_0, Section4_50009, Section5_200u16, Section6,
+    Section7_200, Section8, SectionDescription,
 };
-use crate::readers::ForecastHour;
+use crate::readers::utils::{
+    peek_section_number, validate_no_trailing_data, validate_section_order,
+};
+use crate::readers::{ForecastHour, ReaderOptions};
 use crate::{Grib2Error, Grib2Result};
 
 /// 降水短時間予報ファイルリーダー
@@ -29,6 +32,8 @@ pub struct FPrrReader {
 }
 
 pub struct FPrrSections {
+    /// 第3節:格子系定義節（プロダクトの直前に記録されている場合）
+    pub section3: Option<Section3_0>,
     /// 第4節:プロダクト定義節
     pub section4: Section4_50009,
     /// 第5節:資料表現節
@@ -50,6 +55,20 @@ impl FPrrReader {
     ///
     /// * 降水短時間予報ファイルリーダー
     pub fn new<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        Self::with_options(path, ReaderOptions::default())
+    }
+
+    /// オプションを指定して降水短時間予報ファイルを開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 降水短時間予報ファイルのパス
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 降水短時間予報ファイルリーダー
+    pub fn with_options<P: AsRef<Path>>(path: P, options: ReaderOptions) -> Grib2Result<Self> {
         let path = path.as_ref();
         if !path.is_file() {
             return Err(Grib2Error::FileDoesNotExist);
@@ -59,9 +78,11 @@ impl FPrrReader {
             .open(path)
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
         let mut reader = BufReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
+        let section0 =
+            Section0::from_reader_with_max_offset(&mut reader, options.max_leading_bytes)?;
+        validate_section_order(&mut reader)?;
         let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2;
+        let section2 = Section2::from_reader(&mut reader)?;
         let section3 = Section3_0::from_reader(&mut reader)?;
         let fprr_sections = [
             FPrrSections::from_reader(&mut reader)?,
@@ -71,7 +92,14 @@ impl FPrrReader {
             FPrrSections::from_reader(&mut reader)?,
             FPrrSections::from_reader(&mut reader)?,
         ];
-        let section8 = Section8::from_reader(&mut reader)?;
+        let section8 = Section8::from_reader(&mut reader, options.allow_missing_end_marker)?;
+        // 予想時間別の予想降水量は座標ごとに組み合わせるため、プロダクトの直前に第3節が
+        // 記録されている場合は、最初の第3節と同じ格子でなければならない
+        for sections in &fprr_sections {
+            if let Some(product_section3) = &sections.section3 {
+                section3.validate_same_grid(product_section3)?;
+            }
+        }
 
         // 予想降水量を読み込み
         let preps = [
@@ -82,6 +110,7 @@ impl FPrrReader {
             read_preps(&mut reader, &section3, &fprr_sections[4])?,
             read_preps(&mut reader, &section3, &fprr_sections[5])?,
         ];
+        validate_preps(&preps, section3.number_of_data_points())?;
 
         Ok(Self {
             section0,
@@ -94,6 +123,26 @@ impl FPrrReader {
         })
     }
 
+    /// 降水短時間予報ファイルを開き、第8節の後にデータが存在しないことを確認する。
+    ///
+    /// ファイルに複数のGRIB報が連結されている場合など、第8節の後にデータが存在する場合は
+    /// エラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 降水短時間予報ファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// * 降水短時間予報ファイルリーダー
+    pub fn new_strict<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        let path = path.as_ref();
+        let reader = Self::new(path)?;
+        validate_no_trailing_data(path, reader.section0())?;
+
+        Ok(reader)
+    }
+
     /// 第0節:指示節を返す。
     ///
     /// # 戻り値
@@ -152,16 +201,63 @@ impl FPrrReader {
         &self.section8
     }
 
+    /// 最初のプロダクトの第5節に記録されている資料表現テンプレート番号を返す。
+    ///
+    /// 資料を復号する前に、ランレングス圧縮（テンプレート5.200）で記録されているかを
+    /// 確認する場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料表現テンプレート番号
+    pub fn data_representation_number(&self) -> u16 {
+        self.fprr_sections[0]
+            .section5
+            .data_representation_template_number()
+    }
+
+    /// 第0節から第8節までのすべての節のフィールドを、節の名前と組にして返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![
+            describe("第0節:指示節", &self.section0),
+            describe("第1節:識別節", &self.section1),
+            describe("第2節:地域使用節", &self.section2),
+            describe("第3節:格子系定義節", &self.section3),
+        ];
+        for sections in &self.fprr_sections {
+            descriptions.extend(sections.describe());
+        }
+        descriptions.push(describe("第8節:終端節", &self.section8));
+
+        descriptions
+    }
+
     /// 予想降水量を反復操作するイテレーターを返す。
     ///
+    /// 格子点は`scan_order_iter`と同じ走査順で返される。
+    ///
     /// # 引数
     ///
     /// # 戻り値
     ///
     /// * 予想降水量を反復操作するイテレーター
-    pub fn value_iter(&self) -> FPrrValueIterator {
+    pub fn value_iter(&self) -> FPrrValueIterator<'_> {
+        self.scan_order_iter()
+    }
+
+    /// ファイルに記録されている走査順で、予想降水量を反復操作するイテレーターを返す。
+    ///
+    /// 格子点は、北西端の格子点から西から東へ、北から南へ向かう順に返される。
+    ///
+    /// # 戻り値
+    ///
+    /// * 予想降水量を走査順で反復操作するイテレーター
+    pub fn scan_order_iter(&self) -> FPrrValueIterator<'_> {
         FPrrValueIterator::new(
-            self.section3.lat_of_first_grid_point(),
+            self.section3.lat_of_first_grid_point_i32(),
             self.section3.lon_of_first_grid_point(),
             self.section3.lon_of_last_grid_point(),
             self.section3.j_direction_increment(),
@@ -169,12 +265,37 @@ impl FPrrReader {
             &self.preps,
         )
     }
+
+    /// 格子点ごとに、1時間から6時間までの予想降水量の最大値を返すイテレーターを返す。
+    ///
+    /// 格子点は`scan_order_iter`と同じ走査順で返される。すべての予想時間で値を持たない
+    /// 格子点の最大値は`None`である。
+    ///
+    /// # 戻り値
+    ///
+    /// * 1e-6度単位の緯度と経度及び予想降水量の最大値を返すイテレーター
+    pub fn max_over_hours_iter(&self) -> impl Iterator<Item = (i32, u32, Option<u16>)> + '_ {
+        self.scan_order_iter().map(|value| {
+            let max = [
+                value.hour1,
+                value.hour2,
+                value.hour3,
+                value.hour4,
+                value.hour5,
+                value.hour6,
+            ]
+            .into_iter()
+            .flatten()
+            .max();
+            (value.lat, value.lon, max)
+        })
+    }
 }
 
 pub struct FPrrValue {
-    /// 緯度
-    pub lat: u32,
-    /// 経度
+    /// 南緯を負の値とする緯度（1e-6度単位）
+    pub lat: i32,
+    /// 経度（1e-6度単位）
     pub lon: u32,
     /// 1時間予報降水量
     pub hour1: Option<u16>,
@@ -190,9 +311,20 @@ pub struct FPrrValue {
     pub hour6: Option<u16>,
 }
 
+impl FPrrValue {
+    /// 符号付きの経度（1e-6度単位）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 西経を負の値とする1e-6度単位の経度
+    pub fn lon_i32(&self) -> i32 {
+        lon_to_i32(self.lon)
+    }
+}
+
 pub struct FPrrValueIterator<'a> {
-    /// 格子点の緯度
-    lat: u32,
+    /// 格子点の緯度（南緯を負の値とする1e-6度単位）
+    lat: i32,
     /// 格子点の経度
     lon: u32,
     /// 最初の格子点の経度
@@ -221,7 +353,7 @@ impl<'a> FPrrValueIterator<'a> {
     /// * `lon_inc` - 経度方向の増分
     /// * `preps` - 予想降水量を予想時間でインデックス化した配列
     fn new(
-        lat: u32,
+        lat: i32,
         lon: u32,
         lon_max: u32,
         lat_inc: u32,
@@ -245,23 +377,20 @@ impl<'a> Iterator for FPrrValueIterator<'a> {
     type Item = FPrrValue;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.preps[0].len() <= self.index {
-            return None;
-        }
         let result = FPrrValue {
             lat: self.lat,
             lon: self.lon,
-            hour1: self.preps[0][self.index],
-            hour2: self.preps[1][self.index],
-            hour3: self.preps[2][self.index],
-            hour4: self.preps[3][self.index],
-            hour5: self.preps[4][self.index],
-            hour6: self.preps[5][self.index],
+            hour1: *self.preps[0].get(self.index)?,
+            hour2: *self.preps[1].get(self.index)?,
+            hour3: *self.preps[2].get(self.index)?,
+            hour4: *self.preps[3].get(self.index)?,
+            hour5: *self.preps[4].get(self.index)?,
+            hour6: *self.preps[5].get(self.index)?,
         };
         self.index += 1;
         self.lon += self.lon_inc;
         if self.lon_max < self.lon {
-            self.lat -= self.lat_inc;
+            self.lat = (self.lat as i64 - self.lat_inc as i64) as i32;
             self.lon = self.lon_min;
         }
 
@@ -297,7 +426,12 @@ fn read_preps(
         .reader(reader)
         .total_bytes(fprr_sections.section7.run_length_bytes())
         .number_of_points(section3.number_of_data_points())
+        .grid_shape(
+            section3.number_of_along_lat_points(),
+            section3.number_of_along_lon_points(),
+        )
         .lat_max(section3.lat_of_first_grid_point())
+        .signed_lat(section3.has_southern_grid_points())
         .lon_min(section3.lon_of_first_grid_point())
         .lon_max(section3.lon_of_last_grid_point())
         .lat_inc(section3.j_direction_increment())
@@ -315,27 +449,225 @@ fn read_preps(
     Ok(precipitations)
 }
 
+/// 予想時間別の予想降水量が、すべて同じ格子点を記録しているか確認する。
+///
+/// 予想時間別の予想降水量は、第3節に記録されている資料点数と同じ数の格子点を、同じ走査順で
+/// 記録していなければならない。
+///
+/// # 引数
+///
+/// * `preps` - 予想降水量を予想時間でインデックス化した配列
+/// * `number_of_points` - 第3節に記録されている資料点数
+fn validate_preps(preps: &[Vec<Option<u16>>; 6], number_of_points: u32) -> Grib2Result<()> {
+    if preps
+        .iter()
+        .any(|values| values.len() != number_of_points as usize)
+    {
+        return Err(Grib2Error::GridMismatch {
+            field: "number_of_points",
+        });
+    }
+
+    Ok(())
+}
+
 impl FPrrSections {
     /// 第4節:プロダクト定義節から第7節:資料節を読み込む。
     ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も読み込む。
+    ///
     /// # 引数
     ///
-    /// * `reader` - 土砂災害警戒判定メッシュファイルリーダー
+    /// * `reader` - 降水短時間予報ファイルリーダー
     ///
     /// # 戻り値
     ///
     /// * 第4節:プロダクト定義節から第7節:資料節
     fn from_reader<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+        let section3 = match peek_section_number(reader)? {
+            3 => Some(Section3_0::from_reader(reader)?),
+            _ => None,
+        };
         let section4 = Section4_50009::from_reader(reader)?;
         let section5 = Section5_200u16::from_reader(reader)?;
         let section6 = Section6::from_reader(reader)?;
         let section7 = Section7_200::from_reader(reader)?;
 
         Ok(Self {
+            section3,
             section4,
             section5,
             section6,
             section7,
         })
     }
+
+    /// 第4節:プロダクト定義節から第7節:資料節までのフィールドを、節の名前と組にして返す。
+    ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も含める。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![];
+        if let Some(section3) = &self.section3 {
+            descriptions.push(describe("第3節:格子系定義節", section3));
+        }
+        descriptions.extend([
+            describe("第4節:プロダクト定義節", &self.section4),
+            describe("第5節:資料表現節", &self.section5),
+            describe("第6節:ビットマップ節", &self.section6),
+            describe("第7節:資料節", &self.section7),
+        ]);
+
+        descriptions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::{validate_preps, FPrrReader, FPrrValueIterator};
+    use crate::Grib2Error;
+
+    const FPRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1km_Prr60lv_Fper10min_FH01-06_grib2.bin"
+    );
+
+    /// 最初の第3節の複製を、2番目のプロダクトの第4節の直前に挿入したファイルを作成する。
+    ///
+    /// 複製した第3節の最初と最後の格子点の緯度は、`lat_shift`だけ南に移動する。
+    fn write_with_product_section3(src: &str, name: &str, lat_shift: u32) -> PathBuf {
+        let mut bytes = fs::read(src).unwrap();
+        let section_bytes =
+            |bytes: &[u8], p: usize| u32::from_be_bytes(bytes[p..p + 4].try_into().unwrap());
+        let section3_position = 16 + section_bytes(&bytes, 16) as usize;
+        let section3_bytes = section_bytes(&bytes, section3_position) as usize;
+        let mut position = section3_position + section3_bytes;
+        for _ in 0..4 {
+            position += section_bytes(&bytes, position) as usize;
+        }
+        let mut section3 = bytes[section3_position..section3_position + section3_bytes].to_vec();
+        for range in [46..50, 55..59] {
+            let lat = section_bytes(&section3, range.start) - lat_shift;
+            section3[range].copy_from_slice(&lat.to_be_bytes());
+        }
+        bytes.splice(position..position, section3);
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        let path = std::env::temp_dir().join(format!("{name}_{}.bin", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn same_product_section3_ok() {
+        let path = write_with_product_section3(FPRR_FILE, "grib2_2_fprr_same_section3", 0);
+        let reader = FPrrReader::new(&path);
+        fs::remove_file(path).unwrap();
+
+        let reader = reader.unwrap();
+        assert!(reader
+            .fprr_sections(super::ForecastHour::Hour1)
+            .section3
+            .is_none());
+        assert!(reader
+            .fprr_sections(super::ForecastHour::Hour2)
+            .section3
+            .is_some());
+        let expected = FPrrReader::new(FPRR_FILE).unwrap();
+        assert!(reader
+            .value_iter()
+            .zip(expected.value_iter())
+            .all(|(actual, expected)| (actual.hour2, actual.hour6)
+                == (expected.hour2, expected.hour6)));
+    }
+
+    #[test]
+    fn different_product_section3_err() {
+        let path = write_with_product_section3(FPRR_FILE, "grib2_2_fprr_diff_section3", 1_000_000);
+        let result = FPrrReader::new(&path);
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "lat_of_first_grid_point"
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_preps_ok() {
+        let preps: [Vec<Option<u16>>; 6] = std::array::from_fn(|_| vec![Some(1), None]);
+        assert!(validate_preps(&preps, 2).is_ok());
+    }
+
+    #[test]
+    fn validate_preps_with_mismatched_hours_err() {
+        let mut preps: [Vec<Option<u16>>; 6] = std::array::from_fn(|_| vec![Some(1), None]);
+        preps[3].pop();
+        assert!(matches!(
+            validate_preps(&preps, 2),
+            Err(Grib2Error::GridMismatch {
+                field: "number_of_points"
+            })
+        ));
+    }
+
+    #[test]
+    fn value_iter_with_mismatched_hours_does_not_panic() {
+        let mut preps: [Vec<Option<u16>>; 6] = std::array::from_fn(|_| vec![Some(1), None]);
+        preps[5].pop();
+        let iter = FPrrValueIterator::new(2_000, 1_000, 2_000, 1_000, 1_000, &preps);
+        assert_eq!(1, iter.count());
+    }
+
+    #[test]
+    fn scan_order_iter_starts_at_northwest_corner() {
+        let reader = FPrrReader::new(FPRR_FILE).unwrap();
+        let section3 = reader.section3();
+        let mut iter = reader.scan_order_iter();
+
+        let first = iter.next().unwrap();
+        assert_eq!(section3.lat_of_first_grid_point_i32(), first.lat);
+        assert_eq!(section3.lon_of_first_grid_point(), first.lon);
+        // 以降の格子点は、北から南へ、同じ緯度では西から東へ並ぶ
+        let mut prev = (first.lat, first.lon);
+        for value in iter {
+            assert!(value.lat < prev.0 || (value.lat == prev.0 && prev.1 < value.lon));
+            prev = (value.lat, value.lon);
+        }
+    }
+
+    #[test]
+    fn max_over_hours_iter_ok() {
+        let reader = FPrrReader::new(FPRR_FILE).unwrap();
+        let mut has_value = false;
+        for ((lat, lon, max), value) in reader.max_over_hours_iter().zip(reader.scan_order_iter()) {
+            let hours = [
+                value.hour1,
+                value.hour2,
+                value.hour3,
+                value.hour4,
+                value.hour5,
+                value.hour6,
+            ];
+            assert_eq!((value.lat, value.lon), (lat, lon));
+            match max {
+                Some(max) => {
+                    has_value = true;
+                    assert!(hours.contains(&Some(max)));
+                    assert!(hours.iter().flatten().all(|&v| v <= max));
+                }
+                None => assert!(hours.iter().all(Option::is_none)),
+            }
+        }
+        assert!(has_value);
+    }
 }
@@ -1,10 +1,15 @@
 use std::fs::OpenOptions;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek};
 use std::path::Path;
 
-use crate::readers::records::Grib2RecordIterBuilder;
-use crate::readers::sections::{Section0, Section1, Section2, Section3_0, Section8};
-use crate::readers::{ForecastHour, ForecastRange};
+use time::OffsetDateTime;
+
+use crate::readers::records::{lon_to_i32, Grib2RecordIterBuilder};
+use crate::readers::sections::{
+    describe, Section0, Section1, Section2, Section3_0, Section8, SectionDescription,
+};
+use crate::readers::utils::{validate_no_trailing_data, validate_section_order};
+use crate::readers::{ForecastHour, ForecastRange, ReaderOptions};
 use crate::readers::{PswSections, PswTank};
 use crate::{Grib2Error, Grib2Result};
 
@@ -46,18 +51,8 @@ pub struct FPswReader {
 
 /// タンク別土壌雨量指数予想値
 struct TankValue {
-    /// 1時間土壌雨量指数予想値
-    hour1: Vec<Option<u16>>,
-    /// 2時間土壌雨量指数予想値
-    hour2: Vec<Option<u16>>,
-    /// 3時間土壌雨量指数予想値
-    hour3: Vec<Option<u16>>,
-    /// 4時間土壌雨量指数予想値
-    hour4: Option<Vec<Option<u16>>>,
-    /// 5時間土壌雨量指数予想値
-    hour5: Option<Vec<Option<u16>>>,
-    /// 6時間土壌雨量指数予想値
-    hour6: Option<Vec<Option<u16>>>,
+    /// 1時間から予想時間範囲の最後の予想時間までの土壌雨量指数予想値を、予想時間順に格納したベクター
+    hours: Vec<Vec<Option<u16>>>,
 }
 
 impl FPswReader {
@@ -71,6 +66,25 @@ impl FPswReader {
     ///
     /// * 土壌雨量指数リーダー
     pub fn new<P: AsRef<Path>>(path: P, forecast_range: ForecastRange) -> Grib2Result<Self> {
+        Self::with_options(path, forecast_range, ReaderOptions::default())
+    }
+
+    /// オプションを指定して土壌雨量指数ファイルを開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土壌雨量指数ファイルのパス
+    /// * `forecast_range` - 予想時間範囲
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 土壌雨量指数リーダー
+    pub fn with_options<P: AsRef<Path>>(
+        path: P,
+        forecast_range: ForecastRange,
+        options: ReaderOptions,
+    ) -> Grib2Result<Self> {
         let path = path.as_ref();
         if !path.is_file() {
             return Err(Grib2Error::FileDoesNotExist);
@@ -80,9 +94,11 @@ impl FPswReader {
             .open(path)
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
         let mut reader = BufReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
+        let section0 =
+            Section0::from_reader_with_max_offset(&mut reader, options.max_leading_bytes)?;
+        validate_section_order(&mut reader)?;
         let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2;
+        let section2 = Section2::from_reader(&mut reader)?;
         let section3 = Section3_0::from_reader(&mut reader)?;
         let mut fpsw_sections = vec![];
         for _ in 0..(forecast_range as u8) {
@@ -92,17 +108,9 @@ impl FPswReader {
                 PswSections::from_reader(&mut reader)?,
             ]);
         }
-        let section8 = Section8::from_reader(&mut reader)?;
+        let section8 = Section8::from_reader(&mut reader, options.allow_missing_end_marker)?;
 
-        let mut tank_values = vec![];
-        for tank in [PswTank::All, PswTank::Tank1, PswTank::Tank2] {
-            tank_values.push(TankValue::from_reader(
-                &mut reader,
-                tank,
-                &section3,
-                &fpsw_sections,
-            )?);
-        }
+        let tank_values = TankValue::read_tanks(&mut reader, &section3, &fpsw_sections)?;
 
         Ok(Self {
             forecast_range,
@@ -116,6 +124,26 @@ impl FPswReader {
         })
     }
 
+    /// 土壌雨量指数ファイルを開き、第8節の後にデータが存在しないことを確認する。
+    ///
+    /// ファイルに複数のGRIB報が連結されている場合など、第8節の後にデータが存在する場合は
+    /// エラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土壌雨量指数ファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// * 土壌雨量指数リーダー
+    pub fn new_strict<P: AsRef<Path>>(path: P, forecast_range: ForecastRange) -> Grib2Result<Self> {
+        let path = path.as_ref();
+        let reader = Self::new(path, forecast_range)?;
+        validate_no_trailing_data(path, reader.section0())?;
+
+        Ok(reader)
+    }
+
     /// 第0節:指示節を返す。
     ///
     /// # 戻り値
@@ -176,6 +204,25 @@ impl FPswReader {
         Ok(&self.fpsw_sections[hour as u8 as usize - 1][tank as u8 as usize])
     }
 
+    /// 1時間から記録されている予想時間までの、予想値が有効な日時を返す。
+    ///
+    /// 予想値が有効な日時は、第1節の資料の参照時刻に、全タンクの第4節に記録されている予報時間を
+    /// 加えた日時である。予想時間が同じであれば、タンクによらず有効な日時は同じである。
+    ///
+    /// # 戻り値
+    ///
+    /// * 予想時間の順に格納した予想値が有効な日時
+    pub fn valid_times(&self) -> Grib2Result<Vec<OffsetDateTime>> {
+        let referenced_at = self.section1.referenced_at();
+        self.fpsw_sections
+            .iter()
+            .map(|sections| {
+                let section4 = &sections[PswTank::All as u8 as usize].section4;
+                Ok(referenced_at + section4.forecast_duration()?)
+            })
+            .collect()
+    }
+
     /// 第8節:終端節を返す。
     ///
     /// # 戻り値
@@ -185,6 +232,40 @@ impl FPswReader {
         &self.section8
     }
 
+    /// 最初のプロダクトの第5節に記録されている資料表現テンプレート番号を返す。
+    ///
+    /// 資料を復号する前に、ランレングス圧縮（テンプレート5.200）で記録されているかを
+    /// 確認する場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料表現テンプレート番号
+    pub fn data_representation_number(&self) -> u16 {
+        self.fpsw_sections[0][0]
+            .section5
+            .data_representation_template_number()
+    }
+
+    /// 第0節から第8節までのすべての節のフィールドを、節の名前と組にして返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![
+            describe("第0節:指示節", &self.section0),
+            describe("第1節:識別節", &self.section1),
+            describe("第2節:地域使用節", &self.section2),
+            describe("第3節:格子系定義節", &self.section3),
+        ];
+        for sections in self.fpsw_sections.iter().flatten() {
+            descriptions.extend(sections.describe());
+        }
+        descriptions.push(describe("第8節:終端節", &self.section8));
+
+        descriptions
+    }
+
     /// 予想降水量を反復操作するイテレーターを返す。
     ///
     /// # 引数
@@ -194,13 +275,14 @@ impl FPswReader {
     /// # 戻り値
     ///
     /// * 予想降水量を反復操作するイテレーター
-    pub fn value_iter(&self, tank: PswTank) -> FPswIndexIterator {
+    pub fn value_iter(&self, tank: PswTank) -> FPswIndexIterator<'_> {
         FPswIndexIterator::new(
-            self.section3.lat_of_first_grid_point(),
+            self.section3.lat_of_first_grid_point_i32(),
             self.section3.lon_of_first_grid_point(),
             self.section3.lon_of_last_grid_point(),
             self.section3.j_direction_increment(),
             self.section3.i_direction_increment(),
+            self.forecast_range,
             &self.tank_values[tank as u8 as usize],
         )
     }
@@ -208,28 +290,71 @@ impl FPswReader {
 
 /// タンク土壌雨量指数予想値
 pub struct FPswIndex {
-    /// 緯度
-    pub lat: u32,
-    /// 経度
+    /// 南緯を負の値とする緯度（1e-6度単位）
+    pub lat: i32,
+    /// 経度（1e-6度単位）
     pub lon: u32,
-    /// 1時間土壌雨量指数予想値
-    pub hour1: Option<u16>,
-    /// 2時間土壌雨量指数予想値
-    pub hour2: Option<u16>,
-    /// 3時間土壌雨量指数予想値
-    pub hour3: Option<u16>,
-    /// 4時間土壌雨量指数予想値
-    pub hour4: Option<u16>,
-    /// 5時間土壌雨量指数予想値
-    pub hour5: Option<u16>,
-    /// 6時間土壌雨量指数予想値
-    pub hour6: Option<u16>,
+    /// 1時間から6時間までの土壌雨量指数予想値を予想時間順に格納した配列
+    ///
+    /// 予想時間範囲に含まれない予想時間の要素は`None`である。
+    hours: [Option<u16>; 6],
+    /// 予想時間範囲
+    forecast_range: ForecastRange,
+}
+
+impl FPswIndex {
+    /// 1時間から予想時間範囲の最後の予想時間までの土壌雨量指数予想値を返す。
+    ///
+    /// 要素数は予想時間範囲の時間数と一致する。`ForecastRange::Hours3`のファイルでは、
+    /// 4時間から6時間の予想値は含まれない。
+    ///
+    /// # 戻り値
+    ///
+    /// * 予想時間順に格納した土壌雨量指数予想値のスライス
+    pub fn hours(&self) -> &[Option<u16>] {
+        &self.hours[..self.forecast_range as u8 as usize]
+    }
+
+    /// 予想時間範囲を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 予想時間範囲
+    pub fn forecast_range(&self) -> ForecastRange {
+        self.forecast_range
+    }
+
+    /// 指定された予想時間の土壌雨量指数予想値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `hour` - 予想時間
+    ///
+    /// # 戻り値
+    ///
+    /// * 予想時間がファイルの予想時間範囲に含まれない場合は`None`
+    /// * 予想時間が予想時間範囲に含まれる場合は、その格子点の土壌雨量指数予想値
+    ///   （欠測の場合は`Some(None)`）
+    pub fn hour(&self, hour: ForecastHour) -> Option<Option<u16>> {
+        self.forecast_range
+            .contains(hour)
+            .then(|| self.hours[hour as u8 as usize - 1])
+    }
+
+    /// 符号付きの経度（1e-6度単位）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 西経を負の値とする1e-6度単位の経度
+    pub fn lon_i32(&self) -> i32 {
+        lon_to_i32(self.lon)
+    }
 }
 
 /// タンク土壌雨量指数予想値を反復処理するイテレーター
 pub struct FPswIndexIterator<'a> {
-    /// 格子点の緯度
-    lat: u32,
+    /// 格子点の緯度（南緯を負の値とする1e-6度単位）
+    lat: i32,
     /// 格子点の経度
     lon: u32,
     /// 最初の格子点の経度
@@ -242,6 +367,8 @@ pub struct FPswIndexIterator<'a> {
     lon_inc: u32,
     /// 次に返す土壌雨量指数予想値のインデックス
     index: usize,
+    /// 予想時間範囲
+    forecast_range: ForecastRange,
     /// 土壌雨量指数予想値
     tank_values: &'a TankValue,
 }
@@ -256,13 +383,15 @@ impl<'a> FPswIndexIterator<'a> {
     /// * `lon_max` - 最後の格子点の経度
     /// * `lat_inc` - 緯度方向の増分
     /// * `lon_inc` - 経度方向の増分
+    /// * `forecast_range` - 予想時間範囲
     /// * `tank_values` - タンクの土壌雨量指数予想値
     fn new(
-        lat: u32,
+        lat: i32,
         lon: u32,
         lon_max: u32,
         lat_inc: u32,
         lon_inc: u32,
+        forecast_range: ForecastRange,
         tank_values: &'a TankValue,
     ) -> Self {
         Self {
@@ -273,6 +402,7 @@ impl<'a> FPswIndexIterator<'a> {
             lat_inc,
             lon_inc,
             index: 0,
+            forecast_range,
             tank_values,
         }
     }
@@ -282,32 +412,21 @@ impl<'a> Iterator for FPswIndexIterator<'a> {
     type Item = FPswIndex;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.tank_values.hour1.len() <= self.index {
-            return None;
+        // 格子点ごとにベクターを確保しないように、固定長の配列に格納
+        let mut hours = [None; 6];
+        for (value, values) in hours.iter_mut().zip(&self.tank_values.hours) {
+            *value = *values.get(self.index)?;
         }
-        let (hour4, hour5, hour6) = if self.tank_values.hour4.is_some() {
-            (
-                self.tank_values.hour4.as_ref().unwrap()[self.index],
-                self.tank_values.hour5.as_ref().unwrap()[self.index],
-                self.tank_values.hour6.as_ref().unwrap()[self.index],
-            )
-        } else {
-            (None, None, None)
-        };
         let result = FPswIndex {
             lat: self.lat,
             lon: self.lon,
-            hour1: self.tank_values.hour1[self.index],
-            hour2: self.tank_values.hour2[self.index],
-            hour3: self.tank_values.hour3[self.index],
-            hour4,
-            hour5,
-            hour6,
+            hours,
+            forecast_range: self.forecast_range,
         };
         self.index += 1;
         self.lon += self.lon_inc;
         if self.lon_max < self.lon {
-            self.lat -= self.lat_inc;
+            self.lat = (self.lat as i64 - self.lat_inc as i64) as i32;
             self.lon = self.lon_min;
         }
 
@@ -335,17 +454,25 @@ where
     R: Read + Seek,
 {
     // 第7節のランレングス圧縮オクテット列の開始位置にファイルポインターを移動
+    // 現在位置からの相対位置で移動することで、移動先がバッファ内にある場合はバッファを再利用する
+    let current = reader
+        .stream_position()
+        .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+    let offset = fpsw_sections.section7.run_length_position() as i64 - current as i64;
     reader
-        .seek(SeekFrom::Start(
-            fpsw_sections.section7.run_length_position() as u64,
-        ))
+        .seek_relative(offset)
         .map_err(|e| Grib2Error::Unexpected(e.into()))?;
     // イテレーターを構築
     let iter = Grib2RecordIterBuilder::new()
         .reader(reader)
         .total_bytes(fpsw_sections.section7.run_length_bytes())
         .number_of_points(section3.number_of_data_points())
+        .grid_shape(
+            section3.number_of_along_lat_points(),
+            section3.number_of_along_lon_points(),
+        )
         .lat_max(section3.lat_of_first_grid_point())
+        .signed_lat(section3.has_southern_grid_points())
         .lon_min(section3.lon_of_first_grid_point())
         .lon_max(section3.lon_of_last_grid_point())
         .lat_inc(section3.j_direction_increment())
@@ -364,61 +491,257 @@ where
 }
 
 impl TankValue {
-    /// タンクの土壌雨量指数予想値を読み込み。
-    fn from_reader<R: Read + Seek>(
+    /// すべてのタンクの土壌雨量指数予想値を読み込む。
+    ///
+    /// ファイルには予想時間ごとに全タンク、第1タンク及び第2タンクの順で資料が記録されているため、
+    /// その順番でランレングス圧縮オクテット列を展開する。
+    /// 予想時間とタンク別の土壌雨量指数予想値は座標ごとに組み合わせるため、プロダクトの直前に
+    /// 第3節が記録されている場合は、最初の第3節と同じ格子でなければならない。
+    /// これにより、ファイルポインターの移動は常に次の資料節への前方への移動となり、1つのリーダーの
+    /// バッファを再利用しながら、最初の資料節から最後の資料節まで1度だけ走査する。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 土壌雨量指数予想値ファイルリーダー
+    /// * `section3` - 第3節:格子系定義節
+    /// * `fpsw_sections` - 予想時間とタンク別の第4節:プロダクト定義節から第7節:資料節
+    ///
+    /// # 戻り値
+    ///
+    /// * 全タンク、第1タンク及び第2タンクの順に土壌雨量指数予想値を格納したベクター
+    fn read_tanks<R: Read + Seek>(
         reader: &mut BufReader<R>,
-        tank: PswTank,
         section3: &Section3_0,
         fpsw_sections: &[[PswSections; 3]],
-    ) -> Grib2Result<Self> {
-        let hour1 = read_tank_indexes(
-            reader,
-            section3,
-            &fpsw_sections[ForecastHour::Hour1 as u8 as usize - 1][tank as u8 as usize],
-        )?;
-        let hour2 = read_tank_indexes(
-            reader,
-            section3,
-            &fpsw_sections[ForecastHour::Hour2 as u8 as usize - 1][tank as u8 as usize],
-        )?;
-        let hour3 = read_tank_indexes(
-            reader,
-            section3,
-            &fpsw_sections[ForecastHour::Hour3 as u8 as usize - 1][tank as u8 as usize],
-        )?;
-        if fpsw_sections.len() == 3 {
-            return Ok(Self {
-                hour1,
-                hour2,
-                hour3,
-                hour4: None,
-                hour5: None,
-                hour6: None,
-            });
+    ) -> Grib2Result<Vec<Self>> {
+        // タンク別に予想時間順の土壌雨量指数予想値を格納
+        let mut tanks: [Vec<Vec<Option<u16>>>; 3] = Default::default();
+        for hour_sections in fpsw_sections {
+            for tank in [PswTank::All, PswTank::Tank1, PswTank::Tank2] {
+                let index = tank as u8 as usize;
+                if let Some(product_section3) = &hour_sections[index].section3 {
+                    section3.validate_same_grid(product_section3)?;
+                }
+                tanks[index].push(read_tank_indexes(reader, section3, &hour_sections[index])?);
+            }
         }
-        let hour4 = read_tank_indexes(
-            reader,
-            section3,
-            &fpsw_sections[ForecastHour::Hour4 as u8 as usize - 1][tank as u8 as usize],
-        )?;
-        let hour5 = read_tank_indexes(
-            reader,
-            section3,
-            &fpsw_sections[ForecastHour::Hour5 as u8 as usize - 1][tank as u8 as usize],
-        )?;
-        let hour6 = read_tank_indexes(
-            reader,
-            section3,
-            &fpsw_sections[ForecastHour::Hour6 as u8 as usize - 1][tank as u8 as usize],
-        )?;
 
-        Ok(Self {
-            hour1,
-            hour2,
-            hour3,
-            hour4: Some(hour4),
-            hour5: Some(hour5),
-            hour6: Some(hour6),
-        })
+        Ok(tanks.into_iter().map(|hours| Self { hours }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::{BufReader, Seek, SeekFrom};
+    use std::path::PathBuf;
+
+    use super::FPswReader;
+    use crate::readers::records::Grib2RecordIterBuilder;
+    use crate::readers::{
+        ForecastHour, ForecastRange, PswReader, PswSections, PswTank, ReaderOptions,
+    };
+
+    const PSW_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807170000_SRF_GPV_Ggis1km_Psw_Aper10min_ANAL_grib2.bin"
+    );
+
+    const FPSW_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807152000_SRF_GPV_Ggis1km_Psw_Fper10min_FH01-06_grib2.bin"
+    );
+
+    #[test]
+    fn allow_missing_end_marker_ok() {
+        let path = std::env::temp_dir().join(format!(
+            "grib2_2_fpsw_missing_end_marker_{}",
+            std::process::id()
+        ));
+        let mut bytes = fs::read(FPSW_FILE).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        fs::write(&path, bytes).unwrap();
+        let default = FPswReader::new(&path, ForecastRange::Hours6);
+        let options = ReaderOptions::new().allow_missing_end_marker(true);
+        let lenient = FPswReader::with_options(&path, ForecastRange::Hours6, options);
+        fs::remove_file(path).unwrap();
+
+        assert!(default.is_err());
+        let lenient = lenient.unwrap();
+        assert!(!lenient.section8().has_end_marker());
+        let expected = FPswReader::new(FPSW_FILE, ForecastRange::Hours6).unwrap();
+        assert!(lenient
+            .value_iter(PswTank::All)
+            .zip(expected.value_iter(PswTank::All))
+            .all(|(actual, expected)| actual.hours() == expected.hours()));
+    }
+
+    /// 最初の第3節の複製を、2番目のプロダクトの第4節の直前に挿入したファイルを作成する。
+    ///
+    /// 複製した第3節の最初と最後の格子点の緯度は、`lat_shift`だけ南に移動する。
+    fn write_with_product_section3(src: &str, name: &str, lat_shift: u32) -> PathBuf {
+        let mut bytes = fs::read(src).unwrap();
+        let section_bytes =
+            |bytes: &[u8], p: usize| u32::from_be_bytes(bytes[p..p + 4].try_into().unwrap());
+        let section3_position = 16 + section_bytes(&bytes, 16) as usize;
+        let section3_bytes = section_bytes(&bytes, section3_position) as usize;
+        let mut position = section3_position + section3_bytes;
+        for _ in 0..4 {
+            position += section_bytes(&bytes, position) as usize;
+        }
+        let mut section3 = bytes[section3_position..section3_position + section3_bytes].to_vec();
+        for range in [46..50, 55..59] {
+            let lat = section_bytes(&section3, range.start) - lat_shift;
+            section3[range].copy_from_slice(&lat.to_be_bytes());
+        }
+        bytes.splice(position..position, section3);
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        let path = std::env::temp_dir().join(format!("{name}_{}.bin", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn same_product_section3_ok() {
+        let path = write_with_product_section3(FPSW_FILE, "grib2_2_fpsw_same_section3", 0);
+        let reader = FPswReader::new(&path, ForecastRange::Hours6);
+        fs::remove_file(path).unwrap();
+
+        let reader = reader.unwrap();
+        let expected = FPswReader::new(FPSW_FILE, ForecastRange::Hours6).unwrap();
+        assert!(reader
+            .value_iter(PswTank::Tank1)
+            .zip(expected.value_iter(PswTank::Tank1))
+            .all(|(actual, expected)| actual.hours() == expected.hours()));
+    }
+
+    #[test]
+    fn different_product_section3_err() {
+        let path = write_with_product_section3(FPSW_FILE, "grib2_2_fpsw_diff_section3", 1_000_000);
+        let result = FPswReader::new(&path, ForecastRange::Hours6);
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(crate::Grib2Error::GridMismatch {
+                field: "lat_of_first_grid_point"
+            })
+        ));
+    }
+
+    /// 新しいリーダーで資料節の開始位置に移動して、土壌雨量指数予想値を展開する。
+    fn decode_with_fresh_reader(reader: &FPswReader, sections: &PswSections) -> Vec<Option<u16>> {
+        let mut file = BufReader::new(File::open(FPSW_FILE).unwrap());
+        file.seek(SeekFrom::Start(
+            sections.section7.run_length_position() as u64
+        ))
+        .unwrap();
+        let section3 = reader.section3();
+        Grib2RecordIterBuilder::new()
+            .reader(&mut file)
+            .total_bytes(sections.section7.run_length_bytes())
+            .number_of_points(section3.number_of_data_points())
+            .grid_shape(
+                section3.number_of_along_lat_points(),
+                section3.number_of_along_lon_points(),
+            )
+            .lat_max(section3.lat_of_first_grid_point())
+            .signed_lat(section3.has_southern_grid_points())
+            .lon_min(section3.lon_of_first_grid_point())
+            .lon_max(section3.lon_of_last_grid_point())
+            .lat_inc(section3.j_direction_increment())
+            .lon_inc(section3.i_direction_increment())
+            .nbit(sections.section5.bits_per_value() as u16)
+            .maxv(sections.section5.max_level_value())
+            .level_values(sections.section5.level_values())
+            .build()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect()
+    }
+
+    #[test]
+    fn tank_values_match_fresh_reader_decode() {
+        let reader = FPswReader::new(FPSW_FILE, ForecastRange::Hours6).unwrap();
+        for tank in [PswTank::All, PswTank::Tank1, PswTank::Tank2] {
+            let index = tank as u8 as usize;
+            let hours = &reader.tank_values[index].hours;
+            assert_eq!(reader.fpsw_sections.len(), hours.len());
+            for (hour, hour_sections) in hours.iter().zip(&reader.fpsw_sections) {
+                let expected = decode_with_fresh_reader(&reader, &hour_sections[index]);
+                assert_eq!(&expected, hour);
+            }
+        }
+    }
+
+    #[test]
+    fn same_tank_selects_same_parameter_in_both_readers() {
+        let psw = PswReader::new(PSW_FILE).unwrap();
+        let fpsw = FPswReader::new(FPSW_FILE, ForecastRange::Hours6).unwrap();
+        for tank in [PswTank::All, PswTank::Tank1, PswTank::Tank2] {
+            let analysis = &psw.psw_sections(tank).section4;
+            let forecast = &fpsw
+                .fpsw_sections(ForecastHour::Hour1, tank)
+                .unwrap()
+                .section4;
+            assert_eq!(
+                (analysis.parameter_category(), analysis.parameter_number()),
+                (forecast.parameter_category(), forecast.parameter_number()),
+                "{tank:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn hours3_file_has_no_hours_after_hour3() {
+        // 3時間予想までの第4節から第7節の後に終端マーカーを記録したファイルを作成
+        let reader = FPswReader::new(FPSW_FILE, ForecastRange::Hours6).unwrap();
+        let section7 = &reader
+            .fpsw_sections(ForecastHour::Hour3, PswTank::Tank2)
+            .unwrap()
+            .section7;
+        let end = section7.run_length_position() + section7.run_length_bytes();
+        let mut bytes = fs::read(FPSW_FILE).unwrap();
+        bytes.truncate(end);
+        bytes.extend_from_slice(b"7777");
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        let path =
+            std::env::temp_dir().join(format!("grib2_2_fpsw_{}_hours3.bin", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        let hours3 = FPswReader::new(&path, ForecastRange::Hours3);
+        fs::remove_file(path).unwrap();
+        let hours3 = hours3.unwrap();
+
+        for (index, (short, long)) in hours3
+            .value_iter(PswTank::All)
+            .zip(reader.value_iter(PswTank::All))
+            .enumerate()
+            .step_by(100_003)
+        {
+            assert_eq!(ForecastRange::Hours3, short.forecast_range());
+            assert_eq!(3, short.hours().len(), "{index}");
+            assert_eq!(&long.hours()[..3], short.hours(), "{index}");
+            assert_eq!(
+                long.hour(ForecastHour::Hour3),
+                short.hour(ForecastHour::Hour3)
+            );
+            // 4時間から6時間の予想値は、欠測ではなく記録されていない
+            assert_eq!(None, short.hour(ForecastHour::Hour4));
+            assert!(long.hour(ForecastHour::Hour6).is_some());
+        }
+    }
+
+    #[test]
+    fn valid_times_ok() {
+        let reader = FPswReader::new(FPSW_FILE, ForecastRange::Hours6).unwrap();
+        let times = reader.valid_times().unwrap();
+        assert_eq!(6, times.len());
+        assert!(reader.section1().referenced_at() < times[0]);
+        assert!(times
+            .windows(2)
+            .all(|w| w[1] - w[0] == time::Duration::hours(1)));
     }
 }
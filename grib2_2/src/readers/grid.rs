@@ -0,0 +1,843 @@
+use crate::readers::records::lon_to_i32;
+use crate::readers::sections::Section3_0;
+use crate::readers::Grib2Record;
+use crate::{Grib2Error, Grib2Result};
+
+/// 経度の1周（1e-6度単位）
+const FULL_CIRCLE: i64 = 360_000_000;
+
+/// 格子点の行と列のインデックスで表現した矩形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    /// 北西端の格子点の行のインデックス
+    pub row: u32,
+    /// 北西端の格子点の列のインデックス
+    pub col: u32,
+    /// 行数
+    pub rows: u32,
+    /// 列数
+    pub columns: u32,
+}
+
+/// 格子全体の値を展開してメモリに保持する格子
+///
+/// 格子点の値は、北西端の格子点から東方向に、行ごとに南方向に並べて保持する。
+/// 行のインデックスは北から南に、列のインデックスは西から東に向かって増加する。
+#[derive(Debug, Clone)]
+pub struct DecodedGrid<V> {
+    /// 格子を定義する第3節:格子系定義節
+    section3: Section3_0,
+    /// 格子点の値
+    values: Vec<Option<V>>,
+}
+
+impl<V> DecodedGrid<V>
+where
+    V: Clone + Copy,
+{
+    /// レコードを反復処理するイテレーターから格子を構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `iter` - レコードを反復処理するイテレーター
+    /// * `section3` - 格子を定義する第3節:格子系定義節
+    ///
+    /// # 戻り値
+    ///
+    /// * 格子
+    pub fn from_records<I>(iter: I, section3: &Section3_0) -> Grib2Result<Self>
+    where
+        I: Iterator<Item = Grib2Result<Grib2Record<V>>>,
+    {
+        let number_of_points = section3.number_of_along_lat_points() as usize
+            * section3.number_of_along_lon_points() as usize;
+        let mut values = Vec::with_capacity(number_of_points);
+        for record in iter {
+            values.push(record?.value);
+        }
+        if values.len() != number_of_points {
+            return Err(Grib2Error::GridMismatch {
+                field: "number_of_points",
+            });
+        }
+
+        Ok(Self {
+            section3: section3.clone(),
+            values,
+        })
+    }
+
+    /// 格子を定義する第3節:格子系定義節を返す。
+    pub fn section3(&self) -> &Section3_0 {
+        &self.section3
+    }
+
+    /// 経度方向の格子数（Ni）を返す。
+    pub fn number_of_columns(&self) -> u32 {
+        self.section3.number_of_along_lat_points()
+    }
+
+    /// 緯度方向の格子数（Nj）を返す。
+    pub fn number_of_rows(&self) -> u32 {
+        self.section3.number_of_along_lon_points()
+    }
+
+    /// 格子点の値を、北西端の格子点から東方向に、行ごとに南方向に並べたスライスを返す。
+    pub fn values(&self) -> &[Option<V>] {
+        &self.values
+    }
+
+    /// 値を持つ格子点の数を返す。
+    pub fn number_of_presents(&self) -> usize {
+        self.values.iter().filter(|v| v.is_some()).count()
+    }
+
+    /// 格子点の値を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `row` - 行のインデックス
+    /// * `col` - 列のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// * 格子点の値、格子の範囲外または値を持たない場合は`None`
+    pub fn get(&self, row: u32, col: u32) -> Option<V> {
+        if self.number_of_rows() <= row || self.number_of_columns() <= col {
+            return None;
+        }
+        self.values[self.index(row, col)]
+    }
+
+    /// 行の格子点の緯度を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `row` - 行のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// * 南緯を負の値とする1e-6度単位の緯度
+    pub fn lat_of_row(&self, row: u32) -> i32 {
+        let lat = self.section3.lat_of_first_grid_point_i32() as i64
+            - row as i64 * self.section3.j_direction_increment() as i64;
+        lat as i32
+    }
+
+    /// 列の格子点の経度を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `col` - 列のインデックス
+    ///
+    /// # 戻り値
+    ///
+    /// * 西経を負の値とする1e-6度単位の経度
+    pub fn lon_of_column(&self, col: u32) -> i32 {
+        let lon = (self.section3.lon_of_first_grid_point() as i64
+            + col as i64 * self.section3.i_direction_increment() as i64)
+            .rem_euclid(FULL_CIRCLE);
+        lon_to_i32(lon as u32)
+    }
+
+    /// 座標に最も近い格子点の行と列のインデックスを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `lat` - 南緯を負の値とする1e-6度単位の緯度
+    /// * `lon` - 西経を負の値とする1e-6度単位の経度
+    ///
+    /// # 戻り値
+    ///
+    /// * 行と列のインデックス、座標が格子の範囲外の場合または増分が0の場合は`None`
+    pub fn cell_of(&self, lat: i32, lon: i32) -> Option<(u32, u32)> {
+        let lat_inc = self.section3.j_direction_increment() as i64;
+        let lon_inc = self.section3.i_direction_increment() as i64;
+        if lat_inc == 0 || lon_inc == 0 {
+            return None;
+        }
+        let lat_offset = self.section3.lat_of_first_grid_point_i32() as i64 - lat as i64;
+        let mut lon_offset =
+            (lon as i64 - self.section3.lon_of_first_grid_point() as i64).rem_euclid(FULL_CIRCLE);
+        if FULL_CIRCLE / 2 < lon_offset {
+            lon_offset -= FULL_CIRCLE;
+        }
+        let row = (lat_offset as f64 / lat_inc as f64).round() as i64;
+        let col = (lon_offset as f64 / lon_inc as f64).round() as i64;
+        if row < 0
+            || self.number_of_rows() as i64 <= row
+            || col < 0
+            || self.number_of_columns() as i64 <= col
+        {
+            return None;
+        }
+
+        Some((row as u32, col as u32))
+    }
+
+    /// 座標に最も近い、値を持つ格子点を返す。
+    ///
+    /// 座標に最も近い格子点から、行と列の差の大きい方を距離とするリング状に外側へ向かって
+    /// 探索する。同じリング内に値を持つ格子点が複数ある場合は、中心の格子点からの距離が
+    /// 最も近い格子点を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `lat` - 南緯を負の値とする1e-6度単位の緯度
+    /// * `lon` - 西経を負の値とする1e-6度単位の経度
+    /// * `max_radius_cells` - 探索する最大の距離（格子数）
+    ///
+    /// # 戻り値
+    ///
+    /// * 値を持つ格子点の行のインデックス、列のインデックス及び値、座標が格子の範囲外の
+    ///   場合または探索する範囲に値を持つ格子点がない場合は`None`
+    pub fn nearest_present(
+        &self,
+        lat: i32,
+        lon: i32,
+        max_radius_cells: u32,
+    ) -> Option<(u32, u32, V)> {
+        let (row, col) = self.cell_of(lat, lon)?;
+        let (row, col) = (row as i64, col as i64);
+        for radius in 0..=max_radius_cells as i64 {
+            let mut nearest: Option<(i64, u32, u32, V)> = None;
+            for r in row - radius..=row + radius {
+                for c in col - radius..=col + radius {
+                    if (r - row).abs() != radius && (c - col).abs() != radius {
+                        continue;
+                    }
+                    if r < 0 || c < 0 {
+                        continue;
+                    }
+                    let Some(value) = self.get(r as u32, c as u32) else {
+                        continue;
+                    };
+                    let distance = (r - row).pow(2) + (c - col).pow(2);
+                    if nearest.is_none_or(|(d, ..)| distance < d) {
+                        nearest = Some((distance, r as u32, c as u32, value));
+                    }
+                }
+            }
+            if let Some((_, r, c, value)) = nearest {
+                return Some((r, c, value));
+            }
+        }
+
+        None
+    }
+
+    /// 値を持つ領域の境界の格子点を返す。
+    ///
+    /// 値を持つ格子点のうち、上下左右に隣接する格子点のいずれかが値を持たないか、
+    /// 格子の範囲外である格子点を境界とする。
+    ///
+    /// # 戻り値
+    ///
+    /// * 境界の格子点の行と列のインデックスを、北西端の格子点から走査順に格納したベクター
+    pub fn boundary_cells(&self) -> Vec<(u32, u32)> {
+        let mut cells = vec![];
+        for row in 0..self.number_of_rows() {
+            for col in 0..self.number_of_columns() {
+                if self.get(row, col).is_none() {
+                    continue;
+                }
+                let is_boundary = row == 0
+                    || col == 0
+                    || self.get(row - 1, col).is_none()
+                    || self.get(row + 1, col).is_none()
+                    || self.get(row, col - 1).is_none()
+                    || self.get(row, col + 1).is_none();
+                if is_boundary {
+                    cells.push((row, col));
+                }
+            }
+        }
+
+        cells
+    }
+
+    /// 他の格子との差分を計算する。
+    ///
+    /// 2つのファイルの値を比較するために、格子点ごとに`self - other`を計算する。
+    /// いずれかの格子点が値を持たない場合、差分の格子点は値を持たない。
+    ///
+    /// # 引数
+    ///
+    /// * `other` - 差し引く格子
+    ///
+    /// # 戻り値
+    ///
+    /// * 差分の格子、格子が一致しない場合は`Grib2Error::GridMismatch`
+    pub fn diff(&self, other: &DecodedGrid<V>) -> Grib2Result<DecodedGrid<i32>>
+    where
+        V: Into<i32>,
+    {
+        self.section3.validate_same_grid(&other.section3)?;
+        let values = self
+            .values
+            .iter()
+            .zip(other.values.iter())
+            .map(|(a, b)| match (a, b) {
+                (Some(a), Some(b)) => Some((*a).into() - (*b).into()),
+                _ => None,
+            })
+            .collect();
+
+        Ok(DecodedGrid {
+            section3: self.section3.clone(),
+            values,
+        })
+    }
+
+    /// 格子を`factor`×`factor`の格子点のブロックに分割して、ブロックごとに値を平均した
+    /// 格子を返す。
+    ///
+    /// ブロック内の値を持つ格子点の値を平均し、値を持つ格子点がないブロックは値を持たない。
+    /// 格子点数が`factor`で割り切れない場合、東端及び南端のブロックは格子の範囲内の格子点のみで
+    /// 平均する。新しい格子の格子点の座標は各ブロックの北西端の格子点の座標とし、増分は元の
+    /// 増分の`factor`倍とする。
+    ///
+    /// # 引数
+    ///
+    /// * `factor` - ブロックの1辺の格子数
+    ///
+    /// # 戻り値
+    ///
+    /// * ブロックごとに値を平均した格子
+    pub fn block_mean(&self, factor: u32) -> Grib2Result<DecodedGrid<f64>>
+    where
+        V: Into<f64>,
+    {
+        if factor == 0 {
+            return Err(Grib2Error::RuntimeError(
+                "ブロックの1辺の格子数に0は指定できません。".into(),
+            ));
+        }
+        let (columns, rows) = (self.number_of_columns(), self.number_of_rows());
+        let ni = columns.div_ceil(factor);
+        let nj = rows.div_ceil(factor);
+        let mut values = Vec::with_capacity(ni as usize * nj as usize);
+        for block_row in 0..nj {
+            for block_col in 0..ni {
+                let (mut sum, mut count) = (0.0, 0);
+                for row in block_row * factor..((block_row + 1) * factor).min(rows) {
+                    for col in block_col * factor..((block_col + 1) * factor).min(columns) {
+                        if let Some(value) = self.get(row, col) {
+                            sum += value.into();
+                            count += 1;
+                        }
+                    }
+                }
+                values.push((0 < count).then(|| sum / count as f64));
+            }
+        }
+
+        Ok(DecodedGrid {
+            section3: self.section3.subgrid(0, 0, ni, nj, factor),
+            values,
+        })
+    }
+
+    /// 緯度と経度の範囲に含まれる格子点を切り出した格子を返す。
+    ///
+    /// 範囲の境界上の格子点を含める。切り出した格子の最初と最後の格子点の座標及び格子点数は、
+    /// 切り出した範囲に合わせて更新する。経度0度または180度を跨ぐ範囲は指定できない。
+    ///
+    /// # 引数
+    ///
+    /// * `lat_min` - 南緯を負の値とする1e-6度単位の緯度の最小値
+    /// * `lat_max` - 南緯を負の値とする1e-6度単位の緯度の最大値
+    /// * `lon_min` - 西経を負の値とする1e-6度単位の経度の最小値
+    /// * `lon_max` - 西経を負の値とする1e-6度単位の経度の最大値
+    ///
+    /// # 戻り値
+    ///
+    /// * 切り出した格子
+    pub fn crop(
+        &self,
+        lat_min: i32,
+        lat_max: i32,
+        lon_min: i32,
+        lon_max: i32,
+    ) -> Grib2Result<DecodedGrid<V>> {
+        let rows = (0..self.number_of_rows())
+            .filter(|&row| (lat_min..=lat_max).contains(&self.lat_of_row(row)))
+            .collect::<Vec<_>>();
+        let cols = (0..self.number_of_columns())
+            .filter(|&col| (lon_min..=lon_max).contains(&self.lon_of_column(col)))
+            .collect::<Vec<_>>();
+        let (Some(&first_row), Some(&first_col)) = (rows.first(), cols.first()) else {
+            return Err(Grib2Error::RuntimeError(
+                format!(
+                    "緯度{lat_min}から{lat_max}、経度{lon_min}から{lon_max}の範囲に格子点がありません。"
+                )
+                .into(),
+            ));
+        };
+        let mut values = Vec::with_capacity(rows.len() * cols.len());
+        for &row in &rows {
+            values.extend(cols.iter().map(|&col| self.get(row, col)));
+        }
+
+        Ok(DecodedGrid {
+            section3: self.section3.subgrid(
+                first_row,
+                first_col,
+                cols.len() as u32,
+                rows.len() as u32,
+                1,
+            ),
+            values,
+        })
+    }
+
+    /// 格子点が値を持つかを1ビットで表現したビット列を返す。
+    ///
+    /// 北西端の格子点から走査順に、値を持つ格子点のビットを1とする。格子点の値を保持するより
+    /// 少ないメモリで、欠測の分布を保持できる。
+    ///
+    /// # 戻り値
+    ///
+    /// * 格子点数と同じ長さのビット列
+    #[cfg(feature = "bitvec")]
+    pub fn presence_mask(&self) -> bitvec::vec::BitVec {
+        self.values.iter().map(|v| v.is_some()).collect()
+    }
+
+    /// 同じ値を持つ格子点を矩形にまとめて返す。
+    ///
+    /// 行ごとに同じ値が連続する格子点の並びを求め、直前の行に同じ列の範囲と値を持つ矩形が
+    /// ある場合は、その矩形を南に1行伸ばす。矩形の数は最小とは限らないが、格子点ごとに
+    /// 出力するよりも少ない数の矩形で、値を持つ格子点を重複なく覆う。
+    ///
+    /// # 戻り値
+    ///
+    /// * 矩形と値の組を格納したベクター
+    pub fn rectangles(&self) -> Vec<(Rect, V)>
+    where
+        V: PartialEq,
+    {
+        let mut rectangles: Vec<(Rect, V)> = vec![];
+        // 直前の行まで伸ばした矩形のインデックス
+        let mut open: Vec<usize> = vec![];
+        for row in 0..self.number_of_rows() {
+            let mut next_open = vec![];
+            let mut col = 0;
+            while col < self.number_of_columns() {
+                let Some(value) = self.get(row, col) else {
+                    col += 1;
+                    continue;
+                };
+                let start = col;
+                while col < self.number_of_columns() && self.get(row, col) == Some(value) {
+                    col += 1;
+                }
+                let columns = col - start;
+                let extendable = open.iter().copied().find(|&index| {
+                    let (rect, v) = &rectangles[index];
+                    rect.col == start && rect.columns == columns && *v == value
+                });
+                match extendable {
+                    Some(index) => {
+                        rectangles[index].0.rows += 1;
+                        next_open.push(index);
+                    }
+                    None => {
+                        next_open.push(rectangles.len());
+                        rectangles.push((
+                            Rect {
+                                row,
+                                col: start,
+                                rows: 1,
+                                columns,
+                            },
+                            value,
+                        ));
+                    }
+                }
+            }
+            open = next_open;
+        }
+
+        rectangles
+    }
+
+    /// 値を持つ格子点を、座標リスト（COO）形式の疎行列として返す。
+    ///
+    /// 行のインデックス、列のインデックス及び値を、北西端の格子点から走査順に同じ位置に
+    /// 格納する。欠測が多い格子を、疎行列を扱うライブラリに渡す場合に利用できる。
+    ///
+    /// # 戻り値
+    ///
+    /// * 行のインデックス、列のインデックス及び値を格納したベクターの組
+    pub fn to_coo(&self) -> (Vec<u32>, Vec<u32>, Vec<V>) {
+        let number_of_presents = self.number_of_presents();
+        let mut rows = Vec::with_capacity(number_of_presents);
+        let mut cols = Vec::with_capacity(number_of_presents);
+        let mut values = Vec::with_capacity(number_of_presents);
+        for (index, value) in self.values.iter().enumerate() {
+            if let Some(value) = value {
+                let index = index as u32;
+                rows.push(index / self.number_of_columns());
+                cols.push(index % self.number_of_columns());
+                values.push(*value);
+            }
+        }
+
+        (rows, cols, values)
+    }
+
+    /// 行ごとに値を持つ格子点の数を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 北の行から順に、値を持つ格子点の数を格納したベクター
+    pub fn row_present_counts(&self) -> Vec<u32> {
+        self.values
+            .chunks(self.number_of_columns().max(1) as usize)
+            .map(|row| row.iter().filter(|v| v.is_some()).count() as u32)
+            .collect()
+    }
+
+    /// 格子点の値を物理値に変換したベクターを返す。
+    ///
+    /// 格子点の値に、第5節の`decimal_scale`が返す尺度を乗じて物理値に変換する。
+    ///
+    /// # 引数
+    ///
+    /// * `decimal_scale` - データ代表値を物理値に変換する尺度
+    ///
+    /// # 戻り値
+    ///
+    /// * 北西端の格子点から走査順に物理値を格納したベクター
+    pub fn to_vec(&self, decimal_scale: f64) -> Vec<Option<f64>>
+    where
+        V: Into<f64>,
+    {
+        self.values
+            .iter()
+            .map(|v| v.map(|v| v.into() * decimal_scale))
+            .collect()
+    }
+
+    /// 格子点の値を`f32`型の物理値に変換したベクターを返す。
+    ///
+    /// `to_vec`の半分のメモリで物理値を保持できるため、GPUに転送する場合や大きな格子を
+    /// 扱う場合に利用できる。ただし、`f32`型の仮数部は24ビットであるため、有効桁数は
+    /// 10進数で約7桁となり、`f64`型の物理値を`f32`型に丸めることによる誤差は、相対誤差で
+    /// 約6e-8（2^-24）以下である。
+    ///
+    /// # 引数
+    ///
+    /// * `decimal_scale` - データ代表値を物理値に変換する尺度
+    ///
+    /// # 戻り値
+    ///
+    /// * 北西端の格子点から走査順に物理値を格納したベクター
+    pub fn to_vec_f32(&self, decimal_scale: f64) -> Vec<Option<f32>>
+    where
+        V: Into<f64>,
+    {
+        self.values
+            .iter()
+            .map(|v| v.map(|v| (v.into() * decimal_scale) as f32))
+            .collect()
+    }
+
+    /// 行と列のインデックスから、格子点の値を格納したベクターのインデックスを返す。
+    fn index(&self, row: u32, col: u32) -> usize {
+        row as usize * self.number_of_columns() as usize + col as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DecodedGrid, Rect};
+    use crate::readers::test_util::{section3, InMemoryRunLength};
+    use crate::readers::Grib2Record;
+    use crate::Grib2Error;
+
+    /// 北西端の格子点から走査順に並べた値から格子を構築する。
+    fn grid_of(columns: u32, rows: u32, values: &[Option<u16>]) -> DecodedGrid<u16> {
+        let records = values.iter().map(|&value| {
+            Ok(Grib2Record {
+                lat: 0,
+                lon: 0,
+                value,
+            })
+        });
+        DecodedGrid::from_records(records, &section3(columns, rows)).unwrap()
+    }
+
+    /// 次の3行4列の格子を返す（`.`は欠測）。
+    ///
+    /// ```text
+    /// 100 100 .   .
+    /// 100 .   .   .
+    /// 200 200 200 300
+    /// ```
+    fn grid() -> DecodedGrid<u16> {
+        // nbit = 4、maxv = 3、LNGU = 12
+        let run_length = vec![1, 5, 0, 5, 1, 0, 6, 2, 6, 3];
+        let mut fixture = InMemoryRunLength::new(run_length, 4, 3, vec![100, 200, 300]);
+        DecodedGrid::from_records(fixture.record_iter(4, 3).unwrap(), &section3(4, 3)).unwrap()
+    }
+
+    #[test]
+    fn from_records_ok() {
+        let grid = grid();
+
+        assert_eq!((4, 3), (grid.number_of_columns(), grid.number_of_rows()));
+        assert_eq!(7, grid.number_of_presents());
+        assert_eq!(Some(100), grid.get(1, 0));
+        assert_eq!(None, grid.get(1, 1));
+        assert_eq!(Some(300), grid.get(2, 3));
+        assert_eq!(
+            (34_999_000, 139_003_000),
+            (grid.lat_of_row(1), grid.lon_of_column(3))
+        );
+    }
+
+    #[test]
+    fn from_records_with_short_grid_err() {
+        let mut fixture = InMemoryRunLength::new(vec![1, 2, 5], 4, 3, vec![100, 200, 300]);
+        let iter = fixture.record_iter(3, 1).unwrap();
+        assert!(DecodedGrid::from_records(iter, &section3(3, 2)).is_err());
+    }
+
+    #[test]
+    fn nearest_present_ok() {
+        let grid = grid();
+
+        // 格子点の値をそのまま返す
+        assert_eq!(
+            Some((0, 0, 100)),
+            grid.nearest_present(35_000_000, 139_000_000, 0)
+        );
+        // 行1列1の格子点は欠測であるため、隣接する格子点のうち最も近い格子点の値を返す
+        assert_eq!(None, grid.nearest_present(34_999_000, 139_001_000, 0));
+        assert_eq!(
+            Some((0, 1, 100)),
+            grid.nearest_present(34_999_000, 139_001_000, 1)
+        );
+        // 探索する範囲に値を持つ格子点がない
+        assert_eq!(None, grid.nearest_present(35_000_000, 139_003_000, 0));
+        // 格子の範囲外
+        assert_eq!(None, grid.nearest_present(36_000_000, 139_000_000, 10));
+    }
+
+    #[test]
+    fn boundary_cells_ok() {
+        let mut values = vec![Some(1); 16];
+        let grid = grid_of(4, 4, &values);
+        let cells = grid.boundary_cells();
+        // 内側の4つの格子点以外は、格子の範囲外に隣接する
+        assert_eq!(12, cells.len());
+        assert!(!cells.contains(&(1, 1)));
+
+        // 1行2列の格子点を欠測にすると、隣接する内側の格子点が境界になる
+        values[6] = None;
+        let grid = grid_of(4, 4, &values);
+        let cells = grid.boundary_cells();
+        assert_eq!(14, cells.len());
+        assert!(cells.contains(&(1, 1)));
+        assert!(cells.contains(&(2, 2)));
+        assert!(!cells.contains(&(2, 1)));
+        assert!(!cells.contains(&(1, 2)));
+    }
+
+    #[test]
+    fn diff_ok() {
+        let before = grid();
+        let mut values = before.values().to_vec();
+        // 行2列3の格子点を300から100に変更し、行0列0の格子点を欠測にする
+        values[11] = Some(100);
+        values[0] = None;
+        let after = grid_of(4, 3, &values);
+        let diff = after.diff(&before).unwrap();
+
+        assert_eq!(Some(-200), diff.get(2, 3));
+        assert_eq!(Some(0), diff.get(2, 2));
+        assert_eq!(None, diff.get(0, 0));
+        assert_eq!(None, diff.get(1, 1));
+        assert_eq!(6, diff.number_of_presents());
+    }
+
+    #[test]
+    fn diff_with_different_grid_err() {
+        let result = grid().diff(&grid_of(3, 4, &[Some(1); 12]));
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "number_of_along_lat_points"
+            })
+        ));
+    }
+
+    #[test]
+    fn block_mean_ok() {
+        let grid = grid().block_mean(2).unwrap();
+
+        assert_eq!((2, 2), (grid.number_of_columns(), grid.number_of_rows()));
+        assert_eq!(Some(100.0), grid.get(0, 0));
+        assert_eq!(None, grid.get(0, 1));
+        // 南端のブロックは1行のみで平均する
+        assert_eq!(Some(200.0), grid.get(1, 0));
+        assert_eq!(Some(250.0), grid.get(1, 1));
+        let section3 = grid.section3();
+        assert_eq!(2_000, section3.i_direction_increment());
+        assert_eq!(2_000, section3.j_direction_increment());
+        assert_eq!(34_998_000, section3.lat_of_last_grid_point_i32());
+        assert_eq!(139_002_000, section3.lon_of_last_grid_point());
+        assert_eq!(4, section3.number_of_data_points());
+    }
+
+    #[test]
+    fn block_mean_with_zero_factor_err() {
+        assert!(grid().block_mean(0).is_err());
+    }
+
+    #[test]
+    fn crop_ok() {
+        let grid = grid()
+            .crop(34_998_000, 34_999_000, 139_001_000, 139_003_000)
+            .unwrap();
+
+        assert_eq!((3, 2), (grid.number_of_columns(), grid.number_of_rows()));
+        assert_eq!(
+            vec![None, None, None, Some(200), Some(200), Some(300)],
+            grid.values()
+        );
+        let section3 = grid.section3();
+        assert_eq!(34_999_000, section3.lat_of_first_grid_point_i32());
+        assert_eq!(139_001_000, section3.lon_of_first_grid_point());
+        assert_eq!(34_998_000, section3.lat_of_last_grid_point_i32());
+        assert_eq!(139_003_000, section3.lon_of_last_grid_point());
+        assert_eq!(1_000, section3.i_direction_increment());
+        assert_eq!(6, section3.number_of_data_points());
+        assert_eq!(
+            (34_998_000, 139_003_000),
+            (grid.lat_of_row(1), grid.lon_of_column(2))
+        );
+    }
+
+    #[test]
+    fn crop_outside_grid_err() {
+        assert!(grid()
+            .crop(36_000_000, 37_000_000, 139_000_000, 140_000_000)
+            .is_err());
+    }
+
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn presence_mask_ok() {
+        let grid = grid();
+        let mask = grid.presence_mask();
+
+        assert_eq!(grid.values().len(), mask.len());
+        assert_eq!(grid.number_of_presents(), mask.count_ones());
+        assert!(mask[0]);
+        assert!(!mask[5]);
+    }
+
+    #[test]
+    fn rectangles_ok() {
+        // 1 1 .
+        // 1 1 2
+        // . 2 2
+        let values = [
+            Some(1),
+            Some(1),
+            None,
+            Some(1),
+            Some(1),
+            Some(2),
+            None,
+            Some(2),
+            Some(2),
+        ];
+        let grid = grid_of(3, 3, &values);
+        let rectangles = grid.rectangles();
+
+        assert_eq!(
+            vec![
+                (
+                    Rect {
+                        row: 0,
+                        col: 0,
+                        rows: 2,
+                        columns: 2
+                    },
+                    1
+                ),
+                (
+                    Rect {
+                        row: 1,
+                        col: 2,
+                        rows: 1,
+                        columns: 1
+                    },
+                    2
+                ),
+                (
+                    Rect {
+                        row: 2,
+                        col: 1,
+                        rows: 1,
+                        columns: 2
+                    },
+                    2
+                ),
+            ],
+            rectangles
+        );
+        let covered = rectangles
+            .iter()
+            .map(|(rect, _)| rect.rows * rect.columns)
+            .sum::<u32>();
+        assert_eq!(grid.number_of_presents(), covered as usize);
+    }
+
+    #[test]
+    fn to_coo_ok() {
+        let grid = grid();
+        let (rows, cols, values) = grid.to_coo();
+
+        assert_eq!(grid.number_of_presents(), values.len());
+        assert_eq!(values.len(), rows.len());
+        assert_eq!(values.len(), cols.len());
+        assert!(rows.iter().all(|&row| row < grid.number_of_rows()));
+        assert!(cols.iter().all(|&col| col < grid.number_of_columns()));
+        assert_eq!((2, 3, 300), (rows[6], cols[6], values[6]));
+    }
+
+    #[test]
+    fn row_present_counts_ok() {
+        let grid = grid();
+        let counts = grid.row_present_counts();
+
+        assert_eq!(vec![2, 1, 4], counts);
+        assert_eq!(grid.number_of_rows() as usize, counts.len());
+        assert_eq!(
+            grid.number_of_presents(),
+            counts.iter().sum::<u32>() as usize
+        );
+    }
+
+    #[test]
+    fn to_vec_f32_ok() {
+        let grid = grid();
+        let values = grid.to_vec(0.01);
+        let values_f32 = grid.to_vec_f32(0.01);
+
+        assert_eq!(values.len(), values_f32.len());
+        assert_eq!(Some(3.0), values[11]);
+        for (value, value_f32) in values.iter().zip(values_f32.iter()) {
+            match (value, value_f32) {
+                (Some(a), Some(b)) => assert!((a - *b as f64).abs() <= a.abs() * 1e-7),
+                (None, None) => {}
+                _ => panic!("値の有無が一致しません。"),
+            }
+        }
+    }
+}
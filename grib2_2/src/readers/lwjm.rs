@@ -1,12 +1,17 @@
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
+use crate::export::write_wide_csv;
 use crate::readers::records::{Grib2RecordIter, Grib2RecordIterBuilder};
 use crate::readers::sections::{
-    Section0, Section1, Section2, Section3_0, Section4_50000, Section5_200i16, Section6,
-    Section7_200, Section8,
+    describe, Section0, Section1, Section2, Section3_0, Section4_50000, Section5_200i16, Section6,
+    Section7_200, Section8, SectionDescription,
 };
+use crate::readers::utils::{
+    peek_section_number, validate_no_trailing_data, validate_section_order,
+};
+use crate::readers::ReaderOptions;
 use crate::{Grib2Error, Grib2Result};
 
 /// 土砂災害警戒判定メッシュファイルリーダー
@@ -15,9 +20,9 @@ use crate::{Grib2Error, Grib2Result};
 ///
 /// * 実況の土砂災害警戒判定
 /// * 実況と1時間から3時間予想までの土砂災害警戒判定
-pub struct LwjmReader {
+pub struct LwjmReader<R = File> {
     /// ファイルリーダー
-    reader: BufReader<File>,
+    reader: BufReader<R>,
     /// 土砂災害警戒判定メッシュファイルが、1時間から3時間までの判定を含んでいるかを示すフラグ
     has_forecast: bool,
     /// 第0節:指示節
@@ -36,6 +41,8 @@ pub struct LwjmReader {
 
 /// 第4節:プロダクト定義節から第7節:資料節
 pub struct LwjmSections {
+    /// 第3節:格子系定義節（プロダクトの直前に記録されている場合）
+    pub section3: Option<Section3_0>,
     /// 第4節:プロダクト定義節
     pub section4: Section4_50000,
     /// 第5節:資料表現節
@@ -53,12 +60,32 @@ impl LwjmReader {
     ///
     /// * `path` - 土砂災害警戒判定メッシュファイルのパス
     /// * `has_forecast` - 土砂災害警戒判定メッシュファイルが実況のみを記録している場合は`false`、
-    ///                    実況と1時間から3時間までの予想を記録している場合は`true`
+    ///   実況と1時間から3時間までの予想を記録している場合は`true`
     ///
     /// # 戻り値
     ///
     /// * 土砂災害警戒判定メッシュリーダー
     pub fn new<P: AsRef<Path>>(path: P, has_forecast: bool) -> Grib2Result<Self> {
+        Self::with_options(path, has_forecast, ReaderOptions::default())
+    }
+
+    /// オプションを指定して土砂災害警戒判定メッシュファイルを開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土砂災害警戒判定メッシュファイルのパス
+    /// * `has_forecast` - 土砂災害警戒判定メッシュファイルが実況のみを記録している場合は`false`、
+    ///   実況と1時間から3時間までの予想を記録している場合は`true`
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 土砂災害警戒判定メッシュリーダー
+    pub fn with_options<P: AsRef<Path>>(
+        path: P,
+        has_forecast: bool,
+        options: ReaderOptions,
+    ) -> Grib2Result<Self> {
         let path = path.as_ref();
         if !path.is_file() {
             return Err(Grib2Error::FileDoesNotExist);
@@ -67,10 +94,90 @@ impl LwjmReader {
             .read(true)
             .open(path)
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
-        let mut reader = BufReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
+
+        Self::from_buf_reader(BufReader::new(file), has_forecast, options)
+    }
+
+    /// 土砂災害警戒判定メッシュファイルの内容をすべてメモリに読み込んで開く。
+    ///
+    /// ファイルは開いたときに1回だけ読み込み、以降のレコードの反復処理はメモリから読み込む。
+    /// メモリを消費する代わりに、複数の判定時間のレコードを繰り返し反復処理する場合に高速に
+    /// 動作する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土砂災害警戒判定メッシュファイルのパス
+    /// * `has_forecast` - 土砂災害警戒判定メッシュファイルが実況のみを記録している場合は`false`、
+    ///   実況と1時間から3時間までの予想を記録している場合は`true`
+    ///
+    /// # 戻り値
+    ///
+    /// * 土砂災害警戒判定メッシュリーダー
+    pub fn new_in_memory<P: AsRef<Path>>(
+        path: P,
+        has_forecast: bool,
+    ) -> Grib2Result<LwjmReader<Cursor<Vec<u8>>>> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(Grib2Error::FileDoesNotExist);
+        }
+        let bytes = fs::read(path).map_err(|e| Grib2Error::Unexpected(e.into()))?;
+
+        LwjmReader::from_buf_reader(
+            BufReader::new(Cursor::new(bytes)),
+            has_forecast,
+            ReaderOptions::default(),
+        )
+    }
+
+    /// 土砂災害警戒判定メッシュファイルを開き、第8節の後にデータが存在しないことを確認する。
+    ///
+    /// ファイルに複数のGRIB報が連結されている場合など、第8節の後にデータが存在する場合は
+    /// エラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土砂災害警戒判定メッシュファイルのパス
+    /// * `has_forecast` - 土砂災害警戒判定メッシュファイルが実況のみを記録している場合は`false`、
+    ///   実況と1時間から3時間までの予想を記録している場合は`true`
+    ///
+    /// # 戻り値
+    ///
+    /// * 土砂災害警戒判定メッシュリーダー
+    pub fn new_strict<P: AsRef<Path>>(path: P, has_forecast: bool) -> Grib2Result<Self> {
+        let path = path.as_ref();
+        let reader = Self::new(path, has_forecast)?;
+        validate_no_trailing_data(path, reader.section0())?;
+
+        Ok(reader)
+    }
+}
+
+impl<R> LwjmReader<R>
+where
+    R: Read + Seek,
+{
+    /// 開かれているリーダーから土砂災害警戒判定メッシュを読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 土砂災害警戒判定メッシュを読み込むリーダー
+    /// * `has_forecast` - 実況と1時間から3時間までの予想を記録している場合は`true`
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 土砂災害警戒判定メッシュリーダー
+    fn from_buf_reader(
+        mut reader: BufReader<R>,
+        has_forecast: bool,
+        options: ReaderOptions,
+    ) -> Grib2Result<Self> {
+        let section0 =
+            Section0::from_reader_with_max_offset(&mut reader, options.max_leading_bytes)?;
+        validate_section_order(&mut reader)?;
         let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2;
+        let section2 = Section2::from_reader(&mut reader)?;
         let section3 = Section3_0::from_reader(&mut reader)?;
         let judgments = match has_forecast {
             false => vec![LwjmSections::from_reader(&mut reader)?],
@@ -81,7 +188,7 @@ impl LwjmReader {
                 LwjmSections::from_reader(&mut reader)?,
             ],
         };
-        let section8 = Section8::from_reader(&mut reader)?;
+        let section8 = Section8::from_reader(&mut reader, options.allow_missing_end_marker)?;
 
         Ok(Self {
             reader,
@@ -131,6 +238,24 @@ impl LwjmReader {
         &self.section3
     }
 
+    /// 指定された土砂災害警戒判定時間の格子を定義する第3節:格子系定義節を返す。
+    ///
+    /// プロダクトの直前に第3節が記録されている場合はその節を、記録されていない場合は
+    /// 最初に記録されている第3節を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `hour` - 土砂災害警戒判定時間
+    ///
+    /// # 戻り値
+    ///
+    /// * 第3節:格子系定義節
+    pub fn product_section3(&self, hour: LwjmHour) -> Grib2Result<&Section3_0> {
+        let sections = self.lwjm_sections(hour)?;
+
+        Ok(sections.section3.as_ref().unwrap_or(&self.section3))
+    }
+
     /// 指定された土砂災害警戒判定時間別の第4節:プロダクト定義節から第7節:資料節を返す。
     ///
     /// # 戻り値
@@ -145,7 +270,7 @@ impl LwjmReader {
                 "土砂災害警戒判定メッシュファイルは予測を記録していません。".into(),
             ));
         }
-        Ok(&self.lwjm_sections[(hour as u8) as usize])
+        hour_sections(&self.lwjm_sections, hour)
     }
 
     /// 第8節:終端節を返す。
@@ -157,6 +282,40 @@ impl LwjmReader {
         &self.section8
     }
 
+    /// 最初のプロダクトの第5節に記録されている資料表現テンプレート番号を返す。
+    ///
+    /// 資料を復号する前に、ランレングス圧縮（テンプレート5.200）で記録されているかを
+    /// 確認する場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料表現テンプレート番号
+    pub fn data_representation_number(&self) -> u16 {
+        self.lwjm_sections[0]
+            .section5
+            .data_representation_template_number()
+    }
+
+    /// 第0節から第8節までのすべての節のフィールドを、節の名前と組にして返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![
+            describe("第0節:指示節", &self.section0),
+            describe("第1節:識別節", &self.section1),
+            describe("第2節:地域使用節", &self.section2),
+            describe("第3節:格子系定義節", &self.section3),
+        ];
+        for sections in &self.lwjm_sections {
+            descriptions.extend(sections.describe());
+        }
+        descriptions.push(describe("第8節:終端節", &self.section8));
+
+        descriptions
+    }
+
     /// 指定された土砂災害警戒判定時間のレコードを反復処理するイテレーターを返す。
     ///
     /// # 引数
@@ -166,7 +325,7 @@ impl LwjmReader {
     /// # 戻り値
     ///
     /// * 指定された土砂災害警戒判定時間のレコードを反復処理するイテレーター
-    pub fn record_iter(&mut self, hour: LwjmHour) -> Grib2Result<Grib2RecordIter<'_, File, i16>> {
+    pub fn record_iter(&mut self, hour: LwjmHour) -> Grib2Result<Grib2RecordIter<'_, R, i16>> {
         // 指定された土砂災害警戒判定時間の判定を取得
         // 実況以外、つまり1時間から3時間までの予測のいずれかで、土砂災害警戒判定メッシュファイルが
         // 予測を記録していない場合はエラー
@@ -175,7 +334,8 @@ impl LwjmReader {
                 "土砂災害警戒判定メッシュファイルは予測を記録していません。".into(),
             ));
         }
-        let sections = &self.lwjm_sections[hour as u8 as usize];
+        let sections = hour_sections(&self.lwjm_sections, hour)?;
+        let section3 = sections.section3.as_ref().unwrap_or(&self.section3);
         // ランレングス符号の開始位置にファイルポインターを移動
         self.reader
             .seek(SeekFrom::Start(
@@ -187,22 +347,81 @@ impl LwjmReader {
         Grib2RecordIterBuilder::new()
             .reader(&mut self.reader)
             .total_bytes(sections.section7.run_length_bytes())
-            .number_of_points(self.section3.number_of_data_points())
-            .lat_max(self.section3.lat_of_first_grid_point())
-            .lon_min(self.section3.lon_of_first_grid_point())
-            .lon_max(self.section3.lon_of_last_grid_point())
-            .lat_inc(self.section3.j_direction_increment())
-            .lon_inc(self.section3.i_direction_increment())
+            .number_of_points(section3.number_of_data_points())
+            .grid_shape(
+                section3.number_of_along_lat_points(),
+                section3.number_of_along_lon_points(),
+            )
+            .lat_max(section3.lat_of_first_grid_point())
+            .signed_lat(section3.has_southern_grid_points())
+            .lon_min(section3.lon_of_first_grid_point())
+            .lon_max(section3.lon_of_last_grid_point())
+            .lat_inc(section3.j_direction_increment())
+            .lon_inc(section3.i_direction_increment())
             .nbit(sections.section5.bits_per_value() as u16)
             .maxv(sections.section5.max_level_value())
             .level_values(sections.section5.level_values())
             .build()
     }
+
+    /// 実況と1時間から3時間までの予想の土砂災害警戒判定を、1つの座標につき1行の横持ちのCSVとして
+    /// 書き込む。
+    ///
+    /// 予想を記録している場合は`lon,lat,live,hour1,hour2,hour3`形式、実況のみを記録している
+    /// 場合は`lon,lat,live`形式で書き込む。予想の値を展開して保持した後、実況の値を展開しながら
+    /// 書き込む。
+    ///
+    /// 列は格子点の順番で組み合わせるため、予想の第3節:格子系定義節が実況と異なる格子を定義して
+    /// いる場合は、`Grib2Error::GridMismatch`を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - ライター
+    /// * `coord_decimals` - 座標の小数点以下の桁数
+    ///
+    /// # 戻り値
+    ///
+    /// * 書き込んだ行の数
+    pub fn write_wide_csv<W: Write>(
+        &mut self,
+        writer: &mut W,
+        coord_decimals: usize,
+    ) -> Grib2Result<usize> {
+        let (hours, headers): (&[LwjmHour], &[&str]) = if self.has_forecast {
+            (
+                &[LwjmHour::Hour1, LwjmHour::Hour2, LwjmHour::Hour3],
+                &["live", "hour1", "hour2", "hour3"],
+            )
+        } else {
+            (&[], &["live"])
+        };
+        let live_section3 = self.product_section3(LwjmHour::Live)?;
+        for &hour in hours {
+            live_section3.validate_same_grid(self.product_section3(hour)?)?;
+        }
+        let mut columns = vec![];
+        for &hour in hours {
+            columns.push(
+                self.record_iter(hour)?
+                    .map(|r| r.map(|r| r.value))
+                    .collect::<Grib2Result<Vec<_>>>()?,
+            );
+        }
+        write_wide_csv(
+            self.record_iter(LwjmHour::Live)?,
+            &columns,
+            headers,
+            writer,
+            coord_decimals,
+        )
+    }
 }
 
 impl LwjmSections {
     /// 第4節:プロダクト定義節から第7節:資料節を読み込む。
     ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も読み込む。
+    ///
     /// # 引数
     ///
     /// * `reader` - 土砂災害警戒判定メッシュファイルリーダー
@@ -211,18 +430,45 @@ impl LwjmSections {
     ///
     /// * 第4節:プロダクト定義節から第7節:資料節
     fn from_reader<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+        let section3 = match peek_section_number(reader)? {
+            3 => Some(Section3_0::from_reader(reader)?),
+            _ => None,
+        };
         let section4 = Section4_50000::from_reader(reader)?;
         let section5 = Section5_200i16::from_reader(reader)?;
         let section6 = Section6::from_reader(reader)?;
         let section7 = Section7_200::from_reader(reader)?;
 
         Ok(Self {
+            section3,
             section4,
             section5,
             section6,
             section7,
         })
     }
+
+    /// 第4節:プロダクト定義節から第7節:資料節までのフィールドを、節の名前と組にして返す。
+    ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も含める。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![];
+        if let Some(section3) = &self.section3 {
+            descriptions.push(describe("第3節:格子系定義節", section3));
+        }
+        descriptions.extend([
+            describe("第4節:プロダクト定義節", &self.section4),
+            describe("第5節:資料表現節", &self.section5),
+            describe("第6節:ビットマップ節", &self.section6),
+            describe("第7節:資料節", &self.section7),
+        ]);
+
+        descriptions
+    }
 }
 
 /// 土砂災害警戒判定時間
@@ -254,3 +500,183 @@ impl TryFrom<u8> for LwjmHour {
         }
     }
 }
+
+/// 土砂災害警戒判定時間に対応する第4節から第7節を返す。
+///
+/// # 引数
+///
+/// * `lwjm_sections` - 土砂災害警戒判定時間別の第4節から第7節
+/// * `hour` - 土砂災害警戒判定時間
+///
+/// # 戻り値
+///
+/// * 第4節:プロダクト定義節から第7節:資料節
+fn hour_sections(lwjm_sections: &[LwjmSections], hour: LwjmHour) -> Grib2Result<&LwjmSections> {
+    lwjm_sections.get(hour as u8 as usize).ok_or_else(|| {
+        Grib2Error::RuntimeError(format!(
+            "土砂災害警戒判定メッシュファイルに{}番目の判定が記録されていません。記録されている判定の数は{}です。",
+            hour as u8,
+            lwjm_sections.len()
+        )
+        .into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{LwjmHour, LwjmReader};
+    use crate::export::DEFAULT_COORD_DECIMALS;
+    use crate::readers::ReaderOptions;
+    use crate::Grib2Error;
+
+    const LWJM_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_FH00-03_grib2.bin"
+    );
+
+    const LWJM_LIVE_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_ANAL_grib2.bin"
+    );
+
+    /// 節の長さを返す。
+    fn section_bytes(bytes: &[u8], position: usize) -> usize {
+        u32::from_be_bytes(bytes[position..position + 4].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn product_section3_ok() {
+        let mut bytes = fs::read(LWJM_FILE).unwrap();
+        // 第0節、第1節を読み飛ばして第3節の位置を取得
+        let section3_position = 16 + section_bytes(&bytes, 16);
+        let section3_bytes = section_bytes(&bytes, section3_position);
+        // 1時間予想の第4節の位置を取得
+        let mut position = section3_position + section3_bytes;
+        for _ in 0..4 {
+            position += section_bytes(&bytes, position);
+        }
+        // 最初の格子点の緯度を1度南に移動した第3節を、1時間予想の直前に挿入
+        let mut section3 = bytes[section3_position..section3_position + section3_bytes].to_vec();
+        let lat = u32::from_be_bytes(section3[46..50].try_into().unwrap()) - 1_000_000;
+        section3[46..50].copy_from_slice(&lat.to_be_bytes());
+        bytes.splice(position..position, section3);
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        let path = std::env::temp_dir().join(format!(
+            "grib2_2_lwjm_{}_product_section3.bin",
+            std::process::id()
+        ));
+        fs::write(&path, bytes).unwrap();
+
+        let mut reader = LwjmReader::new_strict(&path, true).unwrap();
+        let live_lat = reader.section3().lat_of_first_grid_point();
+        assert!(reader
+            .lwjm_sections(LwjmHour::Live)
+            .unwrap()
+            .section3
+            .is_none());
+        assert_eq!(
+            live_lat - 1_000_000,
+            reader
+                .product_section3(LwjmHour::Hour1)
+                .unwrap()
+                .lat_of_first_grid_point()
+        );
+        assert_eq!(
+            live_lat,
+            reader
+                .product_section3(LwjmHour::Hour2)
+                .unwrap()
+                .lat_of_first_grid_point()
+        );
+        let first = reader
+            .record_iter(LwjmHour::Hour1)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!((live_lat - 1_000_000) as i32, first.lat);
+        // 格子が異なる予想は、横持ちのCSVに組み合わせられない
+        assert!(matches!(
+            reader.write_wide_csv(&mut vec![], DEFAULT_COORD_DECIMALS),
+            Err(Grib2Error::GridMismatch { .. })
+        ));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn new_in_memory_ok() {
+        let path = std::env::temp_dir().join(format!("grib2_2_lwjm_{}", std::process::id()));
+        fs::copy(LWJM_FILE, &path).unwrap();
+        let reader = LwjmReader::new_in_memory(&path, true);
+        // ファイルを削除しても、メモリからレコードを反復処理できる
+        fs::remove_file(&path).unwrap();
+        let mut reader = reader.unwrap();
+        let mut expected = LwjmReader::new(LWJM_FILE, true).unwrap();
+
+        for hour in [
+            LwjmHour::Live,
+            LwjmHour::Hour1,
+            LwjmHour::Hour2,
+            LwjmHour::Hour3,
+        ] {
+            let values = reader
+                .record_iter(hour)
+                .unwrap()
+                .map(|r| r.unwrap().value)
+                .collect::<Vec<_>>();
+            let expected = expected
+                .record_iter(hour)
+                .unwrap()
+                .map(|r| r.unwrap().value)
+                .collect::<Vec<_>>();
+            assert_eq!(expected, values);
+        }
+    }
+
+    #[test]
+    fn live_only_hour3_err() {
+        let mut reader = LwjmReader::new(LWJM_LIVE_FILE, false).unwrap();
+        assert!(matches!(
+            reader.lwjm_sections(LwjmHour::Hour3),
+            Err(Grib2Error::RuntimeError(_))
+        ));
+        assert!(matches!(
+            reader.record_iter(LwjmHour::Hour3),
+            Err(Grib2Error::RuntimeError(_))
+        ));
+        // 判定の範囲外の時間を指定してもパニックしない
+        reader.has_forecast = true;
+        assert!(matches!(
+            reader.lwjm_sections(LwjmHour::Hour3),
+            Err(Grib2Error::RuntimeError(_))
+        ));
+        assert!(matches!(
+            reader.record_iter(LwjmHour::Hour3),
+            Err(Grib2Error::RuntimeError(_))
+        ));
+    }
+
+    #[test]
+    fn allow_missing_end_marker_ok() {
+        let path = std::env::temp_dir().join(format!(
+            "grib2_2_lwjm_missing_end_marker_{}",
+            std::process::id()
+        ));
+        let mut bytes = fs::read(LWJM_FILE).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        fs::write(&path, bytes).unwrap();
+        let default = LwjmReader::new(&path, true);
+        let options = ReaderOptions::new().allow_missing_end_marker(true);
+        let lenient = LwjmReader::with_options(&path, true, options);
+
+        assert!(default.is_err());
+        let mut lenient = lenient.unwrap();
+        assert!(!lenient.section8().has_end_marker());
+        let count = lenient.record_iter(LwjmHour::Hour3).unwrap().count() as u32;
+        fs::remove_file(path).unwrap();
+        assert_eq!(lenient.section3().number_of_data_points(), count);
+    }
+}
@@ -1,21 +1,29 @@
 mod fprr;
 mod fpsw;
+mod grid;
 mod lwjm;
-mod prr;
+pub mod prr;
 mod psw;
 pub(crate) mod records;
 pub mod sections;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub(crate) mod utils;
 
-use std::cmp::Ordering;
-
 use crate::Grib2Error;
 pub use fprr::{FPrrReader, FPrrValue, FPrrValueIterator};
 pub use fpsw::{FPswIndex, FPswIndexIterator, FPswReader};
+pub use grid::{DecodedGrid, Rect};
 pub use lwjm::{LwjmHour, LwjmReader, LwjmSections};
 pub use prr::PrrReader;
 pub use psw::{PswReader, PswSections, PswTank};
-pub use records::{Grib2Record, Grib2RecordIter};
+pub use records::{
+    validate_run_length, DecodeStats, Grib2Record, Grib2RecordIter, Grib2RecordIterBuilder,
+    Grib2Run, Grib2RunIter, LevelValue,
+};
+pub use utils::peek_reference_time;
+#[cfg(unix)]
+pub use utils::PositionedReader;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -52,43 +60,56 @@ impl TryFrom<u8> for ForecastHour {
     }
 }
 
-/// 座標
-///
-/// 緯度と経度は1e-6度単位で管理するため、実際の緯度と経度にするためには1e-6を乗じる。￥
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct Coordinate {
-    /// 1e-6度単位の緯度
-    pub(crate) lat: u32,
-    /// 1e-6度単位の経度
-    pub(crate) lon: u32,
+/// リーダーのオプション
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReaderOptions {
+    /// 第8節の終端マーカーが記録されていなくてもエラーにしないかを示すフラグ
+    pub(crate) allow_missing_end_marker: bool,
+    /// 未実装のテンプレートの第4節及び第5節を読み飛ばすかを示すフラグ
+    pub(crate) skip_unknown_templates: bool,
+    /// `GRIB`を探す最大のバイト数
+    pub(crate) max_leading_bytes: usize,
 }
 
-impl PartialOrd for Coordinate {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl ReaderOptions {
+    /// 既定のオプションを返す。
+    pub fn new() -> Self {
+        Self::default()
     }
-}
 
-impl Ord for Coordinate {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let result = self.lat.cmp(&other.lat);
-        if result != Ordering::Equal {
-            return result;
-        }
+    /// 第8節の終端マーカーが記録されていなくてもエラーにしないかを設定する。
+    ///
+    /// ダウンロードが途中で中断されたファイルなど、終端マーカーが記録されていないファイルから
+    /// 読み込める資料を取得する場合に`true`を設定する。このオプションは、すべてのリーダーの
+    /// `with_options`で参照される。
+    ///
+    /// このクレートは警告を出力する仕組みを持たないため、終端マーカーが記録されていなくても
+    /// 警告は出力されない。終端マーカーが記録されていたかは、読み込んだ第8節の
+    /// `has_end_marker`で確認する。
+    pub fn allow_missing_end_marker(mut self, allow: bool) -> Self {
+        self.allow_missing_end_marker = allow;
+        self
+    }
 
-        self.lon.cmp(&other.lon)
+    /// `GRIB`の前に記録されている余分なバイトを読み飛ばす最大のバイト数を設定する。
+    ///
+    /// 誤って連結されたファイルなど、`GRIB`の前に余分なバイトが記録されているファイルを読み込む
+    /// 場合に設定する。リーダーは先頭からこのバイト数まで`GRIB`を探し、見つかった位置を
+    /// `Section0::offset`で返す。既定値は0で、先頭に`GRIB`が記録されていない場合はエラーを返す。
+    pub fn max_leading_bytes(mut self, max_leading_bytes: usize) -> Self {
+        self.max_leading_bytes = max_leading_bytes;
+        self
     }
-}
 
-impl<T> From<Grib2Record<T>> for Coordinate
-where
-    T: Clone + Copy,
-{
-    fn from(value: Grib2Record<T>) -> Self {
-        Self {
-            lat: value.lat,
-            lon: value.lon,
-        }
+    /// 未実装のテンプレートの第4節及び第5節を、エラーにせず節の長さに従って読み飛ばすかを
+    /// 設定する。
+    ///
+    /// 第3節の格子系や第7節の資料のみを利用する場合に`true`を設定する。このオプションは
+    /// `Grib2Reader::with_options`で参照される。読み飛ばした節は`Section4::Skipped`または
+    /// `Section5::Skipped`として記録され、第5節を読み飛ばした場合は資料を復号できない。
+    pub fn skip_unknown_templates(mut self, skip: bool) -> Self {
+        self.skip_unknown_templates = skip;
+        self
     }
 }
 
@@ -1,12 +1,19 @@
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, Seek, SeekFrom};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Seek, SeekFrom, Write};
 use std::path::Path;
 
+use crate::export::{write_csv, DEFAULT_COORD_DECIMALS};
 use crate::readers::records::{Grib2RecordIter, Grib2RecordIterBuilder};
 use crate::readers::sections::{
-    Section0, Section1, Section2, Section3_0, Section4_50008, Section5_200u16, Section6,
-    Section7_200, Section8,
+    describe, Section0, Section1, Section2, Section3_0, Section4_50008, Section5_200u16, Section6,
+    Section7_200, Section8, SectionDescription,
 };
+#[cfg(unix)]
+use crate::readers::utils::PositionedReader;
+use crate::readers::utils::{
+    peek_reference_time, validate_no_trailing_data, validate_section_order,
+};
+use crate::readers::ReaderOptions;
 use crate::{Grib2Error, Grib2Result};
 
 /// 解析雨量ファイルリーダー
@@ -44,6 +51,20 @@ impl PrrReader {
     ///
     /// * 解析雨量リーダー
     pub fn new<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        Self::with_options(path, ReaderOptions::default())
+    }
+
+    /// オプションを指定して解析雨量ファイルを開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 解析雨量ファイルのパス
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 解析雨量リーダー
+    pub fn with_options<P: AsRef<Path>>(path: P, options: ReaderOptions) -> Grib2Result<Self> {
         let path = path.as_ref();
         if !path.is_file() {
             return Err(Grib2Error::FileDoesNotExist);
@@ -53,15 +74,17 @@ impl PrrReader {
             .open(path)
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
         let mut reader = BufReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
+        let section0 =
+            Section0::from_reader_with_max_offset(&mut reader, options.max_leading_bytes)?;
+        validate_section_order(&mut reader)?;
         let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2;
+        let section2 = Section2::from_reader(&mut reader)?;
         let section3 = Section3_0::from_reader(&mut reader)?;
         let section4 = Section4_50008::from_reader(&mut reader)?;
         let section5 = Section5_200u16::from_reader(&mut reader)?;
         let section6 = Section6::from_reader(&mut reader)?;
         let section7 = Section7_200::from_reader(&mut reader)?;
-        let section8 = Section8::from_reader(&mut reader)?;
+        let section8 = Section8::from_reader(&mut reader, options.allow_missing_end_marker)?;
 
         Ok(Self {
             reader,
@@ -77,6 +100,26 @@ impl PrrReader {
         })
     }
 
+    /// 解析雨量ファイルを開き、第8節の後にデータが存在しないことを確認する。
+    ///
+    /// ファイルに複数のGRIB報が連結されている場合など、第8節の後にデータが存在する場合は
+    /// エラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 解析雨量ファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// * 解析雨量リーダー
+    pub fn new_strict<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        let path = path.as_ref();
+        let reader = Self::new(path)?;
+        validate_no_trailing_data(path, reader.section0())?;
+
+        Ok(reader)
+    }
+
     /// 第0節:指示節を返す。
     ///
     /// # 戻り値
@@ -158,6 +201,37 @@ impl PrrReader {
         &self.section8
     }
 
+    /// 第5節に記録されている資料表現テンプレート番号を返す。
+    ///
+    /// 資料を復号する前に、ランレングス圧縮（テンプレート5.200）で記録されているかを
+    /// 確認する場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料表現テンプレート番号
+    pub fn data_representation_number(&self) -> u16 {
+        self.section5.data_representation_template_number()
+    }
+
+    /// 第0節から第8節までのすべての節のフィールドを、節の名前と組にして返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        vec![
+            describe("第0節:指示節", &self.section0),
+            describe("第1節:識別節", &self.section1),
+            describe("第2節:地域使用節", &self.section2),
+            describe("第3節:格子系定義節", &self.section3),
+            describe("第4節:プロダクト定義節", &self.section4),
+            describe("第5節:資料表現節", &self.section5),
+            describe("第6節:ビットマップ節", &self.section6),
+            describe("第7節:資料節", &self.section7),
+            describe("第8節:終端節", &self.section8),
+        ]
+    }
+
     /// レコードを反復処理するイテレーターを返す。
     ///
     /// # 戻り値
@@ -174,7 +248,12 @@ impl PrrReader {
             .reader(&mut self.reader)
             .total_bytes(self.section7.run_length_bytes())
             .number_of_points(self.section3.number_of_data_points())
+            .grid_shape(
+                self.section3.number_of_along_lat_points(),
+                self.section3.number_of_along_lon_points(),
+            )
             .lat_max(self.section3.lat_of_first_grid_point())
+            .signed_lat(self.section3.has_southern_grid_points())
             .lon_min(self.section3.lon_of_first_grid_point())
             .lon_max(self.section3.lon_of_last_grid_point())
             .lat_inc(self.section3.j_direction_increment())
@@ -184,4 +263,1166 @@ impl PrrReader {
             .level_values(self.section5.level_values())
             .build()
     }
+
+    /// ファイルポインターを移動せずにレコードを反復処理するイテレーターを構築して、
+    /// 引数`f`に渡す。
+    ///
+    /// ランレングス圧縮符号を`FileExt::read_at`で読み込むため、`&mut self`を必要としない。
+    /// 複数のスレッドから1つのリーダーを共有して、同時にレコードを反復処理できる。
+    ///
+    /// # 引数
+    ///
+    /// * `f` - レコードを反復処理するイテレーターを受け取る関数
+    ///
+    /// # 戻り値
+    ///
+    /// * 引数`f`が返した値
+    #[cfg(unix)]
+    pub fn with_positioned_record_iter<F, T>(&self, f: F) -> Grib2Result<T>
+    where
+        F: FnOnce(Grib2RecordIter<'_, PositionedReader<'_>, u16>) -> T,
+    {
+        let mut reader = BufReader::new(PositionedReader::new(
+            self.reader.get_ref(),
+            self.section7.run_length_position() as u64,
+        ));
+        let iter = Grib2RecordIterBuilder::new()
+            .reader(&mut reader)
+            .total_bytes(self.section7.run_length_bytes())
+            .number_of_points(self.section3.number_of_data_points())
+            .grid_shape(
+                self.section3.number_of_along_lat_points(),
+                self.section3.number_of_along_lon_points(),
+            )
+            .lat_max(self.section3.lat_of_first_grid_point())
+            .signed_lat(self.section3.has_southern_grid_points())
+            .lon_min(self.section3.lon_of_first_grid_point())
+            .lon_max(self.section3.lon_of_last_grid_point())
+            .lat_inc(self.section3.j_direction_increment())
+            .lon_inc(self.section3.i_direction_increment())
+            .nbit(self.section5.bits_per_value() as u16)
+            .maxv(self.section5.max_level_value())
+            .level_values(self.section5.level_values())
+            .build()?;
+
+        Ok(f(iter))
+    }
+
+    /// 指定した緯度に最も近い緯線上の格子点の値を、経度の昇順に返す。
+    ///
+    /// ランレングス圧縮符号は指定した緯線を含む行まで展開し、それ以降は展開しない。
+    /// 指定した緯度が格子の範囲外の場合は、最も近い端の行を返す。
+    /// 第3節のj方向の増分が0の場合は、行を特定できないためエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `lat` - 南緯を負の値とする1e-6度単位の緯度
+    ///
+    /// # 戻り値
+    ///
+    /// * 緯線上の格子点の値を記録したベクター
+    pub fn row_at(&mut self, lat: i32) -> Grib2Result<Vec<Option<u16>>> {
+        let lat_max = self.section3.lat_of_first_grid_point_i32() as i64;
+        let lat_inc = self.section3.j_direction_increment() as i64;
+        if lat_inc == 0 {
+            return Err(Grib2Error::ReadError(
+                "第3節:j方向の増分が0のため、緯度から行を特定できません。".into(),
+            ));
+        }
+        let rows = self.section3.number_of_along_lon_points();
+        let columns = self.section3.number_of_along_lat_points();
+        // 指定した緯度に最も近い行のインデックスを計算
+        let row = ((lat_max - lat as i64).max(0) + lat_inc / 2) / lat_inc;
+        let row = row.min(rows.saturating_sub(1) as i64) as u32;
+        let start = row as u64 * columns as u64;
+        let end = start + columns as u64;
+
+        let mut values = Vec::with_capacity(columns as usize);
+        let mut position = 0u64;
+        for run in self.record_iter()?.runs() {
+            let run = run?;
+            let run_end = position + run.count as u64;
+            if start < run_end {
+                let count = run_end.min(end) - position.max(start);
+                values.resize(values.len() + count as usize, run.value);
+            }
+            position = run_end;
+            if end <= position {
+                break;
+            }
+        }
+        if values.len() != columns as usize {
+            return Err(Grib2Error::Unexpected(
+                format!(
+                    "{}行目の格子点数({})が緯線に沿った格子点数({})と一致しません。",
+                    row,
+                    values.len(),
+                    columns
+                )
+                .into(),
+            ));
+        }
+
+        Ok(values)
+    }
+
+    /// 指定した座標に最も近い格子点の値を返す。
+    ///
+    /// 格子全体をメモリに展開せず、ランレングス圧縮符号を1回走査して、指定した座標に
+    /// 対応する格子点の値のみを収集する。格子の範囲外の座標に対しては`None`を返す。
+    /// 第3節のi方向またはj方向の増分が0の場合は、格子点を特定できないためエラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `coords` - 南緯を負の値とする1e-6度単位の緯度と、1e-6度単位の経度を格納したタプルの
+    ///   スライス
+    ///
+    /// # 戻り値
+    ///
+    /// * `coords`と同じ順番で格子点の値を格納したベクター
+    pub fn values_at(&mut self, coords: &[(i32, u32)]) -> Grib2Result<Vec<Option<u16>>> {
+        let lat_max = self.section3.lat_of_first_grid_point_i32() as i64;
+        let lon_min = self.section3.lon_of_first_grid_point() as i64;
+        let lat_inc = self.section3.j_direction_increment() as i64;
+        let lon_inc = self.section3.i_direction_increment() as i64;
+        if lat_inc == 0 || lon_inc == 0 {
+            return Err(Grib2Error::ReadError(
+                "第3節:i方向またはj方向の増分が0のため、座標から格子点を特定できません。".into(),
+            ));
+        }
+        let rows = self.section3.number_of_along_lon_points() as i64;
+        let columns = self.section3.number_of_along_lat_points() as i64;
+        // 座標ごとに、格子点のインデックスと`coords`内のインデックスを、格子点の走査順に並べる
+        let mut targets = coords
+            .iter()
+            .enumerate()
+            .map(|(i, &(lat, lon))| (lat_max - lat as i64, lon as i64 - lon_min, i))
+            .filter(|&(lat_offset, lon_offset, _)| 0 <= lat_offset && 0 <= lon_offset)
+            .map(|(lat_offset, lon_offset, i)| {
+                let row = (lat_offset + lat_inc / 2) / lat_inc;
+                let column = (lon_offset + lon_inc / 2) / lon_inc;
+                (row, column, i)
+            })
+            .filter(|&(row, column, _)| row < rows && column < columns)
+            .map(|(row, column, i)| (row as u64 * columns as u64 + column as u64, i))
+            .collect::<Vec<_>>();
+        targets.sort_unstable();
+
+        let mut values = vec![None; coords.len()];
+        let mut targets = targets.into_iter().peekable();
+        let mut position = 0u64;
+        for run in self.record_iter()?.runs() {
+            if targets.peek().is_none() {
+                break;
+            }
+            let run = run?;
+            position += run.count as u64;
+            while let Some(&(index, i)) = targets.peek() {
+                if position <= index {
+                    break;
+                }
+                values[i] = run.value;
+                targets.next();
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// 格子を、レベル値とそのレベル値が連続する格子点の数の組の並びとして返す。
+    ///
+    /// 組は格子点の走査順に並び、隣接する組のレベル値は必ず異なる。ランレングス圧縮符号の
+    /// 複数のセットに分割されて記録されている同じレベル値の並びは、1つの組にまとめる。
+    /// 格子を再びランレングス圧縮する場合などに利用できる。
+    ///
+    /// # 戻り値
+    ///
+    /// * レベル値と格子点の数の組を格納したベクター
+    pub fn to_run_length(&mut self) -> Grib2Result<Vec<(u16, u32)>> {
+        let mut pairs: Vec<(u16, u32)> = vec![];
+        for run in self.record_iter()?.runs() {
+            let run = run?;
+            match pairs.last_mut() {
+                Some((level, count)) if *level == run.level => *count += run.count,
+                _ => pairs.push((run.level, run.count)),
+            }
+        }
+
+        Ok(pairs)
+    }
+
+    /// ランレングス圧縮符号を最後まで展開して、第3節に記録されている資料点数と同じ数の格子点に
+    /// 展開されるか確認する。
+    ///
+    /// 格子点ごとのレコードを作成せず、同じ値が連続する格子点の並びごとに展開するため、
+    /// 多数のファイルが壊れていないかを確認する場合に利用できる。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料点数と同じ数の格子点に展開される場合は`()`
+    pub fn verify(&mut self) -> Grib2Result<()> {
+        for run in self.record_iter()?.runs() {
+            run?;
+        }
+
+        Ok(())
+    }
+
+    /// レベル値ごとの格子点の数を返す。
+    ///
+    /// ランレングス圧縮符号を格子点ごとに展開せず、同じレベル値が連続する格子点の数をまとめて
+    /// 加算する。返すベクターの要素数は今回の圧縮に用いたレベルの最大値に1を加えた数で、
+    /// インデックスがレベル値に対応する。レベル値0は値を持たない格子点の数である。
+    ///
+    /// # 戻り値
+    ///
+    /// * レベル値ごとの格子点の数を記録したベクター
+    pub fn level_histogram(&mut self) -> Grib2Result<Vec<u32>> {
+        let maxv = self.section5.max_level_value();
+        let mut histogram = vec![0u32; maxv as usize + 1];
+        for run in self.record_iter()?.runs() {
+            let run = run?;
+            let bin = histogram.get_mut(run.level as usize).ok_or_else(|| {
+                Grib2Error::Unexpected(
+                    format!(
+                        "レベル値({})が今回の圧縮に用いたレベルの最大値({})を超えています。",
+                        run.level, maxv
+                    )
+                    .into(),
+                )
+            })?;
+            *bin += run.count;
+        }
+
+        Ok(histogram)
+    }
+
+    /// 値を持つ格子点を、散布図の描画ライブラリにそのまま渡せる形式で返す。
+    ///
+    /// 座標は1e-6度単位から度単位に変換し、値には第5節のデータ代表値の尺度因子を適用する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 度単位の経度、度単位の緯度及び物理値を格納したタプルのベクター
+    pub fn scatter_points(&mut self) -> Grib2Result<Vec<(f64, f64, f64)>> {
+        let scale = self.section5.decimal_scale();
+        let mut points = vec![];
+        for record in self.record_iter()? {
+            let record = record?;
+            if let Some(value) = record.value {
+                points.push((
+                    record.lon_i32() as f64 / 1e6,
+                    record.lat as f64 / 1e6,
+                    value as f64 * scale,
+                ));
+            }
+        }
+
+        Ok(points)
+    }
+
+    /// 緯度及び経度方向に`stride`個ごとに間引いた格子点を、`lon,lat,value`形式のCSVとして
+    /// 書き込む。
+    ///
+    /// 北西端の格子点から、行と列のインデックスが`stride`の倍数の格子点のみを書き込むため、
+    /// 書き込む行数はおよそ資料点数を`stride`の2乗で除した数になる。格子全体の概要を
+    /// 素早く確認する場合に利用できる。間引いた格子の形状を保つため、値を持たない格子点も
+    /// 値を空欄にして書き込む。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - ライター
+    /// * `stride` - 間引く間隔（1以上）
+    pub fn write_preview_csv<W: Write>(&mut self, writer: &mut W, stride: u32) -> Grib2Result<()> {
+        if stride == 0 {
+            return Err(Grib2Error::RuntimeError(
+                "間引く間隔は1以上でなければなりません。".into(),
+            ));
+        }
+        writer
+            .write_all(b"lon,lat,value\n")
+            .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+        for record in self.record_iter()?.indexed() {
+            let (row, column, record) = record?;
+            if row % stride != 0 || column % stride != 0 {
+                continue;
+            }
+            let lat = record.lat as f64 / 1e6;
+            let lon = record.lon_i32() as f64 / 1e6;
+            let result = match record.value {
+                Some(value) => writer.write_fmt(format_args!(
+                    "{lon:.DEFAULT_COORD_DECIMALS$},{lat:.DEFAULT_COORD_DECIMALS$},{value}\n"
+                )),
+                None => writer.write_fmt(format_args!(
+                    "{lon:.DEFAULT_COORD_DECIMALS$},{lat:.DEFAULT_COORD_DECIMALS$},\n"
+                )),
+            };
+            result.map_err(|e| Grib2Error::Unexpected(e.into()))?;
+        }
+
+        Ok(())
+    }
+
+    /// 格子を復号したレベル値の並びから、ファイルの内容を識別するハッシュ値を計算する。
+    ///
+    /// ハッシュ値は格子点を走査した順番に並んだレベル値のみから計算するため、発表時刻などの
+    /// メタデータや、第5節のレベル別物理値が異なっても、レベル値の並びが同じであれば同じ
+    /// ハッシュ値を返す。ハッシュ関数にはFNV-1a（64ビット）を使用するため、Rustのバージョンや
+    /// 実行環境によらず同じ値を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * ファイルの内容を識別するハッシュ値
+    pub fn content_hash(&mut self) -> Grib2Result<u64> {
+        let mut hasher = Fnv1aHasher::new();
+        for run in self.record_iter()?.runs() {
+            let run = run?;
+            let level = run.level.to_be_bytes();
+            for _ in 0..run.count {
+                hasher.write(&level);
+            }
+        }
+
+        Ok(hasher.finish())
+    }
+}
+
+/// ディレクトリに格納されている解析雨量ファイルのうち、資料の参照時刻が最も新しいファイルを開く。
+///
+/// 資料の参照時刻は、第0節及び第1節のみを読み込んで取得する。第0節及び第1節を読み込めない
+/// ファイルは無視する。また、土壌雨量指数ファイルなど解析雨量リーダーで開けないファイルも無視して、
+/// 資料の参照時刻が次に新しいファイルを開く。
+///
+/// # 引数
+///
+/// * `dir` - 解析雨量ファイルを格納したディレクトリのパス
+///
+/// # 戻り値
+///
+/// * 解析雨量リーダー
+pub fn open_latest<P: AsRef<Path>>(dir: P) -> Grib2Result<PrrReader> {
+    let dir = dir.as_ref();
+    let entries = fs::read_dir(dir).map_err(|e| Grib2Error::Unexpected(e.into()))?;
+    let mut candidates = vec![];
+    for entry in entries {
+        let path = entry.map_err(|e| Grib2Error::Unexpected(e.into()))?.path();
+        if !path.is_file() {
+            continue;
+        }
+        if let Ok(referenced_at) = peek_reference_time(&path) {
+            candidates.push((referenced_at, path));
+        }
+    }
+    // 資料の参照時刻が新しい順に、解析雨量リーダーで開けるファイルを探す
+    candidates.sort_by_key(|(referenced_at, _)| std::cmp::Reverse(*referenced_at));
+    candidates
+        .into_iter()
+        .find_map(|(_, path)| PrrReader::new(path).ok())
+        .ok_or_else(|| {
+            Grib2Error::RuntimeError(
+                format!("{}に解析雨量ファイルが存在しません。", dir.display()).into(),
+            )
+        })
+}
+
+/// 解析雨量ファイルを開き、値を持つ格子点を`lon,lat,value`形式のCSVファイルに書き込む。
+///
+/// CSVの形式は`export::write_csv`と同じで、座標は小数点以下`DEFAULT_COORD_DECIMALS`桁で
+/// 出力する。出力先のファイルが存在する場合は上書きする。
+///
+/// # 引数
+///
+/// * `src_path` - 解析雨量ファイルのパス
+/// * `dst_path` - 出力するCSVファイルのパス
+pub fn convert_to_csv<P, Q>(src_path: P, dst_path: Q) -> Grib2Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = PrrReader::new(src_path)?;
+    let file = File::create(dst_path).map_err(|e| Grib2Error::Unexpected(e.into()))?;
+    let mut writer = BufWriter::new(file);
+    write_csv(reader.record_iter()?, &mut writer, DEFAULT_COORD_DECIMALS)?;
+
+    writer.flush().map_err(|e| Grib2Error::Unexpected(e.into()))
+}
+
+/// 解析雨量ファイルの並びが、アニメーションなどで連続して扱える系列であるか確認する。
+///
+/// すべてのファイルの格子が最初のファイルの格子と一致し、資料の参照時刻が単調に増加し、
+/// かつ等間隔であることを確認する。ファイルは第0節から第8節までのみを読み込み、資料を
+/// 復号しない。ファイルが1つ以下の場合は、常に系列とみなす。
+///
+/// # 引数
+///
+/// * `paths` - 資料の参照時刻の昇順に並べた解析雨量ファイルのパス
+///
+/// # 戻り値
+///
+/// * 連続した系列の場合は`()`
+/// * 格子が一致しない場合は`Grib2Error::GridMismatch`
+/// * 資料の参照時刻が等間隔に増加していない場合は`Grib2Error::RuntimeError`
+pub fn check_sequence<P: AsRef<Path>>(paths: &[P]) -> Grib2Result<()> {
+    let readers = paths
+        .iter()
+        .map(PrrReader::new)
+        .collect::<Grib2Result<Vec<_>>>()?;
+    let Some(first) = readers.first() else {
+        return Ok(());
+    };
+    for reader in &readers[1..] {
+        first.section3().validate_same_grid(reader.section3())?;
+    }
+    let referenced_ats = readers
+        .iter()
+        .map(|reader| reader.section1().referenced_at())
+        .collect::<Vec<_>>();
+    let mut interval = None;
+    for (i, pair) in referenced_ats.windows(2).enumerate() {
+        let current = pair[1] - pair[0];
+        if !current.is_positive() {
+            return Err(Grib2Error::RuntimeError(
+                format!(
+                    "{}番目のファイルの資料の参照時刻({})が、直前のファイルの資料の参照時刻({})より後ではありません。",
+                    i + 2,
+                    pair[1],
+                    pair[0]
+                )
+                .into(),
+            ));
+        }
+        match interval {
+            None => interval = Some(current),
+            Some(expected) if expected != current => {
+                return Err(Grib2Error::RuntimeError(
+                    format!(
+                        "{}番目のファイルの資料の参照時刻の間隔({current})が、最初の間隔({expected})と一致しません。",
+                        i + 2
+                    )
+                    .into(),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// FNV-1a（64ビット）ハッシュ関数
+struct Fnv1aHasher(u64);
+
+impl Fnv1aHasher {
+    /// FNV-1aのオフセット基底
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    /// FNV-1aの素数
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::{check_sequence, convert_to_csv, open_latest, PrrReader};
+    use crate::export::{write_csv, DEFAULT_COORD_DECIMALS};
+    use crate::readers::utils::i32_to_sign_magnitude;
+    use crate::readers::ReaderOptions;
+    use crate::Grib2Error;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    const PSW_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807170000_SRF_GPV_Ggis1km_Psw_Aper10min_ANAL_grib2.bin"
+    );
+
+    /// 解析雨量ファイルを一時ディレクトリに複製して、そのパスを返す。
+    fn copy_prr_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("grib2_2_prr_{}_{name}", std::process::id()));
+        fs::copy(PRR_FILE, &path).unwrap();
+        path
+    }
+
+    #[test]
+    fn open_latest_ok() {
+        let dir = std::env::temp_dir().join(format!("grib2_2_prr_latest_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // 資料の参照時刻（第1節の13バイト目から7バイト）の時を1時間進めたファイルを作成
+        let mut bytes = fs::read(PRR_FILE).unwrap();
+        bytes[16 + 12 + 4] += 1;
+        fs::write(dir.join("newer.bin"), bytes).unwrap();
+        fs::copy(PRR_FILE, dir.join("older.bin")).unwrap();
+        fs::write(dir.join("readme.txt"), "not a grib2 file").unwrap();
+        // 解析雨量ファイルより資料の参照時刻が新しい土壌雨量指数ファイルは無視
+        fs::copy(PSW_FILE, dir.join("psw.bin")).unwrap();
+
+        let reader = open_latest(&dir);
+        let expected =
+            PrrReader::new(PRR_FILE).unwrap().section1().referenced_at() + time::Duration::hours(1);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(expected, reader.unwrap().section1().referenced_at());
+    }
+
+    #[test]
+    fn open_latest_without_files_err() {
+        let dir = std::env::temp_dir().join(format!("grib2_2_prr_empty_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let result = open_latest(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn scatter_points_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let scale = reader.section5().decimal_scale();
+        let present = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .filter(|r| r.value.is_some())
+            .collect::<Vec<_>>();
+        let points = reader.scatter_points().unwrap();
+        assert_eq!(present.len(), points.len());
+
+        let record = present[present.len() / 2];
+        let (lon, lat, value) = points[present.len() / 2];
+        assert_eq!(record.lon_i32() as f64 / 1e6, lon);
+        assert_eq!(record.lat as f64 / 1e6, lat);
+        assert_eq!(record.value.unwrap() as f64 * scale, value);
+    }
+
+    #[test]
+    fn row_at_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let columns = reader.section3().number_of_along_lat_points() as usize;
+        let lat = reader.section3().lat_of_first_grid_point_i32()
+            - reader.section3().j_direction_increment() as i32 * 1_000;
+        let row = reader.row_at(lat).unwrap();
+        assert_eq!(columns, row.len());
+
+        // 格子点ごとに反復処理した結果と一致することを確認
+        let expected = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .filter(|r| r.lat == lat)
+            .map(|r| r.value)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, row);
+    }
+
+    #[test]
+    fn row_at_with_zero_lat_inc_err() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start3 = 16 + reader.section1().section_bytes();
+        // 第3節のj方向の増分を0にしたファイルを作成
+        let path = copy_prr_file("zero_lat_inc.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[start3 + 67..start3 + 71].copy_from_slice(&0u32.to_be_bytes());
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PrrReader::new(&path).unwrap();
+        let result = reader.row_at(35_000_000);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(0, reader.section3().j_direction_increment());
+        assert!(matches!(result, Err(Grib2Error::ReadError(_))));
+    }
+
+    #[test]
+    fn values_at_with_zero_increment_err() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start3 = 16 + reader.section1().section_bytes();
+        for (name, range) in [
+            ("zero_lon_inc.bin", start3 + 63..start3 + 67),
+            ("zero_lat_inc_values_at.bin", start3 + 67..start3 + 71),
+        ] {
+            let path = copy_prr_file(name);
+            let mut bytes = fs::read(&path).unwrap();
+            bytes[range].copy_from_slice(&0u32.to_be_bytes());
+            fs::write(&path, bytes).unwrap();
+            let mut reader = PrrReader::new(&path).unwrap();
+            let result = reader.values_at(&[(35_000_000, 139_000_000)]);
+            fs::remove_file(path).unwrap();
+
+            assert!(matches!(result, Err(Grib2Error::ReadError(_))));
+        }
+    }
+
+    #[test]
+    fn values_at_extreme_coords_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let values = reader
+            .values_at(&[(i32::MIN, 0), (i32::MAX, u32::MAX), (i32::MIN, u32::MAX)])
+            .unwrap();
+        assert_eq!(vec![None, None, None], values);
+    }
+
+    #[test]
+    fn row_at_out_of_range_returns_edge_row() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let columns = reader.section3().number_of_along_lat_points() as usize;
+        assert_eq!(columns, reader.row_at(i32::MAX).unwrap().len());
+        assert_eq!(columns, reader.row_at(i32::MIN).unwrap().len());
+    }
+
+    #[test]
+    fn values_at_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let records = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        // 値を持つ格子点と値を持たない格子点を、走査順と異なる順番で指定
+        let mut samples = records.iter().step_by(99_991).rev().collect::<Vec<_>>();
+        samples.extend(records.iter().filter(|r| r.value.is_some()).take(3));
+        // 南東端の格子点
+        samples.push(records.last().unwrap());
+        let mut coords = samples.iter().map(|r| (r.lat, r.lon)).collect::<Vec<_>>();
+        // 格子の範囲外の座標
+        coords.push((0, 0));
+        let values = reader.values_at(&coords).unwrap();
+        assert_eq!(coords.len(), values.len());
+
+        // 格子点ごとに値を取得した結果と一致することを確認
+        for (record, value) in samples.iter().zip(&values) {
+            assert_eq!(record.value, *value);
+        }
+        assert!(samples.iter().any(|r| r.value.is_some()));
+        assert_eq!(None, *values.last().unwrap());
+    }
+
+    #[test]
+    fn to_run_length_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let pairs = reader.to_run_length().unwrap();
+        assert!(pairs.windows(2).all(|w| w[0].0 != w[1].0));
+
+        // レベル値と格子点の数の組を展開して、格子点ごとに反復処理した値を再現できることを確認
+        let level_values = reader.section5().level_values().to_vec();
+        let expanded = pairs
+            .iter()
+            .flat_map(|&(level, count)| std::iter::repeat_n(level, count as usize))
+            .map(|level| (0 < level).then(|| level_values[level as usize - 1]))
+            .collect::<Vec<_>>();
+        let expected = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, expanded);
+    }
+
+    #[test]
+    fn read_file_with_section2_ok() {
+        // 第1節の直後に8バイトの第2節を挿入し、第0節のGRIB報全体の長さを更新
+        let mut bytes = fs::read(PRR_FILE).unwrap();
+        bytes.splice(16 + 21..16 + 21, [0, 0, 0, 8, 2, 0xAA, 0xBB, 0xCC]);
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        let path =
+            std::env::temp_dir().join(format!("grib2_2_prr_{}_section2", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+
+        let reader = PrrReader::new(&path);
+        fs::remove_file(&path).unwrap();
+        let mut reader = reader.unwrap();
+        let mut expected = PrrReader::new(PRR_FILE).unwrap();
+        assert!(reader.section2().is_present());
+        assert_eq!(8, reader.section2().section_bytes());
+        assert!(!expected.section2().is_present());
+        assert_eq!(
+            expected.to_run_length().unwrap(),
+            reader.to_run_length().unwrap()
+        );
+    }
+
+    #[test]
+    fn new_strict_ok() {
+        assert!(PrrReader::new_strict(PRR_FILE).is_ok());
+    }
+
+    #[test]
+    fn new_strict_with_trailing_data_err() {
+        let path = copy_prr_file("trailing_data.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.extend_from_slice(b"GRIB");
+        fs::write(&path, bytes).unwrap();
+        let lenient = PrrReader::new(&path);
+        let strict = PrrReader::new_strict(&path);
+        fs::remove_file(path).unwrap();
+
+        assert!(lenient.is_ok());
+        assert!(matches!(strict, Err(Grib2Error::ReadError(_))));
+    }
+
+    #[test]
+    fn with_options_skips_leading_junk() {
+        let path = copy_prr_file("leading_junk.bin");
+        let mut bytes = b"junkjunk".to_vec();
+        bytes.extend(fs::read(&path).unwrap());
+        fs::write(&path, bytes).unwrap();
+        let default = PrrReader::new(&path);
+        let options = ReaderOptions::new().max_leading_bytes(8);
+        let reader = PrrReader::with_options(&path, options);
+        fs::remove_file(path).unwrap();
+
+        assert!(default.is_err());
+        let mut reader = reader.unwrap();
+        let mut expected = PrrReader::new(PRR_FILE).unwrap();
+        assert_eq!(8, reader.section0().offset());
+        // ランレングス圧縮符号列の開始位置は、余分なバイトの分だけずれる
+        assert_eq!(
+            expected.section7().run_length_position() + 8,
+            reader.section7().run_length_position()
+        );
+        let values = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        let expected = expected
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        assert_eq!(expected, values);
+    }
+
+    #[test]
+    fn mismatched_grid_shape_err() {
+        // 第3節の経線に沿った格子点数（Nj）を1つ増やしたファイルを作成
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start3 = 16 + reader.section1().section_bytes();
+        let nj = reader.section3().number_of_along_lon_points() + 1;
+        let path = copy_prr_file("mismatched_grid_shape.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[start3 + 34..start3 + 38].copy_from_slice(&nj.to_be_bytes());
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PrrReader::new(&path).unwrap();
+        let result = reader.record_iter().map(|_| ());
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(nj, reader.section3().number_of_along_lon_points());
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "number_of_points"
+            })
+        ));
+    }
+
+    #[test]
+    fn southern_hemisphere_write_csv_ok() {
+        // 第3節の最初と最後の格子点の緯度を50度南に移動したファイルを作成
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start3 = 16 + reader.section1().section_bytes();
+        let lat_first = reader.section3().lat_of_first_grid_point_i32() - 50_000_000;
+        let lat_last = reader.section3().lat_of_last_grid_point_i32() - 50_000_000;
+        let path = copy_prr_file("southern_hemisphere.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[start3 + 46..start3 + 50]
+            .copy_from_slice(&i32_to_sign_magnitude(lat_first).to_be_bytes());
+        bytes[start3 + 55..start3 + 59]
+            .copy_from_slice(&i32_to_sign_magnitude(lat_last).to_be_bytes());
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PrrReader::new(&path).unwrap();
+        let mut buf = vec![];
+        write_csv(
+            reader.record_iter().unwrap(),
+            &mut buf,
+            DEFAULT_COORD_DECIMALS,
+        )
+        .unwrap();
+        // 南緯の座標でも、格子点ごとに反復処理した結果と一致することを確認
+        let records = reader
+            .record_iter()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        let sample = records.iter().find(|r| r.value.is_some()).unwrap();
+        let values = reader.values_at(&[(sample.lat, sample.lon)]).unwrap();
+        let row = reader.row_at(sample.lat).unwrap();
+        let expected = records
+            .iter()
+            .filter(|r| r.lat == sample.lat)
+            .map(|r| r.value)
+            .collect::<Vec<_>>();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(vec![sample.value], values);
+        assert_eq!(expected, row);
+        assert!(reader.section3().has_southern_grid_points());
+        let csv = String::from_utf8(buf).unwrap();
+        let lats = csv
+            .lines()
+            .skip(1)
+            .map(|l| l.split(',').nth(1).unwrap().parse::<f64>().unwrap())
+            .collect::<Vec<_>>();
+        assert!(!lats.is_empty());
+        let (min, max) = (lat_last as f64 / 1e6, lat_first as f64 / 1e6);
+        assert!(lats
+            .iter()
+            .all(|&lat| min <= lat && lat <= max && lat < 0.0));
+    }
+
+    #[test]
+    fn swapped_sections_err() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start4 = 16
+            + reader.section1().section_bytes()
+            + reader.section2().section_bytes()
+            + reader.section3().section_bytes();
+        let start5 = start4 + reader.section4().section_bytes();
+        let end5 = start5 + reader.section5().section_bytes();
+        // 第4節と第5節を入れ替えたファイルを作成
+        let path = copy_prr_file("swapped_sections.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        let section4 = bytes[start4..start5].to_vec();
+        bytes.copy_within(start5..end5, start4);
+        let new_start4 = start4 + (end5 - start5);
+        bytes[new_start4..end5].copy_from_slice(&section4);
+        fs::write(&path, bytes).unwrap();
+        let result = PrrReader::new(&path);
+        fs::remove_file(path).unwrap();
+
+        match result {
+            Err(Grib2Error::ReadError(message)) => {
+                assert!(message.contains("第5節の後に第4節"), "{message}")
+            }
+            _ => panic!("節の順序が不正なファイルを読み込めてしまいました。"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn with_positioned_record_iter_concurrently_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let summarize = || {
+            reader
+                .with_positioned_record_iter(|iter| {
+                    iter.runs()
+                        .map(|r| r.map(|r| (r.count, r.level as u64 * r.count as u64)))
+                        .try_fold((0u32, 0u64), |acc, r| r.map(|r| (acc.0 + r.0, acc.1 + r.1)))
+                })
+                .unwrap()
+                .unwrap()
+        };
+        let (first, second) = std::thread::scope(|s| {
+            let first = s.spawn(summarize);
+            let second = s.spawn(summarize);
+            (first.join().unwrap(), second.join().unwrap())
+        });
+
+        assert_eq!(first, second);
+        assert_eq!(reader.section3().number_of_data_points(), first.0);
+    }
+
+    #[test]
+    fn level_histogram_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let histogram = reader.level_histogram().unwrap();
+
+        assert_eq!(
+            reader.section5().max_level_value() as usize + 1,
+            histogram.len()
+        );
+        assert_eq!(
+            reader.section3().number_of_data_points(),
+            histogram.iter().sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn write_preview_csv_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let stride = 10;
+        let mut buf = vec![];
+        reader.write_preview_csv(&mut buf, stride).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(Some("lon,lat,value"), lines.next());
+        let rows = reader.section3().number_of_along_lon_points();
+        let columns = reader.section3().number_of_along_lat_points();
+        let expected = rows.div_ceil(stride) * columns.div_ceil(stride);
+        assert_eq!(expected as usize, lines.count());
+        // 書き込んだ行数は、資料点数を間隔の2乗で除した数に近い
+        let approx = reader.section3().number_of_data_points() / (stride * stride);
+        assert!(expected.abs_diff(approx) < approx / 100);
+    }
+
+    #[test]
+    fn write_preview_csv_with_zero_stride_err() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        assert!(reader.write_preview_csv(&mut vec![], 0).is_err());
+    }
+
+    #[test]
+    fn data_representation_number_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        assert_eq!(200, reader.data_representation_number());
+
+        // 第5節の資料表現テンプレート番号を書き換えたファイルを作成
+        let path = copy_prr_file("data_representation_number.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        let mut position = 16;
+        while bytes[position + 4] != 5 {
+            position +=
+                u32::from_be_bytes(bytes[position..position + 4].try_into().unwrap()) as usize;
+        }
+        bytes[position + 9..position + 11].copy_from_slice(&201u16.to_be_bytes());
+        fs::write(&path, bytes).unwrap();
+        let reader = PrrReader::new(&path);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(201, reader.unwrap().data_representation_number());
+    }
+
+    #[test]
+    fn convert_to_csv_ok() {
+        let path =
+            std::env::temp_dir().join(format!("grib2_2_prr_{}_convert.csv", std::process::id()));
+        convert_to_csv(PRR_FILE, &path).unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let mut lines = csv.lines();
+        assert_eq!(Some("lon,lat,value"), lines.next());
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        let present = reader
+            .record_iter()
+            .unwrap()
+            .filter(|r| r.as_ref().unwrap().value.is_some())
+            .count();
+        assert_eq!(present, lines.count());
+    }
+
+    /// 資料の参照時刻の時を`hours`時間進めた解析雨量ファイルを作成して、そのパスを返す。
+    fn shifted_prr_file(name: &str, hours: u8) -> PathBuf {
+        let path = copy_prr_file(name);
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[16 + 12 + 4] += hours;
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn check_sequence_ok() {
+        let paths = [
+            shifted_prr_file("sequence_ok0.bin", 0),
+            shifted_prr_file("sequence_ok1.bin", 1),
+            shifted_prr_file("sequence_ok2.bin", 2),
+        ];
+        let result = check_sequence(&paths);
+        let single = check_sequence(&paths[..1]);
+        paths.iter().for_each(|path| fs::remove_file(path).unwrap());
+
+        assert!(result.is_ok());
+        assert!(single.is_ok());
+        assert!(check_sequence::<&str>(&[]).is_ok());
+    }
+
+    #[test]
+    fn check_sequence_with_uneven_interval_err() {
+        let paths = [
+            shifted_prr_file("sequence_uneven0.bin", 0),
+            shifted_prr_file("sequence_uneven1.bin", 1),
+            shifted_prr_file("sequence_uneven3.bin", 3),
+        ];
+        let uneven = check_sequence(&paths);
+        let reversed = check_sequence(&[&paths[1], &paths[0]]);
+        paths.iter().for_each(|path| fs::remove_file(path).unwrap());
+
+        assert!(matches!(uneven, Err(Grib2Error::RuntimeError(_))));
+        assert!(matches!(reversed, Err(Grib2Error::RuntimeError(_))));
+    }
+
+    #[test]
+    fn check_sequence_with_different_grid_err() {
+        // 第3節の最初の格子点の緯度（47-50オクテット）を変更したファイルを作成
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start = 16 + reader.section1().section_bytes();
+        let path = shifted_prr_file("sequence_grid.bin", 1);
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[start + 49] ^= 1;
+        fs::write(&path, bytes).unwrap();
+        let result = check_sequence(&[PathBuf::from(PRR_FILE), path.clone()]);
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "lat_of_first_grid_point"
+            })
+        ));
+    }
+
+    #[test]
+    fn verify_ok() {
+        let mut reader = PrrReader::new(PRR_FILE).unwrap();
+        assert!(reader.verify().is_ok());
+    }
+
+    #[test]
+    fn verify_truncated_run_length_err() {
+        // 第7節のランレングス圧縮符号列の末尾を切り詰めたファイルを作成
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let section7 = reader.section7();
+        let start = section7.run_length_position() - 5;
+        let end = section7.run_length_position() + section7.run_length_bytes();
+        let truncated = 16;
+        let path = copy_prr_file("verify_truncated.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.drain(end - truncated..end);
+        let section_bytes = (section7.section_bytes() - truncated) as u32;
+        bytes[start..start + 4].copy_from_slice(&section_bytes.to_be_bytes());
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PrrReader::new(&path).unwrap();
+        let result = reader.verify();
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(result, Err(Grib2Error::Unexpected(_))));
+    }
+
+    #[test]
+    fn empty_section7_is_all_missing() {
+        // 第7節のランレングス圧縮符号列をすべて削除したファイルを作成
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let section7 = reader.section7();
+        let start = section7.run_length_position() - 5;
+        let end = section7.run_length_position() + section7.run_length_bytes();
+        let path = copy_prr_file("empty_section7.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.drain(start + 5..end);
+        bytes[start..start + 4].copy_from_slice(&5u32.to_be_bytes());
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PrrReader::new(&path).unwrap();
+        let verified = reader.verify();
+        let histogram = reader.level_histogram();
+        fs::remove_file(path).unwrap();
+
+        assert!(verified.is_ok());
+        let histogram = histogram.unwrap();
+        assert_eq!(reader.section3().number_of_data_points(), histogram[0]);
+        assert_eq!(0, histogram[1..].iter().sum::<u32>());
+    }
+
+    #[test]
+    fn describe_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let descriptions = reader.describe();
+        assert_eq!(9, descriptions.len());
+        assert_eq!("第0節:指示節", descriptions[0].0);
+        assert!(descriptions[1].1.iter().any(|(name, _)| *name == "center"));
+        assert_eq!("第8節:終端節", descriptions[8].0);
+    }
+
+    #[test]
+    fn allow_missing_end_marker_ok() {
+        let path = copy_prr_file("missing_end_marker.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 4);
+        fs::write(&path, bytes).unwrap();
+        let default = PrrReader::new(&path);
+        let options = ReaderOptions::new().allow_missing_end_marker(true);
+        let mut lenient = PrrReader::with_options(&path, options).unwrap();
+        let number_of_points = lenient
+            .record_iter()
+            .unwrap()
+            .runs()
+            .map(|r| r.unwrap().count)
+            .sum::<u32>();
+        fs::remove_file(path).unwrap();
+
+        assert!(default.is_err());
+        assert!(!lenient.section8().has_end_marker());
+        assert_eq!(lenient.section3().number_of_data_points(), number_of_points);
+    }
+
+    #[test]
+    fn content_hash_of_copies_are_equal() {
+        let copy1 = copy_prr_file("content_hash_copy1.bin");
+        let copy2 = copy_prr_file("content_hash_copy2.bin");
+        let hash1 = PrrReader::new(&copy1).unwrap().content_hash().unwrap();
+        let hash2 = PrrReader::new(&copy2).unwrap().content_hash().unwrap();
+        fs::remove_file(copy1).unwrap();
+        fs::remove_file(copy2).unwrap();
+
+        assert_eq!(hash1, hash2);
+    }
+
+    #[test]
+    fn content_hash_ignores_level_values() {
+        let original = PrrReader::new(PRR_FILE).unwrap().content_hash().unwrap();
+
+        // 第5節のレベル1の物理値を書き換えたファイルを作成
+        let path = copy_prr_file("content_hash_level_values.bin");
+        let mut bytes = fs::read(&path).unwrap();
+        let mut position = 16;
+        while bytes[position + 4] != 5 {
+            position +=
+                u32::from_be_bytes(bytes[position..position + 4].try_into().unwrap()) as usize;
+        }
+        bytes[position + 18] = bytes[position + 18].wrapping_add(1);
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PrrReader::new(&path).unwrap();
+        let level1 = reader.section5().level_values()[0];
+        let hash = reader.content_hash().unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_ne!(
+            PrrReader::new(PRR_FILE).unwrap().section5().level_values()[0],
+            level1
+        );
+        assert_eq!(original, hash);
+    }
+
+    #[test]
+    fn content_hash_of_modified_file_differs() {
+        let original = PrrReader::new(PRR_FILE).unwrap().content_hash().unwrap();
+
+        // ランレングス符号のうち、最初に現れる0以外のレベル値を別のレベル値に書き換える
+        let path = copy_prr_file("content_hash_modified.bin");
+        let reader = PrrReader::new(&path).unwrap();
+        let position = reader.section7().run_length_position();
+        let maxv = reader.section5().max_level_value() as u8;
+        drop(reader);
+        let mut bytes = fs::read(&path).unwrap();
+        let index = bytes[position..]
+            .iter()
+            .position(|b| 0 < *b && *b < maxv)
+            .unwrap()
+            + position;
+        bytes[index] += 1;
+        fs::write(&path, bytes).unwrap();
+        let modified = PrrReader::new(&path).unwrap().content_hash().unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_ne!(original, modified);
+    }
 }
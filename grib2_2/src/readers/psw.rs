@@ -1,18 +1,23 @@
-use std::fs::{File, OpenOptions};
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
+use crate::export::DEFAULT_COORD_DECIMALS;
 use crate::readers::records::{Grib2RecordIter, Grib2RecordIterBuilder};
 use crate::readers::sections::{
-    Section0, Section1, Section2, Section3_0, Section4_0, Section5_200u16, Section6, Section7_200,
-    Section8,
+    describe, Section0, Section1, Section2, Section3_0, Section4_0, Section5_200u16, Section6,
+    Section7_200, Section8, SectionDescription,
 };
+use crate::readers::utils::{
+    peek_section_number, read_bytes, validate_no_trailing_data, validate_section_order,
+};
+use crate::readers::ReaderOptions;
 use crate::{Grib2Error, Grib2Result};
 
 /// 土壌雨量指数実況値リーダー
-pub struct PswReader {
+pub struct PswReader<R = File> {
     /// ファイルリーダー
-    reader: BufReader<File>,
+    reader: BufReader<R>,
     /// 第0節:指示節
     section0: Section0,
     /// 第1節:識別節
@@ -41,6 +46,20 @@ impl PswReader {
     ///
     /// * 土壌雨量指数リーダー
     pub fn new<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        Self::with_options(path, ReaderOptions::default())
+    }
+
+    /// オプションを指定して土壌雨量指数ファイルを開く。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土壌雨量指数ファイルのパス
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 土壌雨量指数リーダー
+    pub fn with_options<P: AsRef<Path>>(path: P, options: ReaderOptions) -> Grib2Result<Self> {
         let path = path.as_ref();
         if !path.is_file() {
             return Err(Grib2Error::FileDoesNotExist);
@@ -49,17 +68,81 @@ impl PswReader {
             .read(true)
             .open(path)
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
-        let mut reader = BufReader::new(file);
-        let section0 = Section0::from_reader(&mut reader)?;
+
+        Self::from_buf_reader(BufReader::new(file), options)
+    }
+
+    /// 土壌雨量指数ファイルの内容をすべてメモリに読み込んで開く。
+    ///
+    /// ファイルは開いたときに1回だけ読み込み、以降のレコードの反復処理はメモリから読み込む。
+    /// メモリを消費する代わりに、複数のタンクのレコードを繰り返し反復処理する場合に高速に
+    /// 動作する。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土壌雨量指数ファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// * 土壌雨量指数リーダー
+    pub fn new_in_memory<P: AsRef<Path>>(path: P) -> Grib2Result<PswReader<Cursor<Vec<u8>>>> {
+        let path = path.as_ref();
+        if !path.is_file() {
+            return Err(Grib2Error::FileDoesNotExist);
+        }
+        let bytes = fs::read(path).map_err(|e| Grib2Error::Unexpected(e.into()))?;
+
+        PswReader::from_buf_reader(BufReader::new(Cursor::new(bytes)), ReaderOptions::default())
+    }
+
+    /// 土壌雨量指数ファイルを開き、第8節の後にデータが存在しないことを確認する。
+    ///
+    /// ファイルに複数のGRIB報が連結されている場合など、第8節の後にデータが存在する場合は
+    /// エラーを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `path` - 土壌雨量指数ファイルのパス
+    ///
+    /// # 戻り値
+    ///
+    /// * 土壌雨量指数リーダー
+    pub fn new_strict<P: AsRef<Path>>(path: P) -> Grib2Result<Self> {
+        let path = path.as_ref();
+        let reader = Self::new(path)?;
+        validate_no_trailing_data(path, reader.section0())?;
+
+        Ok(reader)
+    }
+}
+
+impl<R> PswReader<R>
+where
+    R: Read + Seek,
+{
+    /// 開かれているリーダーから土壌雨量指数を読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 土壌雨量指数を読み込むリーダー
+    /// * `options` - リーダーのオプション
+    ///
+    /// # 戻り値
+    ///
+    /// * 土壌雨量指数リーダー
+    fn from_buf_reader(mut reader: BufReader<R>, options: ReaderOptions) -> Grib2Result<Self> {
+        let section0 =
+            Section0::from_reader_with_max_offset(&mut reader, options.max_leading_bytes)?;
+        validate_section_order(&mut reader)?;
         let section1 = Section1::from_reader(&mut reader)?;
-        let section2 = Section2;
+        let section2 = Section2::from_reader(&mut reader)?;
         let section3 = Section3_0::from_reader(&mut reader)?;
         let tank_sections = [
             PswSections::from_reader(&mut reader)?,
             PswSections::from_reader(&mut reader)?,
             PswSections::from_reader(&mut reader)?,
         ];
-        let section8 = Section8::from_reader(&mut reader)?;
+        let section8 = Section8::from_reader(&mut reader, options.allow_missing_end_marker)?;
 
         Ok(Self {
             reader,
@@ -108,6 +191,25 @@ impl PswReader {
         &self.section3
     }
 
+    /// 指定されたタンクの格子を定義する第3節:格子系定義節を返す。
+    ///
+    /// プロダクトの直前に第3節が記録されている場合はその節を、記録されていない場合は
+    /// 最初に記録されている第3節を返す。
+    ///
+    /// # 引数
+    ///
+    /// * `tank` - タンク
+    ///
+    /// # 戻り値
+    ///
+    /// * 第3節:格子系定義節
+    pub fn product_section3(&self, tank: PswTank) -> &Section3_0 {
+        self.psw_sections[tank as u8 as usize]
+            .section3
+            .as_ref()
+            .unwrap_or(&self.section3)
+    }
+
     /// 指定されたタンクの第4節:プロダクト定義節から第7節:資料節を返す。
     ///
     /// # 引数
@@ -130,6 +232,40 @@ impl PswReader {
         &self.section8
     }
 
+    /// 最初のプロダクトの第5節に記録されている資料表現テンプレート番号を返す。
+    ///
+    /// 資料を復号する前に、ランレングス圧縮（テンプレート5.200）で記録されているかを
+    /// 確認する場合に利用する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 資料表現テンプレート番号
+    pub fn data_representation_number(&self) -> u16 {
+        self.psw_sections[0]
+            .section5
+            .data_representation_template_number()
+    }
+
+    /// 第0節から第8節までのすべての節のフィールドを、節の名前と組にして返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![
+            describe("第0節:指示節", &self.section0),
+            describe("第1節:識別節", &self.section1),
+            describe("第2節:地域使用節", &self.section2),
+            describe("第3節:格子系定義節", &self.section3),
+        ];
+        for sections in &self.psw_sections {
+            descriptions.extend(sections.describe());
+        }
+        descriptions.push(describe("第8節:終端節", &self.section8));
+
+        descriptions
+    }
+
     /// 指定されたタンクのレコードを反復処理するイテレーターを返す。
     ///
     /// # 引数
@@ -139,7 +275,7 @@ impl PswReader {
     /// # 戻り値
     ///
     /// * 指定された土砂災害警戒判定時間のレコードを反復処理するイテレーター
-    pub fn record_iter(&mut self, tank: PswTank) -> Grib2Result<Grib2RecordIter<'_, File, u16>> {
+    pub fn record_iter(&mut self, tank: PswTank) -> Grib2Result<Grib2RecordIter<'_, R, u16>> {
         // ランレングス符号の開始位置にファイルポインターを移動
         let tank_section = &self.psw_sections[tank as u8 as usize];
         self.reader
@@ -148,25 +284,134 @@ impl PswReader {
             ))
             .map_err(|e| Grib2Error::Unexpected(e.into()))?;
 
-        // イテレーターを構築
-        Grib2RecordIterBuilder::new()
-            .reader(&mut self.reader)
-            .total_bytes(tank_section.section7.run_length_bytes())
-            .number_of_points(self.section3.number_of_data_points())
-            .lat_max(self.section3.lat_of_first_grid_point())
-            .lon_min(self.section3.lon_of_first_grid_point())
-            .lon_max(self.section3.lon_of_last_grid_point())
-            .lat_inc(self.section3.j_direction_increment())
-            .lon_inc(self.section3.i_direction_increment())
-            .nbit(tank_section.section5.bits_per_value() as u16)
-            .maxv(tank_section.section5.max_level_value())
-            .level_values(tank_section.section5.level_values())
-            .build()
+        let section3 = tank_section.section3.as_ref().unwrap_or(&self.section3);
+
+        build_record_iter(&mut self.reader, section3, tank_section)
+    }
+
+    /// 全タンク、第1タンク及び第2タンクの土壌雨量指数を、1つのCSVとして書き込む。
+    ///
+    /// `lon,lat,all,first,second`形式で、1つの座標につき1行を書き込む。タンクごとに
+    /// CSVファイルを出力する代わりに、1回の書き込みで3つのタンクの値をまとめて出力できる。
+    /// 座標は小数点以下`DEFAULT_COORD_DECIMALS`桁で出力し、すべてのタンクが値を持たない
+    /// 座標は書き込まない。タンクごとに異なる格子が記録されている場合は、座標ごとに
+    /// 組み合わせられないため`Grib2Error::GridMismatch`を返す。
+    ///
+    /// 各タンクのランレングス符号をメモリに読み込んだ後、3つのタンクのレコードを同時に展開しながら
+    /// 書き込むため、レコードの反復処理は1回である。
+    ///
+    /// # 引数
+    ///
+    /// * `writer` - ライター
+    ///
+    /// # 戻り値
+    ///
+    /// * 書き込んだ行の数
+    pub fn to_combined_csv<W: Write>(&mut self, writer: &mut W) -> Grib2Result<usize> {
+        let section3 = self.product_section3(PswTank::All).clone();
+        for tank in [PswTank::Tank1, PswTank::Tank2] {
+            section3.validate_same_grid(self.product_section3(tank))?;
+        }
+        let mut all = self.read_run_length(PswTank::All)?;
+        let mut first = self.read_run_length(PswTank::Tank1)?;
+        let mut second = self.read_run_length(PswTank::Tank2)?;
+        let [all_sections, first_sections, second_sections] = &self.psw_sections;
+        let all = build_record_iter(&mut all, &section3, all_sections)?;
+        let first = build_record_iter(&mut first, &section3, first_sections)?;
+        let second = build_record_iter(&mut second, &section3, second_sections)?;
+
+        writer
+            .write_all(b"lon,lat,all,first,second\n")
+            .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+        let cell = |value: Option<u16>| value.map(|v| v.to_string()).unwrap_or_default();
+        let mut number_of_writes = 0;
+        for ((all, first), second) in all.zip(first).zip(second) {
+            let (all, first, second) = (all?, first?, second?);
+            if all.value.is_none() && first.value.is_none() && second.value.is_none() {
+                continue;
+            }
+            let lat = all.lat as f64 / 1e6;
+            let lon = all.lon_i32() as f64 / 1e6;
+            writer
+                .write_fmt(format_args!(
+                    "{lon:.decimals$},{lat:.decimals$},{},{},{}\n",
+                    cell(all.value),
+                    cell(first.value),
+                    cell(second.value),
+                    decimals = DEFAULT_COORD_DECIMALS,
+                ))
+                .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+            number_of_writes += 1;
+        }
+
+        Ok(number_of_writes)
+    }
+
+    /// 指定されたタンクのランレングス符号をメモリに読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `tank` - ランレングス符号を読み込むタンク
+    ///
+    /// # 戻り値
+    ///
+    /// * ランレングス符号を読み込むリーダー
+    fn read_run_length(&mut self, tank: PswTank) -> Grib2Result<BufReader<Cursor<Vec<u8>>>> {
+        let section7 = &self.psw_sections[tank as u8 as usize].section7;
+        self.reader
+            .seek(SeekFrom::Start(section7.run_length_position() as u64))
+            .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+        let bytes = read_bytes(
+            &mut self.reader,
+            "ランレングス圧縮符号",
+            section7.run_length_bytes(),
+        )?;
+
+        Ok(BufReader::new(Cursor::new(bytes)))
     }
 }
 
+/// タンクの第3節:格子系定義節及び第5節:資料表現節から、レコードを反復処理するイテレーターを
+/// 構築する。
+///
+/// # 引数
+///
+/// * `reader` - ランレングス符号の開始位置にファイルポインターが位置するリーダー
+/// * `section3` - 第3節:格子系定義節
+/// * `tank_section` - タンクの第4節:プロダクト定義節から第7節:資料節
+///
+/// # 戻り値
+///
+/// * タンクのレコードを反復処理するイテレーター
+fn build_record_iter<'a, R: Read>(
+    reader: &'a mut BufReader<R>,
+    section3: &Section3_0,
+    tank_section: &'a PswSections,
+) -> Grib2Result<Grib2RecordIter<'a, R, u16>> {
+    Grib2RecordIterBuilder::new()
+        .reader(reader)
+        .total_bytes(tank_section.section7.run_length_bytes())
+        .number_of_points(section3.number_of_data_points())
+        .grid_shape(
+            section3.number_of_along_lat_points(),
+            section3.number_of_along_lon_points(),
+        )
+        .lat_max(section3.lat_of_first_grid_point())
+        .signed_lat(section3.has_southern_grid_points())
+        .lon_min(section3.lon_of_first_grid_point())
+        .lon_max(section3.lon_of_last_grid_point())
+        .lat_inc(section3.j_direction_increment())
+        .lon_inc(section3.i_direction_increment())
+        .nbit(tank_section.section5.bits_per_value() as u16)
+        .maxv(tank_section.section5.max_level_value())
+        .level_values(tank_section.section5.level_values())
+        .build()
+}
+
 /// 土壌雨量指数の第4節プロダクト定義節から第7節:資料節
 pub struct PswSections {
+    /// 第3節:格子系定義節（プロダクトの直前に記録されている場合）
+    pub section3: Option<Section3_0>,
     /// 第4節:プロダクト定義節
     pub section4: Section4_0,
     /// 第5節:資料表現節
@@ -178,19 +423,57 @@ pub struct PswSections {
 }
 
 impl PswSections {
+    /// 第4節:プロダクト定義節から第7節:資料節を読み込む。
+    ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も読み込む。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - 土壌雨量指数ファイルリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// * 第4節:プロダクト定義節から第7節:資料節
     pub(crate) fn from_reader<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+        let section3 = match peek_section_number(reader)? {
+            3 => Some(Section3_0::from_reader(reader)?),
+            _ => None,
+        };
         let section4 = Section4_0::from_reader(reader)?;
         let section5 = Section5_200u16::from_reader(reader)?;
         let section6 = Section6::from_reader(reader)?;
         let section7 = Section7_200::from_reader(reader)?;
 
         Ok(Self {
+            section3,
             section4,
             section5,
             section6,
             section7,
         })
     }
+
+    /// 第4節:プロダクト定義節から第7節:資料節までのフィールドを、節の名前と組にして返す。
+    ///
+    /// プロダクトの直前に第3節:格子系定義節が記録されている場合は、その節も含める。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の名前とフィールドの組を節の順番に格納したベクター
+    pub fn describe(&self) -> Vec<SectionDescription> {
+        let mut descriptions = vec![];
+        if let Some(section3) = &self.section3 {
+            descriptions.push(describe("第3節:格子系定義節", section3));
+        }
+        descriptions.extend([
+            describe("第4節:プロダクト定義節", &self.section4),
+            describe("第5節:資料表現節", &self.section5),
+            describe("第6節:ビットマップ節", &self.section6),
+            describe("第7節:資料節", &self.section7),
+        ]);
+
+        descriptions
+    }
 }
 
 /// 土壌雨量指数タンク
@@ -219,3 +502,169 @@ impl TryFrom<u8> for PswTank {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{PswReader, PswTank};
+    use crate::Grib2Error;
+
+    const PSW_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807170000_SRF_GPV_Ggis1km_Psw_Aper10min_ANAL_grib2.bin"
+    );
+
+    #[test]
+    fn new_strict_ok() {
+        assert!(PswReader::new_strict(PSW_FILE).is_ok());
+    }
+
+    #[test]
+    fn new_strict_with_trailing_data_err() {
+        let path =
+            std::env::temp_dir().join(format!("grib2_2_psw_trailing_{}", std::process::id()));
+        let mut bytes = fs::read(PSW_FILE).unwrap();
+        bytes.extend_from_slice(b"GRIB");
+        fs::write(&path, bytes).unwrap();
+        let lenient = PswReader::new(&path);
+        let strict = PswReader::new_strict(&path);
+        fs::remove_file(path).unwrap();
+
+        assert!(lenient.is_ok());
+        assert!(matches!(strict, Err(Grib2Error::ReadError(_))));
+    }
+
+    #[test]
+    fn product_section3_ok() {
+        let mut bytes = fs::read(PSW_FILE).unwrap();
+        // 第1節、第2節（記録なし）を読み飛ばして第3節の位置を取得
+        let section_bytes =
+            |bytes: &[u8], p: usize| u32::from_be_bytes(bytes[p..p + 4].try_into().unwrap());
+        let section3_position = 16 + section_bytes(&bytes, 16) as usize;
+        let section3_bytes = section_bytes(&bytes, section3_position) as usize;
+        // 最初と最後の格子点の緯度を1度南に移動した第3節を、第1タンクの第4節の直前に挿入
+        let mut position = section3_position + section3_bytes;
+        for _ in 0..4 {
+            position += section_bytes(&bytes, position) as usize;
+        }
+        let mut section3 = bytes[section3_position..section3_position + section3_bytes].to_vec();
+        for range in [46..50, 55..59] {
+            let lat = section_bytes(&section3, range.start) - 1_000_000;
+            section3[range].copy_from_slice(&lat.to_be_bytes());
+        }
+        bytes.splice(position..position, section3);
+        let total_bytes = bytes.len() as u64;
+        bytes[8..16].copy_from_slice(&total_bytes.to_be_bytes());
+        let path = std::env::temp_dir().join(format!(
+            "grib2_2_psw_{}_product_section3.bin",
+            std::process::id()
+        ));
+        fs::write(&path, bytes).unwrap();
+        let mut reader = PswReader::new(&path).unwrap();
+        let lat = reader.section3().lat_of_first_grid_point_i32();
+        let first = reader
+            .record_iter(PswTank::Tank1)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let result = reader.to_combined_csv(&mut vec![]);
+        fs::remove_file(path).unwrap();
+
+        assert!(reader.psw_sections(PswTank::Tank1).section3.is_some());
+        assert!(reader.psw_sections(PswTank::Tank2).section3.is_none());
+        assert_eq!(
+            lat - 1_000_000,
+            reader
+                .product_section3(PswTank::Tank1)
+                .lat_of_first_grid_point_i32()
+        );
+        assert_eq!(
+            lat,
+            reader
+                .product_section3(PswTank::Tank2)
+                .lat_of_first_grid_point_i32()
+        );
+        assert_eq!(lat - 1_000_000, first.lat);
+        // タンクごとに格子が異なるため、座標ごとに組み合わせられない
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "lat_of_first_grid_point"
+            })
+        ));
+    }
+
+    #[test]
+    fn to_combined_csv_ok() {
+        let mut reader = PswReader::new(PSW_FILE).unwrap();
+        let mut buf = vec![];
+        let written = reader.to_combined_csv(&mut buf).unwrap();
+        let csv = String::from_utf8(buf).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(written + 1, csv.lines().count());
+        assert_eq!(Some("lon,lat,all,first,second"), lines.next());
+        assert!(csv.lines().all(|l| l.split(',').count() == 5));
+
+        // 最初の行は、いずれかのタンクが値を持つ最初の格子点
+        let mut tanks = vec![];
+        for tank in [PswTank::All, PswTank::Tank1, PswTank::Tank2] {
+            tanks.push(
+                reader
+                    .record_iter(tank)
+                    .unwrap()
+                    .map(|r| r.unwrap())
+                    .collect::<Vec<_>>(),
+            );
+        }
+        let index = (0..tanks[0].len())
+            .find(|&i| tanks.iter().any(|t| t[i].value.is_some()))
+            .unwrap();
+        let record = tanks[0][index];
+        let values = tanks
+            .iter()
+            .map(|t| t[index].value.map(|v| v.to_string()).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let expected = format!(
+            "{:.6},{:.6},{}",
+            record.lon_i32() as f64 / 1e6,
+            record.lat as f64 / 1e6,
+            values.join(",")
+        );
+        assert_eq!(Some(expected.as_str()), lines.next());
+    }
+
+    #[test]
+    fn new_in_memory_ok() {
+        let path = std::env::temp_dir().join(format!("grib2_2_psw_{}", std::process::id()));
+        std::fs::copy(PSW_FILE, &path).unwrap();
+        let reader = PswReader::new_in_memory(&path);
+        // ファイルを削除しても、メモリからレコードを反復処理できる
+        std::fs::remove_file(&path).unwrap();
+        let mut reader = reader.unwrap();
+        let mut expected = PswReader::new(PSW_FILE).unwrap();
+
+        for tank in [PswTank::All, PswTank::Tank1, PswTank::Tank2] {
+            let values = reader
+                .record_iter(tank)
+                .unwrap()
+                .map(|r| r.unwrap().value)
+                .collect::<Vec<_>>();
+            let expected = expected
+                .record_iter(tank)
+                .unwrap()
+                .map(|r| r.unwrap().value)
+                .collect::<Vec<_>>();
+            assert_eq!(expected, values);
+        }
+        let mut buf = vec![];
+        let mut expected_buf = vec![];
+        assert_eq!(
+            expected.to_combined_csv(&mut expected_buf).unwrap(),
+            reader.to_combined_csv(&mut buf).unwrap()
+        );
+        assert_eq!(expected_buf, buf);
+    }
+}
@@ -1,23 +1,110 @@
 use std::io::{BufReader, Read};
+use std::time::{Duration, Instant};
 
 use num_format::{Locale, ToFormattedString};
 
+use crate::readers::utils::sign_magnitude_to_i32;
 use crate::{Grib2Error, Grib2Result};
 
+/// 経度の一周（1e-6度単位）
+const FULL_CIRCLE: u32 = 360_000_000;
+
 /// GRIB2が第7節に記録しているレコード
 #[derive(Debug, Clone, Copy)]
 pub struct Grib2Record<T>
 where
     T: Clone + Copy,
 {
-    /// 1e-6度単位の緯度
-    pub lat: u32,
+    /// 南緯を負の値とする1e-6度単位の緯度
+    pub lat: i32,
     /// 1e-6度単位の経度
     pub lon: u32,
     /// 値
     pub value: Option<T>,
 }
 
+impl<T> Grib2Record<T>
+where
+    T: Clone + Copy,
+{
+    /// 符号付きの経度（1e-6度単位）を返す。
+    ///
+    /// 180度を超える経度を、西経として負の値に変換する。
+    ///
+    /// # 戻り値
+    ///
+    /// * 西経を負の値とする1e-6度単位の経度
+    pub fn lon_i32(&self) -> i32 {
+        lon_to_i32(self.lon)
+    }
+}
+
+/// 0度から360度までの経度（1e-6度単位）を、西経を負の値とする符号付きの経度に変換する。
+///
+/// # 引数
+///
+/// * `lon` - 1e-6度単位の経度
+///
+/// # 戻り値
+///
+/// * 西経を負の値とする1e-6度単位の経度
+pub(crate) fn lon_to_i32(lon: u32) -> i32 {
+    if FULL_CIRCLE / 2 < lon {
+        lon as i32 - FULL_CIRCLE as i32
+    } else {
+        lon as i32
+    }
+}
+
+/// GRIB2が第7節に記録している、同じ値が連続する格子点の並び
+#[derive(Debug, Clone, Copy)]
+pub struct Grib2Run<T>
+where
+    T: Clone + Copy,
+{
+    /// 並びの最初の格子点の、南緯を負の値とする1e-6度単位の緯度
+    pub lat: i32,
+    /// 並びの最初の格子点の1e-6度単位の経度
+    pub lon: u32,
+    /// 並びに含まれる格子点の数
+    pub count: u32,
+    /// レベル値
+    pub level: u16,
+    /// 値
+    pub value: Option<T>,
+}
+
+/// 第5節のレベル別物理値として記録される値の型
+pub trait LevelValue: Clone + Copy {
+    /// GRIB2で欠測を示す、すべてのビットが1の値であるかを返す。
+    fn is_missing(&self) -> bool;
+}
+
+impl LevelValue for u16 {
+    fn is_missing(&self) -> bool {
+        *self == u16::MAX
+    }
+}
+
+impl LevelValue for i16 {
+    /// 符号付き整数は最上位ビットを符号とする符号と絶対値で記録されるため、0xFFFFは
+    /// -32767として読み込まれる。
+    fn is_missing(&self) -> bool {
+        *self == -i16::MAX
+    }
+}
+
+/// ランレングス圧縮符号を展開した統計
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeStats {
+    /// 展開したランレングス圧縮符号のセット数
+    pub sets_expanded: u64,
+    /// 読み込んだランレングス圧縮符号のバイト数
+    pub bytes_read: usize,
+    /// 最初のセットを展開してから、すべてのセットを展開し終えるまでの経過時間
+    pub elapsed: Duration,
+}
+
 pub struct Grib2RecordIter<'a, R, V>
 where
     R: Read,
@@ -30,8 +117,8 @@ where
     total_bytes: usize,
     /// 経度の最小値（1e-6度単位）
     lon_min: u32,
-    /// 経度の最大値（1e-6度単位）
-    lon_max: u32,
+    /// 経度方向の格子数
+    number_of_columns: u32,
     /// 緯度の増分（1e-6度単位）
     lat_inc: u32,
     /// 経度の増分（1e-6度単位）
@@ -40,14 +127,18 @@ where
     maxv: u16,
     /// LNGU進数
     lngu: u16,
-    /// レベル別物理値
-    level_values: &'a [V],
+    /// レベル値を物理値に変換する関数
+    value_fn: Box<dyn Fn(u16) -> Option<V> + 'a>,
     /// ランレングス圧縮符号を読み込んだバイト数
     read_bytes: usize,
-    /// 現在の緯度（1e-6度単位）
-    current_lat: u32,
+    /// 現在の緯度（南緯を負の値とする1e-6度単位）
+    current_lat: i32,
     /// 現在の経度（1e-6度単位）
     current_lon: u32,
+    /// 現在の経度方向の格子の位置（0始まり）
+    current_column: u32,
+    /// 最後に返した格子点の緯度と経度（1e-6度単位）
+    last_coordinate: (i32, u32),
     /// 現在のレベル値
     current_level: u16,
     /// 現在の物理値
@@ -58,12 +149,30 @@ where
     number_of_reads: u32,
     /// 最後に読み込んだランレングス圧縮符号
     last_run_length: Option<u16>,
+    /// 読み込んだ座標数と資料点数が一致するか確認するかを示すフラグ
+    verify_number_of_points: bool,
+    /// 展開したランレングス圧縮符号のセット数
+    sets_expanded: u64,
+    /// 最初のセットを展開した時刻
+    started_at: Option<Instant>,
+    /// すべてのセットを展開し終えるまでの経過時間
+    elapsed: Option<Duration>,
 }
 
 impl<'a, R, V> Grib2RecordIter<'a, R, V>
 where
     R: Read,
 {
+    /// 現在の緯度を、指定した行数だけ南に移動する。
+    ///
+    /// # 引数
+    ///
+    /// * `rows` - 移動する行数
+    fn move_south(&mut self, rows: u32) {
+        let distance = rows as i64 * self.lat_inc as i64;
+        self.current_lat = (self.current_lat as i64 - distance) as i32;
+    }
+
     /// GRIB2ファイルの現在のファイルポインターの位置から`u8`型の値を読み込む。
     ///
     /// # 戻り値
@@ -86,8 +195,8 @@ where
     /// * ランレングス符号を記録したベクター
     fn retrieve_run_length(&mut self) -> Grib2Result<Vec<u16>> {
         let mut run_length: Vec<u16> = vec![];
-        if self.last_run_length.is_some() {
-            run_length.push(self.last_run_length.unwrap());
+        if let Some(last_run_length) = self.last_run_length.take() {
+            run_length.push(last_run_length);
         }
         while self.read_bytes < self.total_bytes {
             let value = self.read_u8()? as u16;
@@ -101,6 +210,146 @@ where
 
         Ok(run_length)
     }
+
+    /// ランレングス圧縮符号をすべて読み込んだかを確認する。
+    ///
+    /// すべて読み込んでいる場合は、展開に要した経過時間を記録する。
+    ///
+    /// # 戻り値
+    ///
+    /// * すべて読み込んでいない場合は`None`
+    /// * すべて読み込み、読み込んだ座標数が資料点数と一致する場合は`Some(Ok(()))`
+    /// * すべて読み込み、読み込んだ座標数が資料点数と一致しない場合は`Some(Err(...))`
+    fn check_finished(&mut self) -> Option<Grib2Result<()>> {
+        // 現在値返却回数が0かつ、読み込んだバイト数がランレングス圧縮符号列を記録しているバイト数に達している場合は終了
+        if self.returning_times != 0
+            || self.read_bytes < self.total_bytes
+            || self.last_run_length.is_some()
+        {
+            return None;
+        }
+        if self.elapsed.is_none() {
+            self.elapsed = Some(self.started_at.map_or(Duration::ZERO, |at| at.elapsed()));
+        }
+        if !self.verify_number_of_points || self.number_of_reads == self.number_of_points {
+            Some(Ok(()))
+        } else {
+            Some(Err(Grib2Error::Unexpected(
+                format!(
+                    "読み込んだ座標数({})が第3節に記録されている資料点数({})と一致しません。\
+                    ファイルが壊れている、またはクレートにバグがある可能性があります。",
+                    self.number_of_reads.to_formatted_string(&Locale::ja),
+                    self.number_of_points.to_formatted_string(&Locale::ja),
+                )
+                .into(),
+            )))
+        }
+    }
+}
+
+impl<'a, R, V> Grib2RecordIter<'a, R, V>
+where
+    R: Read,
+    V: Copy,
+{
+    /// ランレングス圧縮符号を展開して、現在のレベル値、物理値及び返却回数を更新する。
+    fn load_next_run(&mut self) -> Grib2Result<()> {
+        if self.started_at.is_none() {
+            self.started_at = Some(Instant::now());
+        }
+        // ランレングス圧縮符号を取得
+        let run_length = self.retrieve_run_length()?;
+        // ランレングス圧縮符号を展開
+        let (level, times) = expand_run_length(&run_length, self.maxv, self.lngu);
+        self.sets_expanded += 1;
+        // 現在のレベル値、物理値及び返却回数を更新
+        self.current_level = level;
+        self.current_value = (self.value_fn)(level);
+        self.returning_times = times;
+
+        Ok(())
+    }
+
+    /// ランレングス圧縮符号を展開した統計を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * イテレーターが終了している場合はランレングス圧縮符号を展開した統計、
+    ///   終了していない場合は`None`
+    pub fn stats(&self) -> Option<DecodeStats> {
+        self.elapsed.map(|elapsed| DecodeStats {
+            sets_expanded: self.sets_expanded,
+            bytes_read: self.read_bytes,
+            elapsed,
+        })
+    }
+
+    /// 最後に返したレコードの格子点の緯度と経度を返す。
+    ///
+    /// 反復処理の途中でエラーが発生した位置をログに出力する場合などに利用する。
+    /// まだレコードを返していない場合は、北西端の格子点の緯度と経度を返す。
+    /// `runs`で並びごとに反復処理している場合は、最後に返した並びの最初の格子点の緯度と経度を
+    /// 返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 南緯を負の値とする1e-6度単位の緯度と、1e-6度単位の経度
+    pub fn current_coordinate(&self) -> (i32, u32) {
+        self.last_coordinate
+    }
+
+    /// 格子点ごとではなく、同じ値が連続する格子点の並びごとに反復処理するイテレーターを返す。
+    ///
+    /// ランレングス圧縮符号の1セットが1つの並びとなるため、等値領域のポリゴン化など、
+    /// 格子点ごとに展開する必要がない処理に利用できる。
+    /// なお、並びは経度方向の端で折り返さず、次の緯度の最西端の格子点に続く。
+    ///
+    /// # 戻り値
+    ///
+    /// * 同じ値が連続する格子点の並びを返すイテレーター
+    pub fn runs(self) -> Grib2RunIter<'a, R, V> {
+        Grib2RunIter { inner: self }
+    }
+
+    /// 値を持たない格子点の座標のみを反復処理するイテレーターを返す。
+    ///
+    /// レベル値が0の格子点など、値が`None`となる格子点の座標を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 値を持たない格子点の1e-6度単位の緯度と経度を返すイテレーター
+    pub fn missing(self) -> impl Iterator<Item = Grib2Result<(i32, u32)>> + 'a
+    where
+        V: 'a,
+    {
+        self.filter_map(|record| match record {
+            Ok(record) if record.value.is_none() => Some(Ok((record.lat, record.lon))),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// 格子点の行と列のインデックスを付けてレコードを反復処理するイテレーターを返す。
+    ///
+    /// 行と列のインデックスは、北西端の格子点を`(0, 0)`として、行は南に、列は東に向かって
+    /// 増加する。ラスタータイルを作成する場合など、格子を2次元配列として扱う処理に利用できる。
+    ///
+    /// # 戻り値
+    ///
+    /// * 行のインデックス、列のインデックス及びレコードを返すイテレーター
+    pub fn indexed(self) -> impl Iterator<Item = Grib2Result<(u32, u32, Grib2Record<V>)>> + 'a
+    where
+        V: 'a,
+    {
+        let columns = self.number_of_columns;
+        let mut index = 0;
+        self.map(move |record| {
+            let record = record?;
+            let (row, col) = (index / columns, index % columns);
+            index += 1;
+            Ok((row, col, record))
+        })
+    }
 }
 
 impl<'a, R, V> Iterator for Grib2RecordIter<'a, R, V>
@@ -111,40 +360,17 @@ where
     type Item = Grib2Result<Grib2Record<V>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // 現在値返却回数が0かつ、読み込んだバイト数がランレングス圧縮符号列を記録しているバイト数に達している場合は終了
-        if self.returning_times == 0 && self.total_bytes <= self.read_bytes {
-            if self.number_of_reads == self.number_of_points {
-                return None;
-            } else {
-                return Some(Err(Grib2Error::Unexpected(
-                    format!(
-                        "読み込んだ座標数({})が第3節に記録されている資料点数({})と一致しません。\
-                        ファイルが壊れている、またはクレートにバグがある可能性があります。",
-                        self.number_of_reads.to_formatted_string(&Locale::ja),
-                        self.number_of_points.to_formatted_string(&Locale::ja),
-                    )
-                    .into(),
-                )));
-            }
+        match self.check_finished() {
+            Some(Ok(())) => return None,
+            Some(Err(e)) => return Some(Err(e)),
+            None => {}
         }
 
         // 現在値返却回数が0の場合は、ランレングス圧縮符号を展開して現在値を更新
         if self.returning_times == 0 {
-            // ランレングス圧縮符号を取得
-            let run_length = self.retrieve_run_length();
-            if run_length.is_err() {
-                return Some(Err(run_length.err().unwrap()));
+            if let Err(e) = self.load_next_run() {
+                return Some(Err(e));
             }
-            // ランレングス圧縮符号を展開
-            let (level, times) = expand_run_length(&run_length.unwrap(), self.maxv, self.lngu);
-            // 現在のレベル値、物理値及び返却回数を更新
-            self.current_level = level;
-            self.current_value = if 0 < level {
-                Some(self.level_values[level as usize - 1])
-            } else {
-                None
-            };
-            self.returning_times = times;
         }
 
         // 結果を生成
@@ -153,13 +379,17 @@ where
             lon: self.current_lon,
             value: self.current_value,
         }));
+        self.last_coordinate = (self.current_lat, self.current_lon);
         // 現在値を返す回数を減らす
         self.returning_times -= 1;
         // 格子を移動
-        self.current_lon += self.lon_inc;
-        if self.lon_max < self.current_lon {
-            self.current_lat -= self.lat_inc;
+        self.current_column += 1;
+        if self.current_column == self.number_of_columns {
+            self.move_south(1);
             self.current_lon = self.lon_min;
+            self.current_column = 0;
+        } else {
+            self.current_lon = (self.current_lon + self.lon_inc) % FULL_CIRCLE;
         }
         // 読み込んだ座標数をインクリメント
         self.number_of_reads += 1;
@@ -168,6 +398,61 @@ where
     }
 }
 
+/// 同じ値が連続する格子点の並びを反復処理するイテレーター
+pub struct Grib2RunIter<'a, R, V>
+where
+    R: Read,
+{
+    /// 格子点ごとに反復処理するイテレーター
+    inner: Grib2RecordIter<'a, R, V>,
+}
+
+impl<'a, R, V> Iterator for Grib2RunIter<'a, R, V>
+where
+    R: Read,
+    V: Copy,
+{
+    type Item = Grib2Result<Grib2Run<V>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let inner = &mut self.inner;
+        match inner.check_finished() {
+            Some(Ok(())) => return None,
+            Some(Err(e)) => return Some(Err(e)),
+            None => {}
+        }
+
+        // 現在値返却回数が0の場合は、ランレングス圧縮符号を展開して現在値を更新
+        if inner.returning_times == 0 {
+            if let Err(e) = inner.load_next_run() {
+                return Some(Err(e));
+            }
+        }
+
+        // 結果を生成
+        let count = inner.returning_times;
+        let result = Some(Ok(Grib2Run {
+            lat: inner.current_lat,
+            lon: inner.current_lon,
+            count,
+            level: inner.current_level,
+            value: inner.current_value,
+        }));
+        inner.last_coordinate = (inner.current_lat, inner.current_lon);
+        // 並びに含まれる格子点の数だけ格子を移動
+        let columns = inner.number_of_columns;
+        let index = inner.current_column + count;
+        inner.move_south(index / columns);
+        inner.current_column = index % columns;
+        inner.current_lon = (inner.lon_min + inner.current_column * inner.lon_inc) % FULL_CIRCLE;
+        inner.returning_times = 0;
+        // 読み込んだ座標数を加算
+        inner.number_of_reads += count;
+
+        result
+    }
+}
+
 #[derive(Default)]
 pub struct Grib2RecordIterBuilder<'a, R, V>
 where
@@ -185,6 +470,12 @@ where
     nbit: Option<u16>,
     maxv: Option<u16>,
     level_values: Option<&'a [V]>,
+    value_fn: Option<Box<dyn Fn(u16) -> Option<V> + 'a>>,
+    override_number_of_points: Option<u32>,
+    max_total_bytes: Option<usize>,
+    is_missing: Option<fn(&V) -> bool>,
+    signed_lat: bool,
+    grid_shape: Option<(u32, u32)>,
 }
 
 impl<'a, R, V> Grib2RecordIterBuilder<'a, R, V>
@@ -205,6 +496,12 @@ where
             nbit: None,
             maxv: None,
             level_values: None,
+            value_fn: None,
+            override_number_of_points: None,
+            max_total_bytes: None,
+            is_missing: None,
+            signed_lat: false,
+            grid_shape: None,
         }
     }
 
@@ -215,6 +512,8 @@ where
     }
 
     /// ランレングス圧縮符号全体のバイト数を設定する。
+    ///
+    /// 0を設定した場合、すべての格子点を値を持たない格子点として返す。
     pub fn total_bytes(mut self, total_bytes: usize) -> Self {
         self.total_bytes = Some(total_bytes);
         self
@@ -232,6 +531,16 @@ where
         self
     }
 
+    /// 緯度が負の値（南半球）を取り得るかを設定する。
+    ///
+    /// `true`を設定した場合、`lat_max`で設定する緯度を、GRIB2と同様に最上位ビットを符号と
+    /// する符号と絶対値で表現された値として解釈する。レコードの`lat`は、設定によらず南緯を
+    /// 負の値とする符号付きの緯度である。既定値は`false`である。
+    pub fn signed_lat(mut self, signed_lat: bool) -> Self {
+        self.signed_lat = signed_lat;
+        self
+    }
+
     /// 経度の最小値（1e-6度単位）を設定する。
     pub fn lon_min(mut self, lon_min: u32) -> Self {
         self.lon_min = Some(lon_min);
@@ -239,6 +548,8 @@ where
     }
 
     /// 経度の最大値（1e-6度単位）を設定する。
+    ///
+    /// 格子が経度0度（360度）を跨ぐ場合は、経度の最小値より小さい値を設定する。
     pub fn lon_max(mut self, lon_max: u32) -> Self {
         self.lon_max = Some(lon_max);
         self
@@ -269,8 +580,88 @@ where
     }
 
     /// レベル別物理値を設定する。
-    pub fn level_values(mut self, level_values: &'a [V]) -> Self {
+    ///
+    /// レベル別物理値に欠測を示す値（すべてのビットが1の値）が記録されている場合、その
+    /// レベル値の格子点の値は`None`になる。
+    pub fn level_values(mut self, level_values: &'a [V]) -> Self
+    where
+        V: LevelValue,
+    {
         self.level_values = Some(level_values);
+        self.is_missing = Some(V::is_missing);
+        self
+    }
+
+    /// レベル値を物理値に変換する関数を設定する。
+    ///
+    /// この関数を設定した場合、`level_values`で設定したレベル別物理値は使用せず、レベル値0を
+    /// 含むすべてのレベル値をこの関数で変換する。
+    pub fn value_fn<F>(mut self, value_fn: F) -> Self
+    where
+        F: Fn(u16) -> Option<V> + 'a,
+    {
+        self.value_fn = Some(Box::new(value_fn));
+        self
+    }
+
+    /// レベル別物理値に変換せず、レベル値をそのまま値とするレコードを返すように設定する。
+    ///
+    /// 再符号化やデバッグのためにレベル値を取得する場合に使用する。`level_values`及び
+    /// `value_fn`で設定した内容は使用しない。レベル値0（欠測）の格子点の値は`None`になる。
+    pub fn emit_levels(self) -> Grib2RecordIterBuilder<'a, R, u16> {
+        Grib2RecordIterBuilder {
+            reader: self.reader,
+            total_bytes: self.total_bytes,
+            number_of_points: self.number_of_points,
+            lat_max: self.lat_max,
+            lon_min: self.lon_min,
+            lon_max: self.lon_max,
+            lat_inc: self.lat_inc,
+            lon_inc: self.lon_inc,
+            nbit: self.nbit,
+            maxv: self.maxv,
+            level_values: None,
+            value_fn: Some(Box::new(|level: u16| (0 < level).then_some(level))),
+            override_number_of_points: self.override_number_of_points,
+            max_total_bytes: self.max_total_bytes,
+            is_missing: None,
+            signed_lat: self.signed_lat,
+            grid_shape: self.grid_shape,
+        }
+    }
+
+    /// 第3節に記録されている緯線に沿った格子点数（Ni）と経線に沿った格子点数（Nj）を設定する。
+    ///
+    /// 設定した場合、`build`は経度の範囲と増分から求めた経度方向の格子数がNiと一致し、
+    /// Ni×Njが資料点数と一致するかを確認する。`override_number_of_points`を設定した場合は
+    /// 確認しない。
+    pub fn grid_shape(
+        mut self,
+        number_of_along_lat_points: u32,
+        number_of_along_lon_points: u32,
+    ) -> Self {
+        self.grid_shape = Some((number_of_along_lat_points, number_of_along_lon_points));
+        self
+    }
+
+    /// 第3節に記録されている資料点数の代わりに使用する座標数を設定する。
+    ///
+    /// 第3節に記録されている資料点数が誤っていることが分かっているファイルを読み込むときに
+    /// 使用する。この値を設定した場合、`number_of_points`で設定した値を置き換えるとともに、
+    /// ランレングス圧縮符号をすべて読み込んだ後に、読み込んだ座標数と資料点数が一致するかを
+    /// 確認しない。
+    pub fn override_number_of_points(mut self, number_of_points: u32) -> Self {
+        self.override_number_of_points = Some(number_of_points);
+        self
+    }
+
+    /// ランレングス圧縮符号全体のバイト数の上限を設定する。
+    ///
+    /// 節の長さが壊れたファイルなど、`total_bytes`で設定したバイト数がこの上限を超える場合、
+    /// `build`はランレングス圧縮符号を読み込まずにエラーを返す。設定しない場合は上限を
+    /// 確認しない。
+    pub fn max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
         self
     }
 
@@ -283,14 +674,33 @@ where
                 "ランレングス圧縮符号全体のバイト数が設定されていません。".into(),
             )
         })?;
-        let number_of_points = self.number_of_points.ok_or_else(|| {
-            Grib2Error::RuntimeError(
-                "GRIB2ファイルに記録されている座標数が設定されていません。".into(),
-            )
-        })?;
+        if let Some(max_total_bytes) = self.max_total_bytes {
+            if max_total_bytes < total_bytes {
+                return Err(Grib2Error::ReadError(
+                    format!(
+                        "ランレングス圧縮符号全体のバイト数({total_bytes})が上限({max_total_bytes})を超えています。"
+                    )
+                    .into(),
+                ));
+            }
+        }
+        let verify_number_of_points = self.override_number_of_points.is_none();
+        let number_of_points = self
+            .override_number_of_points
+            .or(self.number_of_points)
+            .ok_or_else(|| {
+                Grib2Error::RuntimeError(
+                    "GRIB2ファイルに記録されている座標数が設定されていません。".into(),
+                )
+            })?;
         let lat_max = self
             .lat_max
             .ok_or_else(|| Grib2Error::RuntimeError("緯度の最大値が設定されていません。".into()))?;
+        let lat_max = if self.signed_lat {
+            sign_magnitude_to_i32(lat_max)
+        } else {
+            lat_max as i32
+        };
         let lon_min = self
             .lon_min
             .ok_or_else(|| Grib2Error::RuntimeError("経度の最小値が設定されていません。".into()))?;
@@ -303,6 +713,35 @@ where
         let lon_inc = self
             .lon_inc
             .ok_or_else(|| Grib2Error::RuntimeError("経度の増分が設定されていません。".into()))?;
+        if lon_inc == 0 {
+            return Err(Grib2Error::RuntimeError("経度の増分が0です。".into()));
+        }
+        // 経度の最小値が最大値より大きい場合は、経度0度（360度）を跨ぐ格子として扱う
+        let lon_span = if lon_min <= lon_max {
+            lon_max - lon_min
+        } else {
+            lon_max + FULL_CIRCLE - lon_min
+        };
+        let number_of_columns = lon_span / lon_inc + 1;
+        // 格子を矩形として走査するため、資料点数は経度方向の格子数の倍数でなければならない
+        if verify_number_of_points && !number_of_points.is_multiple_of(number_of_columns) {
+            return Err(Grib2Error::GridMismatch {
+                field: "number_of_points",
+            });
+        }
+        // 第3節の格子点数が設定されている場合は、格子の形状と資料点数が一致するか確認
+        if let (true, Some((ni, nj))) = (verify_number_of_points, self.grid_shape) {
+            if ni != number_of_columns {
+                return Err(Grib2Error::GridMismatch {
+                    field: "number_of_along_lat_points",
+                });
+            }
+            if ni as u64 * nj as u64 != number_of_points as u64 {
+                return Err(Grib2Error::GridMismatch {
+                    field: "number_of_points",
+                });
+            }
+        }
         let nbit = self.nbit.ok_or_else(|| {
             Grib2Error::RuntimeError("1格子点値当りのビット数が設定されていません。".into())
         })?;
@@ -311,33 +750,88 @@ where
                 "今回の圧縮に用いたレベルの最大値が設定されていません。".into(),
             )
         })?;
-        let level_values = self.level_values.ok_or_else(|| {
-            Grib2Error::RuntimeError("レベル別物理値が設定されていません。".into())
-        })?;
+        let value_fn: Box<dyn Fn(u16) -> Option<V> + 'a> = match (self.value_fn, self.level_values)
+        {
+            (Some(value_fn), _) => value_fn,
+            (None, Some(level_values)) => {
+                validate_level_values(level_values.len(), maxv)?;
+                let is_missing = self.is_missing.unwrap_or(|_| false);
+                Box::new(move |level: u16| {
+                    if 0 < level {
+                        Some(level_values[level as usize - 1]).filter(|v| !is_missing(v))
+                    } else {
+                        None
+                    }
+                })
+            }
+            (None, None) => {
+                return Err(Grib2Error::RuntimeError(
+                    "レベル別物理値が設定されていません。".into(),
+                ))
+            }
+        };
+
+        // ランレングス圧縮符号列が記録されていない場合は、すべての格子点をレベル値0（欠測）の
+        // 1つの並びとして扱う
+        let (returning_times, current_value) = if total_bytes == 0 {
+            (number_of_points, value_fn(0))
+        } else {
+            (0, None)
+        };
 
         Ok(Grib2RecordIter {
             reader,
             total_bytes,
             number_of_points,
             lon_min,
-            lon_max,
+            number_of_columns,
             lat_inc,
             lon_inc,
             maxv,
             lngu: 2u16.pow(nbit as u32) - 1 - maxv,
-            level_values,
+            value_fn,
             read_bytes: 0,
             current_lat: lat_max,
             current_lon: lon_min,
+            current_column: 0,
+            last_coordinate: (lat_max, lon_min),
             current_level: 0,
-            current_value: None,
-            returning_times: 0,
+            current_value,
+            returning_times,
             number_of_reads: 0,
             last_run_length: None,
+            verify_number_of_points,
+            sets_expanded: 0,
+            started_at: None,
+            elapsed: None,
         })
     }
 }
 
+/// レベル別物理値が、今回の圧縮に用いたレベルの最大値までのレベルを網羅しているか確認する。
+///
+/// レベル別物理値が不足していると、レベル値を物理値に変換する際に範囲外を参照するため、
+/// イテレーターを構築する前に確認する。
+///
+/// # 引数
+///
+/// * `number_of_level_values` - レベル別物理値の数
+/// * `maxv` - 今回の圧縮に用いたレベルの最大値
+pub(crate) fn validate_level_values(number_of_level_values: usize, maxv: u16) -> Grib2Result<()> {
+    if number_of_level_values < maxv as usize {
+        return Err(Grib2Error::RuntimeError(
+            format!(
+                "レベル別物理値の数({number_of_level_values})が、今回の圧縮に用いたレベルの\
+                最大値({maxv})より少ないため、レベル値を物理値に変換できません。\
+                第5節が壊れている可能性があります。"
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// 1セットのランレングス圧縮符号を展開する。
 ///
 /// 引数valuesの最初の要素はレベル値で、それ以降はランレングス値である。
@@ -378,7 +872,7 @@ where
 ///   * (2 ^ nbit - maxv)よりも大きなランレングスが必要となった場合、1データでは表現すること
 ///     ができない。
 ///   * これに対応するために、2つ以上のランレングス値を連続させてランレングスを表現するが、連続した
-///      データの単純な総和をランレングスとしても圧縮効率があがらない。
+///     データの単純な総和をランレングスとしても圧縮効率があがらない。
 ///   * よって、lngu(=2 ^ nbit - 1 - maxv)進数を用いてランレングスを表現する。
 ///   * レベル値のすぐ後に続く最初のランレングス値(data1)をlngu進数の1桁目
 ///     RL1={lngu ^ (1 - 1) * (data1 - (maxv + 1))}とする。
@@ -437,9 +931,552 @@ pub(crate) fn expand_run_length(values: &[u16], maxv: u16, lngu: u16) -> (u16, u
     (values[0] as u16, times + 1)
 }
 
+/// ランレングス圧縮符号列を展開して、資料点数と同じ数の格子点に展開されるか確認する。
+///
+/// レコードを作成せずに、ランレングス圧縮符号列のみを検証するため、ファジングや品質確認に
+/// 利用できる。ランレングス圧縮符号は、1バイトに1つ記録されているものとする。
+///
+/// # 引数
+///
+/// * `bytes` - ランレングス圧縮符号列
+/// * `maxv` - 今回の圧縮に用いたレベルの最大値（第5節 13-14オクテット）
+/// * `lngu` - 2 ^ nbit - 1 - maxvの値
+/// * `number_of_points` - 第3節に記録されている資料点数
+///
+/// # 戻り値
+///
+/// * 資料点数と同じ数の格子点に展開される場合は`()`
+pub fn validate_run_length(
+    bytes: &[u8],
+    maxv: u16,
+    lngu: u16,
+    number_of_points: u32,
+) -> Grib2Result<()> {
+    if let Some(&first) = bytes.first() {
+        if maxv < first as u16 {
+            return Err(Grib2Error::ReadError(
+                format!(
+                    "ランレングス圧縮符号列の最初の値({first})がレベルの最大値({maxv})を超えています。"
+                )
+                .into(),
+            ));
+        }
+    }
+    let mut number_of_reads = 0u64;
+    let mut start = 0;
+    while start < bytes.len() {
+        // レベル値から、次のレベル値の直前までを1セットとする
+        let end = bytes[start + 1..]
+            .iter()
+            .position(|&v| v as u16 <= maxv)
+            .map_or(bytes.len(), |i| start + 1 + i);
+        let values = bytes[start..end]
+            .iter()
+            .map(|&v| v as u16)
+            .collect::<Vec<_>>();
+        // ランレングス値は、レベルの最大値 + LNGU以下でなければならない
+        if let Some(&v) = values
+            .iter()
+            .find(|&&v| v as u32 > maxv as u32 + lngu as u32)
+        {
+            return Err(Grib2Error::ReadError(
+                format!("{start}バイト目からのセットに、不正なランレングス値({v})があります。")
+                    .into(),
+            ));
+        }
+        // ランレングス値の桁数が多すぎて、展開すると`u32`で表現できない場合はエラー
+        let digits = values.len() as u32 - 1;
+        if (lngu as u64)
+            .checked_pow(digits)
+            .is_none_or(|max_times| u32::MAX as u64 <= max_times)
+        {
+            return Err(Grib2Error::ReadError(
+                format!("{start}バイト目からのランレングスが長すぎます。").into(),
+            ));
+        }
+        let (_, times) = expand_run_length(&values, maxv, lngu);
+        number_of_reads += times as u64;
+        if number_of_reads > number_of_points as u64 {
+            break;
+        }
+        start = end;
+    }
+    if number_of_reads != number_of_points as u64 {
+        return Err(Grib2Error::ReadError(
+            format!(
+                "ランレングス圧縮符号列を展開した格子点数({number_of_reads})が\
+                資料点数({number_of_points})と一致しません。"
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::expand_run_length;
+    use std::io::{BufReader, Cursor};
+
+    use super::{expand_run_length, validate_run_length, Grib2Record, Grib2RecordIterBuilder};
+    use crate::Grib2Error;
+
+    /// `expand_run_length`のドキュメントに記載したランレングス符号化列
+    ///
+    /// nbit = 4、maxv = 10で、21個の格子点に展開される。
+    const RUN_LENGTH: [u8; 13] = [3, 9, 12, 6, 4, 15, 2, 1, 0, 13, 12, 2, 3];
+
+    /// レベル別物理値
+    const LEVEL_VALUES: [u16; 10] = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+
+    /// 7 x 3の格子を反復処理するイテレーターのビルダーを返す。
+    fn builder(
+        reader: &mut BufReader<Cursor<Vec<u8>>>,
+    ) -> Grib2RecordIterBuilder<'_, Cursor<Vec<u8>>, u16> {
+        Grib2RecordIterBuilder::new()
+            .reader(reader)
+            .total_bytes(RUN_LENGTH.len())
+            .lat_max(35_000_000)
+            .lon_min(139_000_000)
+            .lon_max(139_006_000)
+            .lat_inc(1_000)
+            .lon_inc(1_000)
+            .nbit(4)
+            .maxv(10)
+            .level_values(&LEVEL_VALUES)
+    }
+
+    #[test]
+    fn expand_last_single_level_set_ok() {
+        // 最後のセットはランレングスを伴わないレベル値（3）のみで、1つの格子点に展開される
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let records = builder(&mut reader)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .take(22)
+            .collect::<Vec<_>>();
+        assert_eq!(21, records.len());
+        assert!(records.iter().all(|r| r.is_ok()));
+        assert_eq!(Some(30), records[20].as_ref().unwrap().value);
+    }
+
+    #[test]
+    fn wrong_number_of_points_err() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let mut iter = builder(&mut reader).number_of_points(28).build().unwrap();
+        assert!(iter.any(|r| r.is_err()));
+    }
+
+    #[test]
+    fn total_bytes_over_max_err() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let result = builder(&mut reader)
+            .number_of_points(21)
+            .total_bytes(usize::MAX)
+            .max_total_bytes(1 << 20)
+            .build();
+        assert!(matches!(result, Err(Grib2Error::ReadError(_))));
+
+        // 上限以下の場合は読み込める
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let iter = builder(&mut reader)
+            .number_of_points(21)
+            .max_total_bytes(RUN_LENGTH.len())
+            .build()
+            .unwrap();
+        assert_eq!(21, iter.count());
+    }
+
+    #[test]
+    fn irregular_number_of_points_err() {
+        // 経度方向の格子数(7)の倍数ではない
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let result = builder(&mut reader).number_of_points(20).build();
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "number_of_points"
+            })
+        ));
+    }
+
+    #[test]
+    fn grid_shape_mismatch_err() {
+        // 経度方向の格子数の倍数だが、Ni×Njと一致しない
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let result = builder(&mut reader)
+            .number_of_points(21)
+            .grid_shape(7, 4)
+            .build();
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "number_of_points"
+            })
+        ));
+
+        // Niが経度の範囲と増分から求めた経度方向の格子数(7)と一致しない
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let result = builder(&mut reader)
+            .number_of_points(21)
+            .grid_shape(3, 7)
+            .build();
+        assert!(matches!(
+            result,
+            Err(Grib2Error::GridMismatch {
+                field: "number_of_along_lat_points"
+            })
+        ));
+
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let iter = builder(&mut reader)
+            .number_of_points(21)
+            .grid_shape(7, 3)
+            .build()
+            .unwrap();
+        assert_eq!(21, iter.count());
+    }
+
+    #[test]
+    fn override_number_of_points_ok() {
+        // 第3節に記録されている資料点数が誤っている（28）場合はエラー
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let mut iter = builder(&mut reader).number_of_points(28).build().unwrap();
+        assert!(iter.any(|r| r.is_err()));
+
+        // 正しい座標数（21）で置き換えると、すべての格子点を読み込める
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let records = builder(&mut reader)
+            .number_of_points(28)
+            .override_number_of_points(21)
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(21, records.len());
+        assert_eq!((35_000_000, 139_000_000), (records[0].lat, records[0].lon));
+        assert_eq!(
+            (34_998_000, 139_006_000),
+            (records[20].lat, records[20].lon)
+        );
+
+        // ランレングス圧縮符号列が記録されていない場合は、置き換えた座標数だけ格子点を返す
+        let mut reader = BufReader::new(Cursor::new(vec![]));
+        let records = builder(&mut reader)
+            .total_bytes(0)
+            .number_of_points(28)
+            .override_number_of_points(14)
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(14, records.len());
+        assert_eq!(
+            (34_999_000, 139_006_000),
+            (records[13].lat, records[13].lon)
+        );
+    }
+
+    #[test]
+    fn zero_total_bytes_yields_all_missing() {
+        let mut reader = BufReader::new(Cursor::new(vec![]));
+        let records = builder(&mut reader)
+            .total_bytes(0)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(21, records.len());
+        assert!(records.iter().all(|r| r.value.is_none()));
+        assert_eq!(
+            (34_998_000, 139_006_000),
+            (records[20].lat, records[20].lon)
+        );
+
+        let mut reader = BufReader::new(Cursor::new(vec![]));
+        let runs = builder(&mut reader)
+            .total_bytes(0)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .runs()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(1, runs.len());
+        assert_eq!((21, 0, None), (runs[0].count, runs[0].level, runs[0].value));
+    }
+
+    #[test]
+    fn emit_levels_ok() {
+        // `expand_run_length`のドキュメントに記載した展開後のレベル値
+        const LEVELS: [u16; 21] = [
+            3, 9, 9, 6, 4, 4, 4, 4, 4, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 2, 3,
+        ];
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let levels = builder(&mut reader)
+            .number_of_points(21)
+            .emit_levels()
+            .build()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        let expected = LEVELS
+            .iter()
+            .map(|&level| (0 < level).then_some(level))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, levels);
+    }
+
+    #[test]
+    fn current_coordinate_ok() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let mut iter = builder(&mut reader).number_of_points(21).build().unwrap();
+        assert_eq!((35_000_000, 139_000_000), iter.current_coordinate());
+
+        // 最初のレコードは北西端の格子点
+        iter.next().unwrap().unwrap();
+        assert_eq!((35_000_000, 139_000_000), iter.current_coordinate());
+        // 経度方向の格子数（7）だけ進めると、2行目の最初の格子点
+        let record = iter.nth(6).unwrap().unwrap();
+        assert_eq!((34_999_000, 139_000_000), iter.current_coordinate());
+        assert_eq!((record.lat, record.lon), iter.current_coordinate());
+    }
+
+    #[test]
+    fn runs_ok() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let runs = builder(&mut reader)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .runs()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(21, runs.iter().map(|r| r.count).sum::<u32>());
+        // {0, 13, 12}は8個の0に展開され、2行目の5列目から3行目の5列目まで続く
+        let run = runs.iter().find(|r| r.count == 8).unwrap();
+        assert_eq!((34_999_000, 139_004_000), (run.lat, run.lon));
+        assert_eq!((0, None), (run.level, run.value));
+        let last = runs.last().unwrap();
+        assert_eq!((34_998_000, 139_006_000), (last.lat, last.lon));
+        assert_eq!((3, Some(30)), (last.level, last.value));
+    }
+
+    #[test]
+    fn missing_ok() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let missing = builder(&mut reader)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .missing()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let present = builder(&mut reader)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .filter(|r| r.as_ref().unwrap().value.is_some())
+            .count();
+
+        // {0, 13, 12}は8個の0に展開され、2行目の5列目から3行目の5列目まで続く
+        assert_eq!(8, missing.len());
+        assert_eq!(21, missing.len() + present);
+        assert_eq!((34_999_000, 139_004_000), missing[0]);
+        assert_eq!((34_998_000, 139_004_000), missing[7]);
+    }
+
+    #[test]
+    fn seam_crossing_grid_ok() {
+        // 経度359.998度から0.001度までの4 x 2の格子
+        let run_length = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let build = |reader| {
+            Grib2RecordIterBuilder::new()
+                .reader(reader)
+                .total_bytes(run_length.len())
+                .number_of_points(8)
+                .lat_max(35_000_000)
+                .lon_min(359_998_000)
+                .lon_max(1_000)
+                .lat_inc(1_000)
+                .lon_inc(1_000)
+                .nbit(4)
+                .maxv(10)
+                .level_values(&LEVEL_VALUES)
+                .build()
+                .unwrap()
+        };
+        let expected = [
+            (35_000_000, 359_998_000),
+            (35_000_000, 359_999_000),
+            (35_000_000, 0),
+            (35_000_000, 1_000),
+            (34_999_000, 359_998_000),
+            (34_999_000, 359_999_000),
+            (34_999_000, 0),
+            (34_999_000, 1_000),
+        ];
+
+        let mut reader = BufReader::new(Cursor::new(run_length.to_vec()));
+        let records = build(&mut reader)
+            .map(|r| r.map(|r| (r.lat, r.lon)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(expected.to_vec(), records);
+
+        let mut reader = BufReader::new(Cursor::new(run_length.to_vec()));
+        let runs = build(&mut reader)
+            .runs()
+            .map(|r| r.map(|r| (r.lat, r.lon)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(expected.to_vec(), runs);
+    }
+
+    #[test]
+    fn indexed_ok() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let records = builder(&mut reader)
+            .number_of_points(21)
+            .build()
+            .unwrap()
+            .indexed()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(21, records.len());
+        // 北西端の格子点が(0, 0)
+        let (row, col, record) = records[0];
+        assert_eq!(
+            (0, 0, 35_000_000, 139_000_000),
+            (row, col, record.lat, record.lon)
+        );
+        // 列は東に向かって増加し、行の終わりで次の行の最初の列に戻る
+        let (row, col, record) = records[6];
+        assert_eq!(
+            (0, 6, 35_000_000, 139_006_000),
+            (row, col, record.lat, record.lon)
+        );
+        let (row, col, record) = records[7];
+        assert_eq!(
+            (1, 0, 34_999_000, 139_000_000),
+            (row, col, record.lat, record.lon)
+        );
+        let (row, col, _) = records[20];
+        assert_eq!((2, 6), (row, col));
+    }
+
+    #[test]
+    fn missing_level_value_is_none() {
+        // レベル3（符号化列の最初の値）の物理値に欠測を示す値を記録
+        let mut level_values = LEVEL_VALUES;
+        level_values[2] = 0xFFFF;
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let records = builder(&mut reader)
+            .number_of_points(21)
+            .level_values(&level_values)
+            .build()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(None, records[0].value);
+        assert!(records.iter().any(|r| r.value == Some(90)));
+    }
+
+    #[test]
+    fn level_value_is_missing_ok() {
+        use super::LevelValue;
+
+        assert!((-i16::MAX).is_missing());
+        assert!(!(-1i16).is_missing());
+        assert!(u16::MAX.is_missing());
+        assert!(!0u16.is_missing());
+    }
+
+    #[test]
+    fn southern_hemisphere_grid_ok() {
+        use crate::readers::utils::i32_to_sign_magnitude;
+
+        // 北緯0.001度から南緯0.001度まで、赤道を跨ぐ7 x 3の格子
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let records = builder(&mut reader)
+            .number_of_points(21)
+            .lat_max(1_000)
+            .signed_lat(true)
+            .build()
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(1_000, records[0].lat);
+        assert_eq!(0, records[7].lat);
+        assert_eq!(-1_000, records[14].lat);
+
+        // 格子全体が南半球にある場合
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let runs = builder(&mut reader)
+            .number_of_points(21)
+            .lat_max(i32_to_sign_magnitude(-33_000_000))
+            .signed_lat(true)
+            .build()
+            .unwrap()
+            .runs()
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        let last = runs.last().unwrap();
+        assert_eq!(-33_002_000, last.lat);
+    }
+
+    #[test]
+    fn lon_i32_ok() {
+        let record = Grib2Record {
+            lat: 0,
+            lon: 359_000_000,
+            value: Some(0u16),
+        };
+        assert_eq!(-1_000_000, record.lon_i32());
+        let record = Grib2Record {
+            lon: 139_000_000,
+            ..record
+        };
+        assert_eq!(139_000_000, record.lon_i32());
+    }
+
+    #[test]
+    fn value_fn_ok() {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Category {
+            Low,
+            High,
+        }
+
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let values = Grib2RecordIterBuilder::new()
+            .reader(&mut reader)
+            .total_bytes(RUN_LENGTH.len())
+            .number_of_points(21)
+            .lat_max(35_000_000)
+            .lon_min(139_000_000)
+            .lon_max(139_006_000)
+            .lat_inc(1_000)
+            .lon_inc(1_000)
+            .nbit(4)
+            .maxv(10)
+            .value_fn(|level| match level {
+                0 => None,
+                1..=5 => Some(Category::Low),
+                _ => Some(Category::High),
+            })
+            .build()
+            .unwrap()
+            .map(|r| r.unwrap().value)
+            .collect::<Vec<_>>();
+        assert_eq!(21, values.len());
+        assert_eq!(Some(Category::Low), values[0]);
+        assert_eq!(Some(Category::High), values[1]);
+        assert_eq!(None, values[11]);
+    }
 
     #[test]
     fn expand_run_length0_ok() {
@@ -480,4 +1517,49 @@ mod tests {
         let expected = (0u16, 8u32);
         assert_eq!(expected, expand_run_length(&values, maxv, lngu));
     }
+
+    #[test]
+    fn validate_run_length_ok() {
+        let maxv = 10;
+        let lngu = 2u16.pow(4) - 1 - maxv;
+        assert!(validate_run_length(&RUN_LENGTH, maxv, lngu, 21).is_ok());
+    }
+
+    #[test]
+    fn validate_corrupt_run_length_err() {
+        let maxv = 10;
+        let lngu = 2u16.pow(4) - 1 - maxv;
+        // 最後のレベル値が欠落している
+        let truncated = &RUN_LENGTH[..RUN_LENGTH.len() - 1];
+        assert!(validate_run_length(truncated, maxv, lngu, 21).is_err());
+        // 最初の値がランレングス値
+        let mut corrupt = RUN_LENGTH.to_vec();
+        corrupt[0] = 15;
+        assert!(validate_run_length(&corrupt, maxv, lngu, 21).is_err());
+        // 資料点数と一致しない
+        assert!(validate_run_length(&RUN_LENGTH, maxv, lngu, 22).is_err());
+    }
+
+    #[test]
+    fn empty_level_values_err() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let result = builder(&mut reader)
+            .number_of_points(21)
+            .level_values(&[])
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn stats_after_iteration_ok() {
+        let mut reader = BufReader::new(Cursor::new(RUN_LENGTH.to_vec()));
+        let mut iter = builder(&mut reader).number_of_points(21).build().unwrap();
+        assert!(iter.stats().is_none());
+
+        assert_eq!(21, iter.by_ref().filter(|r| r.is_ok()).count());
+        let stats = iter.stats().unwrap();
+        // {3}, {9, 12}, {6}, {4, 15}, {2}, {1}, {0, 13, 12}, {2}, {3}の9セット
+        assert_eq!(9, stats.sets_expanded);
+        assert_eq!(RUN_LENGTH.len(), stats.bytes_read);
+    }
 }
@@ -1,3 +1,4 @@
+use std::fmt::Write;
 use std::io::{BufReader, Read, Seek};
 
 mod section0;
@@ -10,17 +11,49 @@ mod section6;
 mod section7;
 mod section8;
 
-use crate::Grib2Result;
-pub use section0::Section0;
-pub use section1::Section1;
+use crate::{Grib2Error, Grib2Result};
+pub use section0::{Discipline, Section0};
+pub use section1::{ProductionStatus, Section1};
 pub use section2::Section2;
-pub use section3::{Section3, Section3_0};
-pub use section4::{Section4, Section4_0, Section4_50000, Section4_50008, Section4_50009};
+pub use section3::{Section3, Section3_0, DEFAULT_EARTH_RADIUS};
+pub(crate) use section4::forecast_duration;
+pub use section4::{
+    Section4, Section4_0, Section4_50000, Section4_50008, Section4_50009, TimeRangeSpec,
+};
 pub use section5::{Section5, Section5_200i16, Section5_200u16};
 pub use section6::Section6;
 pub use section7::{Section7, Section7_200};
 pub use section8::Section8;
 
+/// 節のフィールドを列挙するトレイト
+///
+/// 具体的な節の型を知らなくても、節が記録しているフィールドを名前と値の組で列挙できる。
+pub trait Describe {
+    /// 節のフィールドの名前と、値を文字列に変換した組を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * フィールドの名前と値の文字列の組を格納したベクター
+    fn fields(&self) -> Vec<(&'static str, String)>;
+}
+
+/// 節の名前と、節のフィールドの名前と値の文字列の組
+pub type SectionDescription = (&'static str, Vec<(&'static str, String)>);
+
+/// 節の名前とフィールドを組にして返す。
+///
+/// # 引数
+///
+/// * `name` - 節の名前
+/// * `section` - 節
+///
+/// # 戻り値
+///
+/// * 節の名前とフィールドの組
+pub(crate) fn describe<S: Describe>(name: &'static str, section: &S) -> SectionDescription {
+    (name, section.fields())
+}
+
 /// GRIB2のテンプレートに実装するトレイト
 pub trait TemplateReader {
     /// GRIB2のテンプレートを読み込む。
@@ -56,3 +89,88 @@ pub trait TemplateReaderWithBytes {
     where
         Self: Sized;
 }
+
+/// 16進ダンプの1行に出力するバイト数
+const HEX_DUMP_BYTES_PER_LINE: usize = 16;
+
+/// GRIB2ファイルから節のバイト列を読み込み、オフセット、16進数及びASCII文字で表現したダンプ
+/// 文字列を返す。
+///
+/// 節を読み込めない原因を調査するときに使用する。ダンプの形式は`hexdump -C`と同様で、
+/// オフセットは読み込みを開始した位置からの相対位置である。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+/// * `section_bytes` - 読み込むバイト数
+///
+/// # 戻り値
+///
+/// * ダンプ文字列
+pub fn hex_dump<R: Read>(reader: &mut BufReader<R>, section_bytes: usize) -> Grib2Result<String> {
+    let lines = section_bytes.div_ceil(HEX_DUMP_BYTES_PER_LINE);
+    let mut dump = String::with_capacity(lines * 79);
+    let mut buf = [0u8; HEX_DUMP_BYTES_PER_LINE];
+    let mut offset = 0;
+    while offset < section_bytes {
+        let len = HEX_DUMP_BYTES_PER_LINE.min(section_bytes - offset);
+        reader.read_exact(&mut buf[..len]).map_err(|e| {
+            Grib2Error::ReadError(
+                format!("16進ダンプするバイト列の読み込みに失敗しました。{e}").into(),
+            )
+        })?;
+        // Stringへの書き込みは失敗しない
+        let _ = write!(dump, "{offset:08x} ");
+        for i in 0..HEX_DUMP_BYTES_PER_LINE {
+            if i % 8 == 0 {
+                dump.push(' ');
+            }
+            match buf[..len].get(i) {
+                Some(b) => {
+                    let _ = write!(dump, "{b:02x} ");
+                }
+                None => dump.push_str("   "),
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(buf[..len].iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+        dump.push_str("|\n");
+        offset += len;
+    }
+
+    Ok(dump)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::hex_dump;
+
+    #[test]
+    fn hex_dump_ok() {
+        let bytes = b"GRIB\x00\x00\x00\x02\x00\x00\x00\x00\x00\x00\x00\x15\x01\x02\x037777";
+        let mut reader = BufReader::new(Cursor::new(bytes.to_vec()));
+        let expected = concat!(
+            "00000000  47 52 49 42 00 00 00 02  00 00 00 00 00 00 00 15  |GRIB............|\n",
+            "00000010  01 02 03                                          |...|\n",
+        );
+        assert_eq!(expected, hex_dump(&mut reader, 19).unwrap());
+        // 読み込んだバイト数だけ読み進めている
+        let mut rest = String::new();
+        std::io::Read::read_to_string(&mut reader, &mut rest).unwrap();
+        assert_eq!("7777", rest);
+    }
+
+    #[test]
+    fn hex_dump_of_short_input_err() {
+        let mut reader = BufReader::new(Cursor::new(vec![0u8; 4]));
+        assert!(hex_dump(&mut reader, 5).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use std::io::{BufReader, Read};
 
-use crate::readers::utils::{read_bytes, read_u64, read_u8};
+use crate::readers::sections::Describe;
+use crate::readers::utils::{read_bytes, read_u64, read_u8, skip_to_grib};
 use crate::{Grib2Error, Grib2Result};
 
 /// 第0節:指示節
@@ -16,6 +17,8 @@ pub struct Section0 {
     editions: u8,
     /// GRIB報全体のバイト数
     total_bytes: usize,
+    /// 読み込みを開始した位置から`GRIB`が記録されている位置までのバイト数
+    offset: usize,
 }
 
 impl Section0 {
@@ -29,13 +32,28 @@ impl Section0 {
     ///
     /// * 第0節:指示節
     pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+        Self::from_reader_with_max_offset(reader, 0)
+    }
+
+    /// `GRIB`の前に余分なバイトが記録されている場合を考慮して、第0節:指示節を読み込む。
+    ///
+    /// 読み込みを開始した位置から最大`max_offset`バイトまで`GRIB`を探し、見つかった位置から
+    /// 第0節を読み込む。見つかった位置は[`Section0::offset`]で取得できる。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    /// * `max_offset` - `GRIB`を探す最大のバイト数
+    ///
+    /// # 戻り値
+    ///
+    /// * 第0節:指示節
+    pub(crate) fn from_reader_with_max_offset<R: Read>(
+        reader: &mut BufReader<R>,
+        max_offset: usize,
+    ) -> Grib2Result<Self> {
         // GRIB: 4バイト
-        let grib = read_bytes(reader, "第0節:GRIB", 4)?;
-        if grib != b"GRIB" {
-            return Err(Grib2Error::ReadError(
-                "第0節:GRIBは、ASCIIバイト表現で`GRIB`を記録していなければなりません。".into(),
-            ));
-        }
+        let offset = skip_to_grib(reader, max_offset)?;
         // 保留: 2バイト
         let reserved = read_bytes(reader, "第0節:保留", 2)?;
         // 資料分野: 1バイト
@@ -46,11 +64,12 @@ impl Section0 {
         let total_bytes = read_u64(reader, "第0節:GRIB報全体の長さ")? as usize;
 
         Ok(Self {
-            grib: grib.try_into().unwrap(),
+            grib: *b"GRIB",
             reserved: reserved.try_into().unwrap(),
             field,
             editions,
             total_bytes,
+            offset,
         })
     }
 
@@ -64,11 +83,24 @@ impl Section0 {
         &self.reserved
     }
 
+    /// 保留（第0節 5-6オクテット）を、ビッグエンディアンの符号なし整数として返す。
+    ///
+    /// GRIB2（第2版）では保留とされ、意味は定められていない。気象庁のファイルでは、
+    /// すべてのビットが1の`0xFFFF`が記録されている。
+    pub fn reserved_as_u16(&self) -> u16 {
+        u16::from_be_bytes(self.reserved)
+    }
+
     /// 資料分野を返す。
     pub fn field(&self) -> u8 {
         self.field
     }
 
+    /// 資料分野を符号表0.0の列挙型で返す。
+    pub fn discipline(&self) -> Grib2Result<Discipline> {
+        Discipline::try_from(self.field)
+    }
+
     /// GRIB版番号を返す。
     pub fn editions(&self) -> u8 {
         self.editions
@@ -78,4 +110,135 @@ impl Section0 {
     pub fn total_bytes(&self) -> usize {
         self.total_bytes
     }
+
+    /// 読み込みを開始した位置から`GRIB`が記録されている位置までのバイト数を返す。
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// 資料分野（符号表0.0）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Discipline {
+    /// 気象プロダクト
+    Meteorological = 0,
+    /// 水文プロダクト
+    Hydrological = 1,
+    /// 陸面プロダクト
+    LandSurface = 2,
+    /// 衛星リモートセンシングプロダクト
+    SatelliteRemoteSensing = 3,
+    /// 宇宙天気プロダクト
+    SpaceWeather = 4,
+    /// 海洋プロダクト
+    Oceanographic = 10,
+    /// 健康及び社会経済への影響
+    HealthAndSocioeconomicImpacts = 20,
+    /// 欠測値
+    Missing = 255,
+}
+
+impl TryFrom<u8> for Discipline {
+    type Error = Grib2Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Meteorological),
+            1 => Ok(Self::Hydrological),
+            2 => Ok(Self::LandSurface),
+            3 => Ok(Self::SatelliteRemoteSensing),
+            4 => Ok(Self::SpaceWeather),
+            10 => Ok(Self::Oceanographic),
+            20 => Ok(Self::HealthAndSocioeconomicImpacts),
+            255 => Ok(Self::Missing),
+            _ => Err(Grib2Error::ConvertError(
+                format!("`{value}`を`Discipline`型に変換できません。").into(),
+            )),
+        }
+    }
+}
+
+impl Describe for Section0 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("grib", format!("{:?}", self.grib())),
+            ("reserved", format!("{:?}", self.reserved())),
+            ("field", self.field().to_string()),
+            ("editions", self.editions().to_string()),
+            ("total_bytes", self.total_bytes().to_string()),
+            ("offset", self.offset().to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::{Discipline, Section0};
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    /// 解析雨量ファイルの先頭に8バイトの余分なバイトを付加したリーダーを返す。
+    fn reader_with_junk() -> BufReader<Cursor<Vec<u8>>> {
+        let mut bytes = b"\xEF\xBB\xBFjunk\x00".to_vec();
+        assert_eq!(8, bytes.len());
+        bytes.extend(std::fs::read(PRR_FILE).unwrap());
+        BufReader::new(Cursor::new(bytes))
+    }
+
+    #[test]
+    fn from_reader_with_max_offset_skips_junk() {
+        let section0 = Section0::from_reader_with_max_offset(&mut reader_with_junk(), 16).unwrap();
+        assert_eq!(8, section0.offset());
+        assert_eq!(b"GRIB", section0.grib());
+        assert_eq!(2, section0.editions());
+    }
+
+    #[test]
+    fn from_reader_with_junk_err() {
+        assert!(Section0::from_reader(&mut reader_with_junk()).is_err());
+        assert!(Section0::from_reader_with_max_offset(&mut reader_with_junk(), 7).is_err());
+    }
+
+    #[test]
+    fn discipline_ok() {
+        let cases = [
+            (0, Discipline::Meteorological),
+            (1, Discipline::Hydrological),
+            (2, Discipline::LandSurface),
+            (3, Discipline::SatelliteRemoteSensing),
+            (4, Discipline::SpaceWeather),
+            (10, Discipline::Oceanographic),
+            (20, Discipline::HealthAndSocioeconomicImpacts),
+            (255, Discipline::Missing),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(expected, Discipline::try_from(code).unwrap());
+        }
+    }
+
+    #[test]
+    fn discipline_of_unknown_code_err() {
+        assert!(Discipline::try_from(5).is_err());
+    }
+
+    #[test]
+    fn reserved_as_u16_ok() {
+        let mut reader = BufReader::new(std::fs::File::open(PRR_FILE).unwrap());
+        let section0 = Section0::from_reader(&mut reader).unwrap();
+        assert_eq!(&[0xFF, 0xFF], section0.reserved());
+        assert_eq!(0xFFFF, section0.reserved_as_u16());
+    }
+
+    #[test]
+    fn discipline_of_prr_is_meteorological() {
+        let mut reader = BufReader::new(std::fs::File::open(PRR_FILE).unwrap());
+        let section0 = Section0::from_reader(&mut reader).unwrap();
+        assert_eq!(Discipline::Meteorological, section0.discipline().unwrap());
+    }
 }
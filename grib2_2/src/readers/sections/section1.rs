@@ -2,11 +2,12 @@ use std::io::{BufReader, Read};
 
 use time::OffsetDateTime;
 
-use crate::readers::utils::{read_date_time, read_u16, read_u8, validate_u32, validate_u8};
-use crate::Grib2Result;
+use crate::readers::sections::Describe;
+use crate::readers::utils::{read_bytes, read_date_time, read_u16, read_u32, read_u8, validate_u8};
+use crate::{Grib2Error, Grib2Result};
 
-/// 第1節:節の長さ（バイト）
-const SECTION1_BYTES: u32 = 21;
+/// 第1節:標準で定義されている節の長さ（バイト）
+const SECTION1_BYTES: usize = 21;
 
 /// 第1節:識別節
 #[derive(Debug, Clone, Copy)]
@@ -34,6 +35,9 @@ pub struct Section1 {
 impl Section1 {
     /// 第1節:識別節を読み込む。
     ///
+    /// 節の長さが21バイトより長い場合は、標準で定義されている21バイトを読み込み、残りの
+    /// バイトを読み飛ばす。
+    ///
     /// # 引数
     ///
     /// * `reader` - GRIB2ファイルリーダー
@@ -43,7 +47,13 @@ impl Section1 {
     /// * 第1節:識別節
     pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
         // 節の長さ: 4bytes
-        let section_bytes = validate_u32(reader, SECTION1_BYTES, "第1節:節の長さ")? as usize;
+        let section_bytes = read_u32(reader, "第1節:節の長さ")? as usize;
+        if section_bytes < SECTION1_BYTES {
+            return Err(Grib2Error::ReadError(
+                format!("第1節:節の長さ({section_bytes})が{SECTION1_BYTES}バイトより短いです。")
+                    .into(),
+            ));
+        }
         // 節番号
         validate_u8(reader, 1, "第1節:節番号")?;
         // 作成中枢の識別: 2bytes
@@ -62,6 +72,10 @@ impl Section1 {
         let production_status_of_processed_data = read_u8(reader, "第1節:作成ステータス")?;
         // 資料の種類
         let type_of_processed_data = read_u8(reader, "第1節:資料の種類")?;
+        // 地域で拡張されたバイトなど、標準で定義されていないバイトを読み飛ばす
+        if SECTION1_BYTES < section_bytes {
+            read_bytes(reader, "第1節:予約", section_bytes - SECTION1_BYTES)?;
+        }
 
         Ok(Self {
             section_bytes,
@@ -116,8 +130,171 @@ impl Section1 {
         self.production_status_of_processed_data
     }
 
+    /// 作成ステータスを符号表1.3の列挙型で返す。
+    pub fn production_status(&self) -> Grib2Result<ProductionStatus> {
+        ProductionStatus::try_from(self.production_status_of_processed_data)
+    }
+
     /// 資料の種類を返す。
     pub fn type_of_processed_data(&self) -> u8 {
         self.type_of_processed_data
     }
 }
+
+/// 作成ステータス（符号表1.3）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ProductionStatus {
+    /// 現業プロダクト
+    Operational = 0,
+    /// 現業試験プロダクト
+    OperationalTest = 1,
+    /// 研究プロダクト
+    Research = 2,
+    /// 再解析プロダクト
+    ReAnalysis = 3,
+    /// THORPEX双方向全球アンサンブル（TIGGE）
+    Tigge = 4,
+    /// THORPEX双方向全球アンサンブル（TIGGE）試験
+    TiggeTest = 5,
+    /// S2S現業プロダクト
+    S2sOperational = 6,
+    /// S2S試験プロダクト
+    S2sTest = 7,
+    /// 領域再解析アンサンブルの不確実性プロジェクト（UERRA）
+    Uerra = 8,
+    /// 領域再解析アンサンブルの不確実性プロジェクト（UERRA）試験
+    UerraTest = 9,
+    /// 欠測値
+    Missing = 255,
+}
+
+impl TryFrom<u8> for ProductionStatus {
+    type Error = Grib2Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Operational),
+            1 => Ok(Self::OperationalTest),
+            2 => Ok(Self::Research),
+            3 => Ok(Self::ReAnalysis),
+            4 => Ok(Self::Tigge),
+            5 => Ok(Self::TiggeTest),
+            6 => Ok(Self::S2sOperational),
+            7 => Ok(Self::S2sTest),
+            8 => Ok(Self::Uerra),
+            9 => Ok(Self::UerraTest),
+            255 => Ok(Self::Missing),
+            _ => Err(Grib2Error::ConvertError(
+                format!("`{value}`を`ProductionStatus`型に変換できません。").into(),
+            )),
+        }
+    }
+}
+
+impl Describe for Section1 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            ("center", self.center().to_string()),
+            ("sub_center", self.sub_center().to_string()),
+            ("table_version", self.table_version().to_string()),
+            (
+                "local_table_version",
+                self.local_table_version().to_string(),
+            ),
+            (
+                "significance_of_reference_time",
+                self.significance_of_reference_time().to_string(),
+            ),
+            ("referenced_at", self.referenced_at().to_string()),
+            (
+                "production_status_of_processed_data",
+                self.production_status_of_processed_data().to_string(),
+            ),
+            (
+                "type_of_processed_data",
+                self.type_of_processed_data().to_string(),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::{ProductionStatus, Section1};
+    use crate::readers::sections::Describe;
+    use crate::readers::PrrReader;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    #[test]
+    fn production_status_operational_ok() {
+        assert_eq!(
+            ProductionStatus::Operational,
+            ProductionStatus::try_from(0).unwrap()
+        );
+    }
+
+    #[test]
+    fn production_status_operational_test_ok() {
+        assert_eq!(
+            ProductionStatus::OperationalTest,
+            ProductionStatus::try_from(1).unwrap()
+        );
+    }
+
+    #[test]
+    fn production_status_reserved_err() {
+        assert!(ProductionStatus::try_from(100).is_err());
+    }
+
+    #[test]
+    fn fields_include_center() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let fields = reader.section1().fields();
+        let center = fields.iter().find(|(name, _)| *name == "center").unwrap();
+        assert_eq!(reader.section1().center().to_string(), center.1);
+    }
+
+    #[test]
+    fn read_longer_section1_ok() {
+        let bytes = std::fs::read(PRR_FILE).unwrap();
+        // 第0節（16バイト）の直後から第1節を取り出し、4バイトの予約を追加して25バイトにする
+        let mut section1 = bytes[16..16 + 21].to_vec();
+        section1[..4].copy_from_slice(&25u32.to_be_bytes());
+        section1.extend([0xFF; 4]);
+        // 後続のバイトが読み飛ばされていないことを確認するため、目印を追加
+        section1.push(0xAB);
+        let mut reader = BufReader::new(Cursor::new(section1));
+        let section1 = Section1::from_reader(&mut reader).unwrap();
+        let expected = PrrReader::new(PRR_FILE).unwrap();
+
+        assert_eq!(25, section1.section_bytes());
+        assert_eq!(
+            expected.section1().referenced_at(),
+            section1.referenced_at()
+        );
+        assert_eq!(
+            expected.section1().type_of_processed_data(),
+            section1.type_of_processed_data()
+        );
+        let mut rest = vec![];
+        std::io::Read::read_to_end(&mut reader, &mut rest).unwrap();
+        assert_eq!(vec![0xAB], rest);
+    }
+
+    #[test]
+    fn read_shorter_section1_err() {
+        let bytes = std::fs::read(PRR_FILE).unwrap();
+        let mut section1 = bytes[16..16 + 21].to_vec();
+        section1[..4].copy_from_slice(&20u32.to_be_bytes());
+        let mut reader = BufReader::new(Cursor::new(section1));
+        assert!(Section1::from_reader(&mut reader).is_err());
+    }
+}
@@ -1,3 +1,109 @@
+use std::io::{BufReader, Read, Seek};
+
+use crate::readers::sections::Describe;
+use crate::readers::utils::{peek_section_number, read_bytes, read_u32, validate_u8};
+use crate::{Grib2Error, Grib2Result};
+
 /// 第2節:地域使用節（不使用）
+///
+/// 気象庁のGRIB2ファイルは第2節を記録しないが、記録されている場合は内容を読み飛ばす。
 #[derive(Debug, Clone, Copy)]
-pub struct Section2;
+pub struct Section2 {
+    /// 節の長さ（バイト）
+    section_bytes: usize,
+}
+
+impl Section2 {
+    /// 第2節:地域使用節を読み込む。
+    ///
+    /// 次に記録されている節の節番号が2の場合は節を読み飛ばし、それ以外の場合はファイル
+    /// ポインターを移動しない。
+    ///
+    /// # 引数
+    ///
+    /// * `reader` - GRIB2ファイルリーダー
+    ///
+    /// # 戻り値
+    ///
+    /// * 第2節:地域使用節
+    pub(crate) fn from_reader<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+        if peek_section_number(reader)? != 2 {
+            return Ok(Self { section_bytes: 0 });
+        }
+        // 節の長さ: 4bytes
+        let section_bytes = read_u32(reader, "第2節:節の長さ")? as usize;
+        // 節番号: 1byte
+        validate_u8(reader, 2, "第2節:節番号")?;
+        // 地域使用: 節の長さ - 5bytes
+        let remaining = section_bytes.checked_sub(5).ok_or_else(|| {
+            Grib2Error::ReadError(format!("第2節:節の長さ({section_bytes})が不正です。").into())
+        })?;
+        read_bytes(reader, "第2節:地域使用", remaining)?;
+
+        Ok(Self { section_bytes })
+    }
+
+    /// 節の長さ（バイト）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 節の長さ（バイト）。第2節が記録されていない場合は0
+    pub fn section_bytes(&self) -> usize {
+        self.section_bytes
+    }
+
+    /// 第2節が記録されているかを返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 第2節が記録されている場合は`true`
+    pub fn is_present(&self) -> bool {
+        0 < self.section_bytes
+    }
+}
+
+impl Describe for Section2 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        if !self.is_present() {
+            return vec![];
+        }
+
+        vec![("section_bytes", self.section_bytes().to_string())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor, Read};
+
+    use super::Section2;
+
+    #[test]
+    fn read_present_section2_ok() {
+        // 7バイトの第2節の後に、第3節の節の長さの先頭バイトを記録
+        let bytes = vec![0, 0, 0, 7, 2, 0xAA, 0xBB, 0xCC];
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section2 = Section2::from_reader(&mut reader).unwrap();
+        assert!(section2.is_present());
+        assert_eq!(7, section2.section_bytes());
+
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(vec![0xCC], rest);
+    }
+
+    #[test]
+    fn read_absent_section2_ok() {
+        // 第3節の節の長さ及び節番号
+        let bytes = vec![0, 0, 0, 72, 3, 0];
+        let mut reader = BufReader::new(Cursor::new(bytes.clone()));
+        let section2 = Section2::from_reader(&mut reader).unwrap();
+        assert!(!section2.is_present());
+        assert_eq!(0, section2.section_bytes());
+
+        // ファイルポインターは移動しない
+        let mut rest = vec![];
+        reader.read_to_end(&mut rest).unwrap();
+        assert_eq!(bytes, rest);
+    }
+}
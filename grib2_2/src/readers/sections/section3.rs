@@ -1,11 +1,19 @@
 use std::io::{BufReader, Read};
 
-use crate::readers::sections::TemplateReader;
-use crate::readers::utils::{read_u16, read_u32, read_u8, validate_u8};
-use crate::Grib2Result;
+use crate::readers::sections::{Describe, TemplateReader};
+use crate::readers::utils::{
+    i32_to_sign_magnitude, read_u16, read_u32, read_u8, sign_magnitude_to_i32, validate_u8,
+};
+use crate::{Grib2Error, Grib2Result};
+
+/// 第3節の節の長さから格子系定義テンプレート番号までのバイト数
+const SECTION3_HEADER_BYTES: usize = 14;
+
+/// 地球の形状が球体でない場合に使用する、地球の半径の既定値（メートル、平均半径）
+pub const DEFAULT_EARTH_RADIUS: f64 = 6_371_000.0;
 
 /// 第3節:格子系定義節
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Section3<T>
 where
     T: TemplateReader,
@@ -24,6 +32,14 @@ where
     grid_definition_template_number: u16,
     /// テンプレート3
     template3: T,
+    /// 格子点数を定義するリスト（準規則格子の行ごとの格子点数）
+    number_of_points_list: Vec<u32>,
+}
+
+/// 格子系定義テンプレートに実装するトレイト
+pub(crate) trait GridDefinitionTemplate {
+    /// テンプレートのバイト数
+    const TEMPLATE_BYTES: usize;
 }
 
 impl<T> Section3<T>
@@ -39,7 +55,10 @@ where
     /// # 戻り値
     ///
     /// * 第3節:格子系定義節
-    pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+    pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self>
+    where
+        T: GridDefinitionTemplate,
+    {
         // 節の長さ: 4バイト
         let section_bytes = read_u32(reader, "第3節:節の長さ")? as usize;
         // 節番号: 1バイト
@@ -58,6 +77,16 @@ where
         let grid_definition_template_number = read_u16(reader, "第3節:格子系定義テンプレート番号")?;
         // テンプレート3
         let template3 = T::from_reader(reader)?;
+        // 格子点数を定義するリスト
+        let list_bytes = section_bytes
+            .checked_sub(SECTION3_HEADER_BYTES + T::TEMPLATE_BYTES)
+            .ok_or_else(|| {
+                Grib2Error::ReadError(
+                    format!("第3節:節の長さ`{section_bytes}`がテンプレートより短いです。").into(),
+                )
+            })?;
+        let number_of_points_list =
+            read_number_of_points_list(reader, number_of_octets_for_number_of_points, list_bytes)?;
 
         Ok(Self {
             section_bytes,
@@ -67,6 +96,7 @@ where
             description_of_number_of_points,
             grid_definition_template_number,
             template3,
+            number_of_points_list,
         })
     }
 
@@ -99,6 +129,58 @@ where
     pub fn grid_definition_template_number(&self) -> u16 {
         self.grid_definition_template_number
     }
+
+    /// 格子点数を定義するリストを返す。
+    ///
+    /// 準規則格子の場合は行ごとの格子点数を返す。規則格子の場合は空のスライスを返す。
+    pub fn number_of_points_list(&self) -> &[u32] {
+        &self.number_of_points_list
+    }
+}
+
+/// テンプレートの後に記録されている格子点数を定義するリストを読み込む。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2リーダー
+/// * `octets` - 格子点数を定義するリストのオクテット数
+/// * `list_bytes` - テンプレートの後に記録されているバイト数
+///
+/// # 戻り値
+///
+/// * 格子点数を定義するリスト
+fn read_number_of_points_list<R: Read>(
+    reader: &mut BufReader<R>,
+    octets: u8,
+    list_bytes: usize,
+) -> Grib2Result<Vec<u32>> {
+    let name = "第3節:格子点数を定義するリスト";
+    if octets == 0 {
+        if 0 < list_bytes {
+            return Err(Grib2Error::ReadError(
+                format!("{name}のオクテット数が0ですが、テンプレートの後に{list_bytes}バイト記録されています。").into(),
+            ));
+        }
+        return Ok(vec![]);
+    }
+    if !matches!(octets, 1 | 2 | 4) {
+        return Err(Grib2Error::ReadError(
+            format!("{name}のオクテット数`{octets}`に対応していません。").into(),
+        ));
+    }
+    if !list_bytes.is_multiple_of(octets as usize) {
+        return Err(Grib2Error::ReadError(
+            format!("{name}のバイト数`{list_bytes}`がオクテット数`{octets}`の倍数ではありません。")
+                .into(),
+        ));
+    }
+    (0..list_bytes / octets as usize)
+        .map(|_| match octets {
+            1 => read_u8(reader, name).map(u32::from),
+            2 => read_u16(reader, name).map(u32::from),
+            _ => read_u32(reader, name),
+        })
+        .collect()
 }
 
 /// テンプレート3.0
@@ -228,6 +310,10 @@ impl TemplateReader for Template3_0 {
     }
 }
 
+impl GridDefinitionTemplate for Template3_0 {
+    const TEMPLATE_BYTES: usize = 58;
+}
+
 pub type Section3_0 = Section3<Template3_0>;
 
 impl Section3_0 {
@@ -287,13 +373,16 @@ impl Section3_0 {
     }
 
     /// 最初の格子点の緯度（1e-6度単位）を返す。
+    ///
+    /// 格子点の座標及び増分を返すメソッドは、原作成領域の基本角が0以外の場合、基本角及び
+    /// 基本角の細分を適用して1e-6度単位に変換した値を返す。
     pub fn lat_of_first_grid_point(&self) -> u32 {
-        self.template3.lat_of_first_grid_point
+        self.to_micro_degrees(self.template3.lat_of_first_grid_point)
     }
 
     /// 最初の格子点の経度（1e-6度単位）を返す。
     pub fn lon_of_first_grid_point(&self) -> u32 {
-        self.template3.lon_of_first_grid_point
+        self.to_micro_degrees(self.template3.lon_of_first_grid_point)
     }
 
     /// 分解能及び成分フラグを返す。
@@ -303,26 +392,553 @@ impl Section3_0 {
 
     /// 最後の格子点の緯度（1e-6度単位）を返す。
     pub fn lat_of_last_grid_point(&self) -> u32 {
-        self.template3.lat_of_last_grid_point
+        self.to_micro_degrees(self.template3.lat_of_last_grid_point)
+    }
+
+    /// 最初の格子点の符号付きの緯度（1e-6度単位）を返す。
+    ///
+    /// GRIB2は緯度を最上位ビットを符号とする符号と絶対値で記録するため、南半球の格子は
+    /// このメソッドで緯度を取得する。南緯は負の値である。
+    pub fn lat_of_first_grid_point_i32(&self) -> i32 {
+        sign_magnitude_to_i32(self.lat_of_first_grid_point())
+    }
+
+    /// 最後の格子点の符号付きの緯度（1e-6度単位）を返す。
+    pub fn lat_of_last_grid_point_i32(&self) -> i32 {
+        sign_magnitude_to_i32(self.lat_of_last_grid_point())
+    }
+
+    /// 格子が南半球の格子点を含むかを返す。
+    ///
+    /// レコードイテレーターを構築するときに`Grib2RecordIterBuilder::signed_lat`に設定する。
+    pub fn has_southern_grid_points(&self) -> bool {
+        self.lat_of_first_grid_point_i32() < 0 || self.lat_of_last_grid_point_i32() < 0
     }
 
     /// 最後の格子点の経度（1e-6度単位）を返す。
     pub fn lon_of_last_grid_point(&self) -> u32 {
-        self.template3.lon_of_last_grid_point
+        self.to_micro_degrees(self.template3.lon_of_last_grid_point)
     }
 
     /// i方向（経度方向）の増分（1e-6度単位）を返す。
     pub fn i_direction_increment(&self) -> u32 {
-        self.template3.i_direction_increment
+        self.to_micro_degrees(self.template3.i_direction_increment)
     }
 
     /// j方向（緯度方向）の増分（1e-6度単位）を返す。
     pub fn j_direction_increment(&self) -> u32 {
-        self.template3.j_direction_increment
+        self.to_micro_degrees(self.template3.j_direction_increment)
     }
 
     /// 走査モードを返す。
     pub fn scanning_mode(&self) -> u8 {
         self.template3.scanning_mode
     }
+
+    /// 地球の形状が球体の場合に、地球球体の半径（メートル）を返す。
+    ///
+    /// # 戻り値
+    ///
+    /// * 地球球体の半径（メートル）、地球の形状が球体でない場合は`None`
+    pub fn earth_radius(&self) -> Option<f64> {
+        let t = &self.template3;
+        match t.shape_of_earth {
+            0 => Some(6_367_470.0),
+            1 => Some(
+                t.scaled_value_of_radius_of_spherical_earth as f64
+                    / 10f64.powi(t.scale_factor_of_radius_of_spherical_earth as i32),
+            ),
+            6 => Some(6_371_229.0),
+            8 => Some(6_371_200.0),
+            _ => None,
+        }
+    }
+
+    /// 格子の中央の緯度における、格子間隔のおおよその長さ（キロメートル）を返す。
+    ///
+    /// 地球を球体とみなして計算する。地球の半径は、`earth_radius`を指定した場合はその値を、
+    /// 指定しない場合は第3節に記録された地球球体の半径を使用する。地球の形状が球体でない
+    /// 場合は[`DEFAULT_EARTH_RADIUS`]を使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `earth_radius` - 計算に使用する地球の半径（メートル）
+    ///
+    /// # 戻り値
+    ///
+    /// * 経度方向及び緯度方向の格子間隔（キロメートル）
+    pub fn approx_resolution_km(&self, earth_radius: Option<f64>) -> (f64, f64) {
+        let radius = earth_radius
+            .or_else(|| self.earth_radius())
+            .unwrap_or(DEFAULT_EARTH_RADIUS);
+        let center_lat = (self.lat_of_first_grid_point_i32() as f64
+            + self.lat_of_last_grid_point_i32() as f64)
+            / 2e6;
+        let lon_inc = (self.i_direction_increment() as f64 / 1e6).to_radians();
+        let lat_inc = (self.j_direction_increment() as f64 / 1e6).to_radians();
+
+        (
+            radius * lon_inc * center_lat.to_radians().cos() / 1_000.0,
+            radius * lat_inc / 1_000.0,
+        )
+    }
+
+    /// 格子間隔から、プロダクトの空間解像度を表すラベルを返す。
+    ///
+    /// 気象庁の格子は、緯度方向の格子間隔により次のとおり判定する。判定は格子間隔の
+    /// ±10%の範囲で行う。
+    ///
+    /// | ラベル | 経度方向の格子間隔 | 緯度方向の格子間隔 |
+    /// |--------|--------------------|--------------------|
+    /// | `"250m"` | 11.25秒（0.003125度） | 7.5秒（約0.002083度） |
+    /// | `"1km"` | 45秒（0.0125度） | 30秒（約0.008333度） |
+    /// | `"5km"` | 225秒（0.0625度） | 150秒（約0.041667度） |
+    ///
+    /// # 戻り値
+    ///
+    /// * `"250m"`、`"1km"`、`"5km"`、または上記に該当しない場合は`"unknown"`
+    pub fn resolution_label(&self) -> &'static str {
+        match self.j_direction_increment() {
+            1_875..=2_291 => "250m",
+            7_500..=9_166 => "1km",
+            37_500..=45_833 => "5km",
+            _ => "unknown",
+        }
+    }
+
+    /// 第3節に記録されている角度を1e-6度単位に変換する。
+    ///
+    /// 原作成領域の基本角が0または欠測値の場合、角度は1e-6度単位で記録されている。
+    /// それ以外の場合、角度は`基本角 / 基本角の細分`度単位で記録されている。
+    ///
+    /// # 引数
+    ///
+    /// * `value` - 第3節に記録されている角度
+    ///
+    /// # 戻り値
+    ///
+    /// * 1e-6度単位の角度
+    fn to_micro_degrees(&self, value: u32) -> u32 {
+        let basic_angle = self.template3.basic_angle_of_initial_product_domain;
+        let subdivisions = self.template3.subdivisions_of_basic_angle;
+        if basic_angle == 0
+            || basic_angle == u32::MAX
+            || subdivisions == 0
+            || subdivisions == u32::MAX
+        {
+            return value;
+        }
+
+        // 南半球の緯度の符号を示す最上位ビットは変換せずに残す
+        let sign = value & 0x8000_0000;
+        let magnitude = (value & 0x7FFF_FFFF) as u64;
+        sign | (magnitude * basic_angle as u64 * 1_000_000 / subdivisions as u64) as u32
+    }
+
+    /// 格子の一部を切り出して、一定の間隔で間引いた格子を定義する第3節を返す。
+    ///
+    /// 展開した格子を切り出したり集約したりしたときに、格子の範囲と増分を更新するために
+    /// 使用する。角度は1e-6度単位で記録し、原作成領域の基本角及び基本角の細分は0とする。
+    ///
+    /// # 引数
+    ///
+    /// * `first_row` - 新しい格子の北西端の格子点の、元の格子における行のインデックス
+    /// * `first_col` - 新しい格子の北西端の格子点の、元の格子における列のインデックス
+    /// * `ni` - 新しい格子の緯線に沿った格子点数
+    /// * `nj` - 新しい格子の経線に沿った格子点数
+    /// * `step` - 新しい格子の格子点の間隔（元の格子の格子数）
+    ///
+    /// # 戻り値
+    ///
+    /// * 新しい格子を定義する第3節:格子系定義節
+    pub(crate) fn subgrid(
+        &self,
+        first_row: u32,
+        first_col: u32,
+        ni: u32,
+        nj: u32,
+        step: u32,
+    ) -> Section3_0 {
+        const FULL_CIRCLE: i64 = 360_000_000;
+        let lat_inc = self.j_direction_increment() as i64;
+        let lon_inc = self.i_direction_increment() as i64;
+        let lat_first = self.lat_of_first_grid_point_i32() as i64 - first_row as i64 * lat_inc;
+        let lon_first = (self.lon_of_first_grid_point() as i64 + first_col as i64 * lon_inc)
+            .rem_euclid(FULL_CIRCLE);
+        let lat_last = lat_first - nj.saturating_sub(1) as i64 * step as i64 * lat_inc;
+        let lon_last = (lon_first + ni.saturating_sub(1) as i64 * step as i64 * lon_inc)
+            .rem_euclid(FULL_CIRCLE);
+
+        let mut section3 = self.clone();
+        section3.number_of_data_points = ni * nj;
+        let template3 = &mut section3.template3;
+        template3.number_of_along_lat_points = ni;
+        template3.number_of_along_lon_points = nj;
+        template3.basic_angle_of_initial_product_domain = 0;
+        template3.subdivisions_of_basic_angle = 0;
+        template3.lat_of_first_grid_point = i32_to_sign_magnitude(lat_first as i32);
+        template3.lon_of_first_grid_point = lon_first as u32;
+        template3.lat_of_last_grid_point = i32_to_sign_magnitude(lat_last as i32);
+        template3.lon_of_last_grid_point = lon_last as u32;
+        template3.i_direction_increment = (lon_inc * step as i64) as u32;
+        template3.j_direction_increment = (lat_inc * step as i64) as u32;
+
+        section3
+    }
+
+    /// 他の第3節:格子系定義節と同じ格子であるかを返す。
+    ///
+    /// 2つのファイルの値を比較または差分する前に、格子の格子点数、最初と最後の格子点の
+    /// 座標及び増分が一致していることを確認するために使用する。
+    ///
+    /// # 引数
+    ///
+    /// * `other` - 比較する第3節:格子系定義節
+    ///
+    /// # 戻り値
+    ///
+    /// * 同じ格子の場合は`true`
+    pub fn same_grid(&self, other: &Section3_0) -> bool {
+        self.validate_same_grid(other).is_ok()
+    }
+
+    /// 他の第3節:格子系定義節と同じ格子であるか確認する。
+    ///
+    /// # 引数
+    ///
+    /// * `other` - 比較する第3節:格子系定義節
+    ///
+    /// # 戻り値
+    ///
+    /// * 同じ格子の場合は`()`、異なる場合は最初に一致しなかった項目名を格納した
+    ///   `Grib2Error::GridMismatch`
+    pub fn validate_same_grid(&self, other: &Section3_0) -> Grib2Result<()> {
+        let (a, b) = (&self.template3, &other.template3);
+        let fields = [
+            (
+                "number_of_along_lat_points",
+                a.number_of_along_lat_points == b.number_of_along_lat_points,
+            ),
+            (
+                "number_of_along_lon_points",
+                a.number_of_along_lon_points == b.number_of_along_lon_points,
+            ),
+            (
+                "lat_of_first_grid_point",
+                a.lat_of_first_grid_point == b.lat_of_first_grid_point,
+            ),
+            (
+                "lon_of_first_grid_point",
+                a.lon_of_first_grid_point == b.lon_of_first_grid_point,
+            ),
+            (
+                "lat_of_last_grid_point",
+                a.lat_of_last_grid_point == b.lat_of_last_grid_point,
+            ),
+            (
+                "lon_of_last_grid_point",
+                a.lon_of_last_grid_point == b.lon_of_last_grid_point,
+            ),
+            (
+                "i_direction_increment",
+                a.i_direction_increment == b.i_direction_increment,
+            ),
+            (
+                "j_direction_increment",
+                a.j_direction_increment == b.j_direction_increment,
+            ),
+        ];
+        match fields.iter().find(|(_, same)| !same) {
+            Some((field, _)) => Err(Grib2Error::GridMismatch { field }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Describe for Section3_0 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            (
+                "source_of_grid_definition",
+                self.source_of_grid_definition().to_string(),
+            ),
+            (
+                "number_of_data_points",
+                self.number_of_data_points().to_string(),
+            ),
+            (
+                "number_of_octets_for_number_of_points",
+                self.number_of_octets_for_number_of_points().to_string(),
+            ),
+            (
+                "description_of_number_of_points",
+                self.description_of_number_of_points().to_string(),
+            ),
+            (
+                "grid_definition_template_number",
+                self.grid_definition_template_number().to_string(),
+            ),
+            (
+                "number_of_points_list",
+                format!("{:?}", self.number_of_points_list()),
+            ),
+            ("shape_of_earth", self.shape_of_earth().to_string()),
+            (
+                "scale_factor_of_radius_of_spherical_earth",
+                self.scale_factor_of_radius_of_spherical_earth().to_string(),
+            ),
+            (
+                "scaled_value_of_radius_of_spherical_earth",
+                self.scaled_value_of_radius_of_spherical_earth().to_string(),
+            ),
+            (
+                "scale_factor_of_major_axis",
+                self.scale_factor_of_major_axis().to_string(),
+            ),
+            (
+                "scaled_value_of_earth_major_axis",
+                self.scaled_value_of_earth_major_axis().to_string(),
+            ),
+            (
+                "scale_factor_of_minor_axis",
+                self.scale_factor_of_minor_axis().to_string(),
+            ),
+            (
+                "scaled_value_of_earth_minor_axis",
+                self.scaled_value_of_earth_minor_axis().to_string(),
+            ),
+            (
+                "number_of_along_lat_points",
+                self.number_of_along_lat_points().to_string(),
+            ),
+            (
+                "number_of_along_lon_points",
+                self.number_of_along_lon_points().to_string(),
+            ),
+            (
+                "basic_angle_of_initial_product_domain",
+                self.basic_angle_of_initial_product_domain().to_string(),
+            ),
+            (
+                "subdivisions_of_basic_angle",
+                self.subdivisions_of_basic_angle().to_string(),
+            ),
+            (
+                "lat_of_first_grid_point",
+                self.lat_of_first_grid_point().to_string(),
+            ),
+            (
+                "lon_of_first_grid_point",
+                self.lon_of_first_grid_point().to_string(),
+            ),
+            (
+                "resolution_and_component_flags",
+                self.resolution_and_component_flags().to_string(),
+            ),
+            (
+                "lat_of_last_grid_point",
+                self.lat_of_last_grid_point().to_string(),
+            ),
+            (
+                "lon_of_last_grid_point",
+                self.lon_of_last_grid_point().to_string(),
+            ),
+            (
+                "i_direction_increment",
+                self.i_direction_increment().to_string(),
+            ),
+            (
+                "j_direction_increment",
+                self.j_direction_increment().to_string(),
+            ),
+            ("scanning_mode", self.scanning_mode().to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::{Section3_0, DEFAULT_EARTH_RADIUS};
+    use crate::readers::PrrReader;
+    use crate::Grib2Error;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    /// 格子点数を定義するリストを持つ第3節のバイト列を返す。
+    fn section3_bytes(octets: u8, list: &[u8]) -> Vec<u8> {
+        let section_bytes = (72 + list.len()) as u32;
+        let mut bytes = section_bytes.to_be_bytes().to_vec();
+        // 節番号、格子系定義の出典
+        bytes.extend([3, 0]);
+        // 資料点数
+        bytes.extend(15u32.to_be_bytes());
+        // 格子点数を定義するリストのオクテット数及び説明
+        bytes.extend([octets, 1]);
+        // 格子系定義テンプレート番号
+        bytes.extend(0u16.to_be_bytes());
+        // テンプレート3.0
+        bytes.extend([0u8; 58]);
+        bytes.extend(list);
+        bytes
+    }
+
+    #[test]
+    fn nonzero_basic_angle_ok() {
+        // 基本角1度、細分1000（0.001度単位）
+        let mut bytes = section3_bytes(0, &[]);
+        let template = 14;
+        bytes[template + 24..template + 28].copy_from_slice(&1u32.to_be_bytes());
+        bytes[template + 28..template + 32].copy_from_slice(&1_000u32.to_be_bytes());
+        bytes[template + 32..template + 36].copy_from_slice(&35_000u32.to_be_bytes());
+        bytes[template + 36..template + 40].copy_from_slice(&139_000u32.to_be_bytes());
+        bytes[template + 49..template + 53].copy_from_slice(&10u32.to_be_bytes());
+        bytes[template + 53..template + 57].copy_from_slice(&5u32.to_be_bytes());
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+
+        assert_eq!(35_000_000, section3.lat_of_first_grid_point());
+        assert_eq!(139_000_000, section3.lon_of_first_grid_point());
+        assert_eq!(10_000, section3.i_direction_increment());
+        assert_eq!(5_000, section3.j_direction_increment());
+    }
+
+    #[test]
+    fn southern_hemisphere_lat_ok() {
+        // 基本角1度、細分1000で、南緯10度から南緯20度までの格子
+        let mut bytes = section3_bytes(0, &[]);
+        let template = 14;
+        bytes[template + 24..template + 28].copy_from_slice(&1u32.to_be_bytes());
+        bytes[template + 28..template + 32].copy_from_slice(&1_000u32.to_be_bytes());
+        bytes[template + 32..template + 36]
+            .copy_from_slice(&(0x8000_0000u32 | 10_000).to_be_bytes());
+        bytes[template + 41..template + 45]
+            .copy_from_slice(&(0x8000_0000u32 | 20_000).to_be_bytes());
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+
+        assert_eq!(-10_000_000, section3.lat_of_first_grid_point_i32());
+        assert_eq!(-20_000_000, section3.lat_of_last_grid_point_i32());
+        assert_eq!(0x8000_0000 | 10_000_000, section3.lat_of_first_grid_point());
+    }
+
+    #[test]
+    fn zero_basic_angle_keeps_micro_degrees() {
+        let mut bytes = section3_bytes(0, &[]);
+        bytes[14 + 49..14 + 53].copy_from_slice(&12_500u32.to_be_bytes());
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        assert_eq!(12_500, section3.i_direction_increment());
+    }
+
+    #[test]
+    fn quasi_regular_list_ok() {
+        let bytes = section3_bytes(2, &[0, 3, 0, 5, 0, 7]);
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        assert_eq!(&[3, 5, 7], section3.number_of_points_list());
+    }
+
+    #[test]
+    fn regular_grid_has_empty_list() {
+        let bytes = section3_bytes(0, &[]);
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+        assert!(section3.number_of_points_list().is_empty());
+    }
+
+    #[test]
+    fn unsupported_octets_err() {
+        let bytes = section3_bytes(3, &[0, 0, 3, 0, 0, 5]);
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        assert!(Section3_0::from_reader(&mut reader).is_err());
+    }
+
+    #[test]
+    fn list_not_multiple_of_octets_err() {
+        let bytes = section3_bytes(2, &[0, 3, 0]);
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        assert!(Section3_0::from_reader(&mut reader).is_err());
+    }
+
+    #[test]
+    fn approx_resolution_km_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let section3 = reader.section3();
+        // 解析雨量の地球の形状はGRS80回転楕円体であるため、既定値の半径を使用する
+        assert_eq!(None, section3.earth_radius());
+        assert_eq!(
+            section3.approx_resolution_km(Some(DEFAULT_EARTH_RADIUS)),
+            section3.approx_resolution_km(None)
+        );
+
+        // 1km格子（経度45秒、緯度30秒）
+        let (lon_km, lat_km) = section3.approx_resolution_km(None);
+        assert!((0.9..1.3).contains(&lon_km), "lon_km: {lon_km}");
+        assert!((0.9..1.0).contains(&lat_km), "lat_km: {lat_km}");
+
+        // 格子間隔は地球の半径に比例する
+        let (half_lon_km, half_lat_km) =
+            section3.approx_resolution_km(Some(DEFAULT_EARTH_RADIUS / 2.0));
+        assert!((lon_km / 2.0 - half_lon_km).abs() < 1e-9);
+        assert!((lat_km / 2.0 - half_lat_km).abs() < 1e-9);
+    }
+
+    #[test]
+    fn approx_resolution_km_of_southern_grid_ok() {
+        // 南緯50度から南緯70度までの、経度方向及び緯度方向の格子間隔が1度の格子
+        let mut bytes = section3_bytes(0, &[]);
+        let template = 14;
+        bytes[template + 32..template + 36]
+            .copy_from_slice(&(0x8000_0000u32 | 50_000_000).to_be_bytes());
+        bytes[template + 41..template + 45]
+            .copy_from_slice(&(0x8000_0000u32 | 70_000_000).to_be_bytes());
+        bytes[template + 49..template + 53].copy_from_slice(&1_000_000u32.to_be_bytes());
+        bytes[template + 53..template + 57].copy_from_slice(&1_000_000u32.to_be_bytes());
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        let section3 = Section3_0::from_reader(&mut reader).unwrap();
+
+        // 格子の中央は南緯60度
+        let (lon_km, lat_km) = section3.approx_resolution_km(Some(DEFAULT_EARTH_RADIUS));
+        let expected_lat_km = DEFAULT_EARTH_RADIUS * 1f64.to_radians() / 1_000.0;
+        assert!((expected_lat_km - lat_km).abs() < 1e-9);
+        assert!(
+            (expected_lat_km / 2.0 - lon_km).abs() < 1e-9,
+            "lon_km: {lon_km}"
+        );
+    }
+
+    #[test]
+    fn resolution_label_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        assert_eq!("1km", reader.section3().resolution_label());
+    }
+
+    #[test]
+    fn same_grid_ok() {
+        let read = |bytes: Vec<u8>| {
+            let mut reader = BufReader::new(Cursor::new(bytes));
+            Section3_0::from_reader(&mut reader).unwrap()
+        };
+        let section3 = read(section3_bytes(0, &[]));
+        assert!(section3.same_grid(&read(section3_bytes(0, &[]))));
+
+        // i方向の増分（テンプレートの50バイト目から4バイト）を変更
+        let mut bytes = section3_bytes(0, &[]);
+        bytes[14 + 49..14 + 53].copy_from_slice(&12_500u32.to_be_bytes());
+        let other = read(bytes);
+        assert_eq!(12_500, other.i_direction_increment());
+        assert!(!section3.same_grid(&other));
+        assert!(matches!(
+            section3.validate_same_grid(&other),
+            Err(Grib2Error::GridMismatch {
+                field: "i_direction_increment"
+            })
+        ));
+    }
 }
@@ -1,12 +1,12 @@
 use std::io::{BufReader, Read};
 
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 
-use crate::readers::sections::TemplateReader;
+use crate::readers::sections::{Describe, TemplateReader};
 use crate::readers::utils::{
-    read_date_time, read_i32, read_u16, read_u32, read_u64, read_u8, validate_u8,
+    read_date_time, read_f32, read_i32, read_u16, read_u32, read_u64, read_u8, validate_u8,
 };
-use crate::Grib2Result;
+use crate::{Grib2Error, Grib2Result};
 
 /// 第4節:プロダクト定義節
 pub struct Section4<T>
@@ -21,6 +21,8 @@ where
     product_definition_template_number: u16,
     /// テンプレート4
     template4: T,
+    /// テンプレート直後の座標値
+    after_template_coordinates: Vec<f32>,
 }
 
 impl<T> Section4<T>
@@ -49,12 +51,17 @@ where
             read_u16(reader, "第4節:プロダクト定義テンプレート番号")?;
         // テンプレート4
         let template4 = T::from_reader(reader)?;
+        // テンプレート直後の座標値: 4バイト x テンプレート直後の座標値の数
+        let after_template_coordinates = (0..number_of_after_template_points)
+            .map(|_| read_f32(reader, "第4節:テンプレート直後の座標値"))
+            .collect::<Grib2Result<Vec<_>>>()?;
 
         Ok(Self {
             section_bytes,
             number_of_after_template_points,
             product_definition_template_number,
             template4,
+            after_template_coordinates,
         })
     }
 
@@ -72,6 +79,11 @@ where
     pub fn product_definition_template_number(&self) -> u16 {
         self.product_definition_template_number
     }
+
+    /// テンプレート直後に記録されている、IEEE 754形式の32ビット浮動小数点数の座標値を返す。
+    pub fn after_template_coordinates(&self) -> &[f32] {
+        &self.after_template_coordinates
+    }
 }
 
 /// テンプレート4.0
@@ -177,6 +189,13 @@ impl Section4_0 {
     pub fn parameter_number(&self) -> u8 {
         self.template4.parameter_number
     }
+    /// パラメータカテゴリーとパラメータ番号に対応するパラメータ名を返す。
+    pub fn parameter_name(&self) -> Option<&'static str> {
+        parameter_name(
+            self.template4.parameter_category,
+            self.template4.parameter_number,
+        )
+    }
     /// 作成処理の種類を返す。
     pub fn type_of_generating_process(&self) -> u8 {
         self.template4.type_of_generating_process
@@ -205,6 +224,13 @@ impl Section4_0 {
     pub fn forecast_time(&self) -> i32 {
         self.template4.forecast_time
     }
+    /// 期間の単位の指示符と予報時間から、予報時間の長さを返す。
+    pub fn forecast_duration(&self) -> Grib2Result<Duration> {
+        forecast_duration(
+            self.template4.indicator_of_unit_of_time_range,
+            self.template4.forecast_time,
+        )
+    }
     /// 第一固定面の種類を返す。
     pub fn type_of_first_fixed_surface(&self) -> u8 {
         self.template4.type_of_first_fixed_surface
@@ -229,6 +255,13 @@ impl Section4_0 {
     pub fn scaled_value_of_second_fixed_surface(&self) -> u32 {
         self.template4.scaled_value_of_second_fixed_surface
     }
+
+    /// 値が統計処理期間の積算値であるかを返す。
+    ///
+    /// このテンプレートは統計処理の情報を持たず、値は瞬間値であるため、常に`false`を返す。
+    pub fn is_accumulation(&self) -> bool {
+        false
+    }
 }
 
 /// テンプレート4.50000
@@ -379,6 +412,14 @@ impl Section4_50000 {
         self.template4.parameter_number
     }
 
+    /// パラメータカテゴリーとパラメータ番号に対応するパラメータ名を返す。
+    pub fn parameter_name(&self) -> Option<&'static str> {
+        parameter_name(
+            self.template4.parameter_category,
+            self.template4.parameter_number,
+        )
+    }
+
     /// 作成処理の種類を返す。
     pub fn type_of_generating_process(&self) -> u8 {
         self.template4.type_of_generating_process
@@ -413,6 +454,13 @@ impl Section4_50000 {
     pub fn forecast_time(&self) -> i32 {
         self.template4.forecast_time
     }
+    /// 期間の単位の指示符と予報時間から、予報時間の長さを返す。
+    pub fn forecast_duration(&self) -> Grib2Result<Duration> {
+        forecast_duration(
+            self.template4.indicator_of_unit_of_time_range,
+            self.template4.forecast_time,
+        )
+    }
 
     /// 第一固定面の種類を返す。
     pub fn type_of_first_fixed_surface(&self) -> u8 {
@@ -473,10 +521,83 @@ impl Section4_50000 {
     pub fn minutes_from_source_document2(&self) -> u8 {
         self.template4.minutes_from_source_document2
     }
+
+    /// 値が統計処理期間の積算値であるかを返す。
+    ///
+    /// このテンプレートは統計処理の情報を持たず、値は瞬間値であるため、常に`false`を返す。
+    pub fn is_accumulation(&self) -> bool {
+        false
+    }
+}
+
+/// 統計処理に使用した時間間隔を記述する期間の仕様
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRangeSpec {
+    /// 統計処理の種類
+    pub type_of_stat_proc: u8,
+    /// 統計処理の時間増分の種類
+    pub type_of_stat_proc_time_increment: u8,
+    /// 統計処理の時間の単位の指示符
+    pub stat_proc_time_unit: u8,
+    /// 統計処理した時間の長さ
+    pub stat_proc_time_length: u32,
+    /// 連続的な資料場間の増分に関する時間の単位の指示符
+    pub successive_time_unit: u8,
+    /// 連続的な資料場間の時間の増分
+    pub successive_time_increment: u32,
+}
+
+/// 期間の仕様を、期間の仕様の数だけ読み込む。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2リーダー
+/// * `number_of_time_range_specs` - 期間の仕様の数
+///
+/// # 戻り値
+///
+/// * 期間の仕様を記録されている順に格納したベクター
+fn read_time_range_specs<R: Read>(
+    reader: &mut BufReader<R>,
+    number_of_time_range_specs: u8,
+) -> Grib2Result<Vec<TimeRangeSpec>> {
+    if number_of_time_range_specs == 0 {
+        return Err(Grib2Error::ReadError(
+            "第4節:期間の仕様の数が0です。".into(),
+        ));
+    }
+    let mut specs = Vec::with_capacity(number_of_time_range_specs as usize);
+    for _ in 0..number_of_time_range_specs {
+        // 統計処理の種類: 1バイト
+        let type_of_stat_proc = read_u8(reader, "第4節:統計処理の種類")?;
+        // 統計処理の時間増分の種類: 1バイト
+        let type_of_stat_proc_time_increment = read_u8(reader, "第4節:統計処理の時間増分の種類")?;
+        // 統計処理の時間の単位の指示符: 1バイト
+        let stat_proc_time_unit = read_u8(reader, "第4節:統計処理の時間の単位の指示符")?;
+        // 統計処理した期間の長さ: 4バイト
+        let stat_proc_time_length = read_u32(reader, "第4節:統計処理の時間増分の長さ")?;
+        // 連続的な資料場間の増分に関する時間の単位の指示符: 1バイト
+        let successive_time_unit = read_u8(
+            reader,
+            "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
+        )?;
+        // 連続的な資料場間の時間の増分: 4バイト
+        let successive_time_increment = read_u32(reader, "第4節:連続的な資料場間の時間の増分")?;
+        specs.push(TimeRangeSpec {
+            type_of_stat_proc,
+            type_of_stat_proc_time_increment,
+            stat_proc_time_unit,
+            stat_proc_time_length,
+            successive_time_unit,
+            successive_time_increment,
+        });
+    }
+
+    Ok(specs)
 }
 
 /// テンプレート4.50008
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Template4_50008 {
     /// パラメータカテゴリー
     parameter_category: u8,
@@ -514,18 +635,8 @@ pub struct Template4_50008 {
     number_of_time_range_specs: u8,
     /// 統計処理における欠測資料の総数
     number_of_missing_values: u32,
-    /// 統計処理の種類
-    type_of_stat_proc: u8,
-    /// 統計処理の時間増分の種類
-    type_of_stat_proc_time_increment: u8,
-    /// 統計処理の時間の単位の指示符
-    stat_proc_time_unit: u8,
-    /// 統計処理した時間の長さ
-    stat_proc_time_length: u32,
-    /// 連続的な資料場間の増分に関する時間の単位の指示符
-    successive_time_unit: u8,
-    /// 連続的な資料場間の時間の増分
-    successive_time_increment: u32,
+    /// 期間の仕様
+    time_range_specs: Vec<TimeRangeSpec>,
     /// レーダー等運用情報その1
     radar_info1: u64,
     /// レーダー等運用情報その2
@@ -579,21 +690,8 @@ impl TemplateReader for Template4_50008 {
         )?;
         // 統計処理における欠測資料の総数: 4バイト
         let number_of_missing_values = read_u32(reader, "第4節:統計処理における欠測資料の総数")?;
-        // 統計処理の種類: 1バイト
-        let type_of_stat_proc = read_u8(reader, "第4節:統計処理の種類")?;
-        // 統計処理の時間増分の種類: 1バイト
-        let type_of_stat_proc_time_increment = read_u8(reader, "第4節:統計処理の時間増分の種類")?;
-        // 統計処理の時間の単位の指示符: 1バイト
-        let stat_proc_time_unit = read_u8(reader, "第4節:統計処理の時間の単位の指示符")?;
-        // 統計処理した期間の長さ: 4バイト
-        let stat_proc_time_length = read_u32(reader, "第4節:統計処理の時間増分の長さ")?;
-        // 連続的な資料場間の増分に関する時間の単位の指示符: 1バイト
-        let successive_time_unit = read_u8(
-            reader,
-            "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
-        )?;
-        // 連続的な資料場間の時間の増分: 4バイト
-        let successive_time_increment = read_u32(reader, "第4節:連続的な資料場間の時間の増分")?;
+        // 期間の仕様: 12バイト x 期間の仕様の数
+        let time_range_specs = read_time_range_specs(reader, number_of_time_range_specs)?;
         // レーダー等運用情報その1: 8バイト
         let radar_info1 = read_u64(reader, "第4節:レーダー等運用情報その1")?;
         // レーダー等運用情報その2: 8バイト
@@ -620,12 +718,7 @@ impl TemplateReader for Template4_50008 {
             end_of_all_time_intervals,
             number_of_time_range_specs,
             number_of_missing_values,
-            type_of_stat_proc,
-            type_of_stat_proc_time_increment,
-            stat_proc_time_unit,
-            stat_proc_time_length,
-            successive_time_unit,
-            successive_time_increment,
+            time_range_specs,
             radar_info1,
             radar_info2,
             rain_gauge_info,
@@ -644,6 +737,13 @@ impl Section4_50008 {
     pub fn parameter_number(&self) -> u8 {
         self.template4.parameter_number
     }
+    /// パラメータカテゴリーとパラメータ番号に対応するパラメータ名を返す。
+    pub fn parameter_name(&self) -> Option<&'static str> {
+        parameter_name(
+            self.template4.parameter_category,
+            self.template4.parameter_number,
+        )
+    }
     /// 作成処理の種類を返す。
     pub fn type_of_generating_process(&self) -> u8 {
         self.template4.type_of_generating_process
@@ -672,6 +772,13 @@ impl Section4_50008 {
     pub fn forecast_time(&self) -> i32 {
         self.template4.forecast_time
     }
+    /// 期間の単位の指示符と予報時間から、予報時間の長さを返す。
+    pub fn forecast_duration(&self) -> Grib2Result<Duration> {
+        forecast_duration(
+            self.template4.indicator_of_unit_of_time_range,
+            self.template4.forecast_time,
+        )
+    }
     /// 第一固定面の種類を返す。
     pub fn type_of_first_fixed_surface(&self) -> u8 {
         self.template4.type_of_first_fixed_surface
@@ -708,29 +815,33 @@ impl Section4_50008 {
     pub fn number_of_missing_values(&self) -> u32 {
         self.template4.number_of_missing_values
     }
-    /// 統計処理の種類を返す。
+    /// 期間の仕様を、記録されている順に返す。
+    pub fn time_range_specs(&self) -> &[TimeRangeSpec] {
+        &self.template4.time_range_specs
+    }
+    /// 最初の期間の仕様の統計処理の種類を返す。
     pub fn type_of_stat_proc(&self) -> u8 {
-        self.template4.type_of_stat_proc
+        self.template4.time_range_specs[0].type_of_stat_proc
     }
-    /// 統計処理の時間増分の種類を返す。
+    /// 最初の期間の仕様の統計処理の時間増分の種類を返す。
     pub fn type_of_stat_proc_time_increment(&self) -> u8 {
-        self.template4.type_of_stat_proc_time_increment
+        self.template4.time_range_specs[0].type_of_stat_proc_time_increment
     }
-    /// 統計処理の時間の単位の指示符を返す。
+    /// 最初の期間の仕様の統計処理の時間の単位の指示符を返す。
     pub fn stat_proc_time_unit(&self) -> u8 {
-        self.template4.stat_proc_time_unit
+        self.template4.time_range_specs[0].stat_proc_time_unit
     }
-    /// 統計処理した時間の長さを返す。
+    /// 最初の期間の仕様の統計処理した時間の長さを返す。
     pub fn stat_proc_time_length(&self) -> u32 {
-        self.template4.stat_proc_time_length
+        self.template4.time_range_specs[0].stat_proc_time_length
     }
-    /// 連続的な資料場間の増分に関する時間の単位の指示符を返す。
+    /// 最初の期間の仕様の連続的な資料場間の増分に関する時間の単位の指示符を返す。
     pub fn successive_time_unit(&self) -> u8 {
-        self.template4.successive_time_unit
+        self.template4.time_range_specs[0].successive_time_unit
     }
-    /// 連続的な資料場間の時間の増分を返す。
+    /// 最初の期間の仕様の連続的な資料場間の時間の増分を返す。
     pub fn successive_time_increment(&self) -> u32 {
-        self.template4.successive_time_increment
+        self.template4.time_range_specs[0].successive_time_increment
     }
     /// レーダー等運用情報その1を返す。
     pub fn radar_info1(&self) -> u64 {
@@ -744,6 +855,34 @@ impl Section4_50008 {
     pub fn rain_gauge_info(&self) -> u64 {
         self.template4.rain_gauge_info
     }
+
+    /// 値が統計処理期間の積算値であるかを返す。
+    ///
+    /// 統計処理の種類が積算（符号表4.10の1）の場合に`true`を返す。積算値を時間方向に
+    /// さらに合計すると、同じ期間を重複して積算することになるため注意すること。
+    pub fn is_accumulation(&self) -> bool {
+        self.type_of_stat_proc() == STAT_PROC_ACCUMULATION
+    }
+
+    /// 統計処理した期間の開始時と終了時(UTC)を返す。
+    ///
+    /// 終了時は全時間間隔の終了時で、開始時は終了時から最初の期間の仕様の統計処理した時間の
+    /// 長さを遡った日時である。
+    pub fn accumulation_window(&self) -> Grib2Result<(OffsetDateTime, OffsetDateTime)> {
+        let length = i32::try_from(self.stat_proc_time_length()).map_err(|_| {
+            Grib2Error::ConvertError(
+                format!(
+                    "統計処理した時間の長さ`{}`をi32型に変換できません。",
+                    self.stat_proc_time_length()
+                )
+                .into(),
+            )
+        })?;
+        let end = self.end_of_all_time_intervals();
+        let start = end - forecast_duration(self.stat_proc_time_unit(), length)?;
+
+        Ok((start, end))
+    }
 }
 
 /// テンプレート4.50009
@@ -785,18 +924,8 @@ pub struct Template4_50009 {
     number_of_time_range_specs: u8,
     /// 統計処理における欠測資料の総数
     number_of_missing_values: u32,
-    /// 統計処理の種類
-    type_of_stat_proc: u8,
-    /// 統計処理の時間増分の種類
-    type_of_stat_proc_time_increment: u8,
-    /// 統計処理の時間の単位の指示符
-    stat_proc_time_unit: u8,
-    /// 統計処理した時間の長さ
-    stat_proc_time_length: u32,
-    /// 連続的な資料場間の増分に関する時間の単位の指示符
-    successive_time_unit: u8,
-    /// 連続的な資料場間の時間の増分
-    successive_time_increment: u32,
+    /// 期間の仕様
+    time_range_specs: Vec<TimeRangeSpec>,
     /// レーダー等運用情報その1
     radar_info1: u64,
     /// レーダー等運用情報その2
@@ -856,21 +985,8 @@ impl TemplateReader for Template4_50009 {
         )?;
         // 統計処理における欠測資料の総数: 4バイト
         let number_of_missing_values = read_u32(reader, "第4節:統計処理における欠測資料の総数")?;
-        // 統計処理の種類: 1バイト
-        let type_of_stat_proc = read_u8(reader, "第4節:統計処理の種類")?;
-        // 統計処理の時間増分の種類: 1バイト
-        let type_of_stat_proc_time_increment = read_u8(reader, "第4節:統計処理の時間増分の種類")?;
-        // 統計処理の時間の単位の指示符: 1バイト
-        let stat_proc_time_unit = read_u8(reader, "第4節:統計処理の時間の単位の指示符")?;
-        // 統計処理した期間の長さ: 4バイト
-        let stat_proc_time_length = read_u32(reader, "第4節:統計処理の時間増分の長さ")?;
-        // 連続的な資料場間の増分に関する時間の単位の指示符: 1バイト
-        let successive_time_unit = read_u8(
-            reader,
-            "第4節:連続的な資料場間の増分に関する時間の単位の指示符",
-        )?;
-        // 連続的な資料場間の時間の増分: 4バイト
-        let successive_time_increment = read_u32(reader, "第4節:連続的な資料場間の時間の増分")?;
+        // 期間の仕様: 12バイト x 期間の仕様の数
+        let time_range_specs = read_time_range_specs(reader, number_of_time_range_specs)?;
         // レーダー等運用情報その1: 8バイト
         let radar_info1 = read_u64(reader, "第4節:レーダー等運用情報その1")?;
         // レーダー等運用情報その2: 8バイト
@@ -909,12 +1025,7 @@ impl TemplateReader for Template4_50009 {
             end_of_all_time_intervals,
             number_of_time_range_specs,
             number_of_missing_values,
-            type_of_stat_proc,
-            type_of_stat_proc_time_increment,
-            stat_proc_time_unit,
-            stat_proc_time_length,
-            successive_time_unit,
-            successive_time_increment,
+            time_range_specs,
             radar_info1,
             radar_info2,
             rain_gauge_info,
@@ -929,13 +1040,20 @@ pub type Section4_50009 = Section4<Template4_50009>;
 
 impl Section4_50009 {
     /// パラメータカテゴリーを返す。
-    pub fn parameter_category(self) -> u8 {
+    pub fn parameter_category(&self) -> u8 {
         self.template4.parameter_category
     }
     /// パラメータ番号を返す。
     pub fn parameter_number(&self) -> u8 {
         self.template4.parameter_number
     }
+    /// パラメータカテゴリーとパラメータ番号に対応するパラメータ名を返す。
+    pub fn parameter_name(&self) -> Option<&'static str> {
+        parameter_name(
+            self.template4.parameter_category,
+            self.template4.parameter_number,
+        )
+    }
     /// 作成処理の種類を返す。
     pub fn type_of_generating_process(&self) -> u8 {
         self.template4.type_of_generating_process
@@ -964,6 +1082,13 @@ impl Section4_50009 {
     pub fn forecast_time(&self) -> i32 {
         self.template4.forecast_time
     }
+    /// 期間の単位の指示符と予報時間から、予報時間の長さを返す。
+    pub fn forecast_duration(&self) -> Grib2Result<Duration> {
+        forecast_duration(
+            self.template4.indicator_of_unit_of_time_range,
+            self.template4.forecast_time,
+        )
+    }
     /// 第一固定面の種類を返す。
     pub fn type_of_first_fixed_surface(&self) -> u8 {
         self.template4.type_of_first_fixed_surface
@@ -1000,29 +1125,33 @@ impl Section4_50009 {
     pub fn number_of_missing_values(&self) -> u32 {
         self.template4.number_of_missing_values
     }
-    /// 統計処理の種類を返す。
+    /// 期間の仕様を、記録されている順に返す。
+    pub fn time_range_specs(&self) -> &[TimeRangeSpec] {
+        &self.template4.time_range_specs
+    }
+    /// 最初の期間の仕様の統計処理の種類を返す。
     pub fn type_of_stat_proc(&self) -> u8 {
-        self.template4.type_of_stat_proc
+        self.template4.time_range_specs[0].type_of_stat_proc
     }
-    /// 統計処理の時間増分の種類を返す。
+    /// 最初の期間の仕様の統計処理の時間増分の種類を返す。
     pub fn type_of_stat_proc_time_increment(&self) -> u8 {
-        self.template4.type_of_stat_proc_time_increment
+        self.template4.time_range_specs[0].type_of_stat_proc_time_increment
     }
-    /// 統計処理の時間の単位の指示符を返す。
+    /// 最初の期間の仕様の統計処理の時間の単位の指示符を返す。
     pub fn stat_proc_time_unit(&self) -> u8 {
-        self.template4.stat_proc_time_unit
+        self.template4.time_range_specs[0].stat_proc_time_unit
     }
-    /// 統計処理した時間の長さを返す。
+    /// 最初の期間の仕様の統計処理した時間の長さを返す。
     pub fn stat_proc_time_length(&self) -> u32 {
-        self.template4.stat_proc_time_length
+        self.template4.time_range_specs[0].stat_proc_time_length
     }
-    /// 連続的な資料場間の増分に関する時間の単位の指示符を返す。
+    /// 最初の期間の仕様の連続的な資料場間の増分に関する時間の単位の指示符を返す。
     pub fn successive_time_unit(&self) -> u8 {
-        self.template4.successive_time_unit
+        self.template4.time_range_specs[0].successive_time_unit
     }
-    /// 連続的な資料場間の時間の増分を返す。
+    /// 最初の期間の仕様の連続的な資料場間の時間の増分を返す。
     pub fn successive_time_increment(&self) -> u32 {
-        self.template4.successive_time_increment
+        self.template4.time_range_specs[0].successive_time_increment
     }
     /// レーダー等運用情報その1を返す。
     pub fn radar_info1(&self) -> u64 {
@@ -1048,4 +1177,635 @@ impl Section4_50009 {
     pub fn combined_ratios_of_forecast_areas(&self) -> &[u16] {
         &self.template4.combined_ratios_of_forecast_areas
     }
+
+    /// 値が統計処理期間の積算値であるかを返す。
+    ///
+    /// 統計処理の種類が積算（符号表4.10の1）の場合に`true`を返す。積算値を時間方向に
+    /// さらに合計すると、同じ期間を重複して積算することになるため注意すること。
+    pub fn is_accumulation(&self) -> bool {
+        self.type_of_stat_proc() == STAT_PROC_ACCUMULATION
+    }
+}
+
+/// 統計処理の種類（符号表4.10）: 積算
+const STAT_PROC_ACCUMULATION: u8 = 1;
+
+/// 資料分野0（気象プロダクト）のパラメータカテゴリーとパラメータ番号に対応するパラメータ名
+///
+/// WMOのGRIB2符号表4.2と、このクレートが読み込むプロダクトで使用されている気象庁の地域パラメータを
+/// 登録している。
+const PARAMETER_NAMES: [(u8, u8, &str); 11] = [
+    // パラメータカテゴリー0: 温度
+    (0, 0, "Temperature"),
+    // パラメータカテゴリー1: 湿度
+    (1, 0, "Specific humidity"),
+    (1, 1, "Relative humidity"),
+    (1, 7, "Precipitation rate"),
+    (1, 8, "Total precipitation"),
+    (1, 52, "Total precipitation rate"),
+    // パラメータカテゴリー1: 湿度（気象庁地域使用）
+    (1, 200, "Hourly precipitation amount"),
+    (1, 201, "Precipitation intensity"),
+    (1, 206, "Soil water index"),
+    (1, 208, "Landslide alert judgment"),
+    // パラメータカテゴリー2: 運動量
+    (2, 1, "Wind speed"),
+];
+
+/// パラメータカテゴリーとパラメータ番号に対応するパラメータ名を返す。
+///
+/// # 引数
+///
+/// * `category` - パラメータカテゴリー
+/// * `number` - パラメータ番号
+///
+/// # 戻り値
+///
+/// * パラメータ名、登録されていない組み合わせの場合は`None`
+pub(crate) fn parameter_name(category: u8, number: u8) -> Option<&'static str> {
+    PARAMETER_NAMES
+        .iter()
+        .find(|(c, n, _)| *c == category && *n == number)
+        .map(|(_, _, name)| *name)
+}
+
+/// 期間の単位の指示符と予報時間から、予報時間の長さを返す。
+///
+/// 期間の単位の指示符は、WMOのGRIB2符号表4.4のうち、分（0）、時（1）、日（2）、3時間（10）、
+/// 6時間（11）、12時間（12）及び秒（13）に対応している。
+///
+/// # 引数
+///
+/// * `unit` - 期間の単位の指示符
+/// * `forecast_time` - 予報時間（負の値は参照時刻より前を示す）
+///
+/// # 戻り値
+///
+/// * 予報時間の長さ
+pub(crate) fn forecast_duration(unit: u8, forecast_time: i32) -> Grib2Result<Duration> {
+    let forecast_time = forecast_time as i64;
+    match unit {
+        0 => Ok(Duration::minutes(forecast_time)),
+        1 => Ok(Duration::hours(forecast_time)),
+        2 => Ok(Duration::days(forecast_time)),
+        10 => Ok(Duration::hours(forecast_time * 3)),
+        11 => Ok(Duration::hours(forecast_time * 6)),
+        12 => Ok(Duration::hours(forecast_time * 12)),
+        13 => Ok(Duration::seconds(forecast_time)),
+        _ => Err(Grib2Error::NotImplemented(
+            format!("期間の単位の指示符`{unit}`は未実装です。").into(),
+        )),
+    }
+}
+
+impl Describe for Section4_0 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            (
+                "number_of_after_template_points",
+                self.number_of_after_template_points().to_string(),
+            ),
+            (
+                "product_definition_template_number",
+                self.product_definition_template_number().to_string(),
+            ),
+            ("parameter_category", self.parameter_category().to_string()),
+            ("parameter_number", self.parameter_number().to_string()),
+            (
+                "type_of_generating_process",
+                self.type_of_generating_process().to_string(),
+            ),
+            ("background_process", self.background_process().to_string()),
+            (
+                "generating_process_identifier",
+                self.generating_process_identifier().to_string(),
+            ),
+            (
+                "hours_after_data_cutoff",
+                self.hours_after_data_cutoff().to_string(),
+            ),
+            (
+                "minutes_after_data_cutoff",
+                self.minutes_after_data_cutoff().to_string(),
+            ),
+            (
+                "indicator_of_unit_of_time_range",
+                self.indicator_of_unit_of_time_range().to_string(),
+            ),
+            ("forecast_time", self.forecast_time().to_string()),
+            (
+                "type_of_first_fixed_surface",
+                self.type_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_first_fixed_surface",
+                self.scale_factor_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_first_fixed_surface",
+                self.scaled_value_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "type_of_second_fixed_surface",
+                self.type_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_second_fixed_surface",
+                self.scale_factor_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_second_fixed_surface",
+                self.scaled_value_of_second_fixed_surface().to_string(),
+            ),
+        ]
+    }
+}
+
+impl Describe for Section4_50000 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            (
+                "number_of_after_template_points",
+                self.number_of_after_template_points().to_string(),
+            ),
+            (
+                "product_definition_template_number",
+                self.product_definition_template_number().to_string(),
+            ),
+            ("parameter_category", self.parameter_category().to_string()),
+            ("parameter_number", self.parameter_number().to_string()),
+            (
+                "type_of_generating_process",
+                self.type_of_generating_process().to_string(),
+            ),
+            ("background_process", self.background_process().to_string()),
+            (
+                "generating_process_identifier",
+                self.generating_process_identifier().to_string(),
+            ),
+            (
+                "hours_after_data_cutoff",
+                self.hours_after_data_cutoff().to_string(),
+            ),
+            (
+                "minutes_after_data_cutoff",
+                self.minutes_after_data_cutoff().to_string(),
+            ),
+            (
+                "indicator_of_unit_of_time_range",
+                self.indicator_of_unit_of_time_range().to_string(),
+            ),
+            ("forecast_time", self.forecast_time().to_string()),
+            (
+                "type_of_first_fixed_surface",
+                self.type_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_first_fixed_surface",
+                self.scale_factor_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_first_fixed_surface",
+                self.scaled_value_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "type_of_second_fixed_surface",
+                self.type_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_second_fixed_surface",
+                self.scale_factor_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_second_fixed_surface",
+                self.scaled_value_of_second_fixed_surface().to_string(),
+            ),
+            ("source_document1", self.source_document1().to_string()),
+            (
+                "hours_from_source_document1",
+                self.hours_from_source_document1().to_string(),
+            ),
+            (
+                "minutes_from_source_document1",
+                self.minutes_from_source_document1().to_string(),
+            ),
+            ("source_document2", self.source_document2().to_string()),
+            (
+                "hours_from_source_document2",
+                self.hours_from_source_document2().to_string(),
+            ),
+            (
+                "minutes_from_source_document2",
+                self.minutes_from_source_document2().to_string(),
+            ),
+        ]
+    }
+}
+
+impl Describe for Section4_50008 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            (
+                "number_of_after_template_points",
+                self.number_of_after_template_points().to_string(),
+            ),
+            (
+                "product_definition_template_number",
+                self.product_definition_template_number().to_string(),
+            ),
+            ("parameter_category", self.parameter_category().to_string()),
+            ("parameter_number", self.parameter_number().to_string()),
+            (
+                "type_of_generating_process",
+                self.type_of_generating_process().to_string(),
+            ),
+            ("background_process", self.background_process().to_string()),
+            (
+                "generating_process_identifier",
+                self.generating_process_identifier().to_string(),
+            ),
+            (
+                "hours_after_data_cutoff",
+                self.hours_after_data_cutoff().to_string(),
+            ),
+            (
+                "minutes_after_data_cutoff",
+                self.minutes_after_data_cutoff().to_string(),
+            ),
+            (
+                "indicator_of_unit_of_time_range",
+                self.indicator_of_unit_of_time_range().to_string(),
+            ),
+            ("forecast_time", self.forecast_time().to_string()),
+            (
+                "type_of_first_fixed_surface",
+                self.type_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_first_fixed_surface",
+                self.scale_factor_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_first_fixed_surface",
+                self.scaled_value_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "type_of_second_fixed_surface",
+                self.type_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_second_fixed_surface",
+                self.scale_factor_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_second_fixed_surface",
+                self.scaled_value_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "end_of_all_time_intervals",
+                self.end_of_all_time_intervals().to_string(),
+            ),
+            (
+                "number_of_time_range_specs",
+                self.number_of_time_range_specs().to_string(),
+            ),
+            (
+                "number_of_missing_values",
+                self.number_of_missing_values().to_string(),
+            ),
+            ("type_of_stat_proc", self.type_of_stat_proc().to_string()),
+            (
+                "type_of_stat_proc_time_increment",
+                self.type_of_stat_proc_time_increment().to_string(),
+            ),
+            (
+                "stat_proc_time_unit",
+                self.stat_proc_time_unit().to_string(),
+            ),
+            (
+                "stat_proc_time_length",
+                self.stat_proc_time_length().to_string(),
+            ),
+            (
+                "successive_time_unit",
+                self.successive_time_unit().to_string(),
+            ),
+            (
+                "successive_time_increment",
+                self.successive_time_increment().to_string(),
+            ),
+            ("radar_info1", self.radar_info1().to_string()),
+            ("radar_info2", self.radar_info2().to_string()),
+            ("rain_gauge_info", self.rain_gauge_info().to_string()),
+        ]
+    }
+}
+
+impl Describe for Section4_50009 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            (
+                "number_of_after_template_points",
+                self.number_of_after_template_points().to_string(),
+            ),
+            (
+                "product_definition_template_number",
+                self.product_definition_template_number().to_string(),
+            ),
+            ("parameter_category", self.parameter_category().to_string()),
+            ("parameter_number", self.parameter_number().to_string()),
+            (
+                "type_of_generating_process",
+                self.type_of_generating_process().to_string(),
+            ),
+            ("background_process", self.background_process().to_string()),
+            (
+                "generating_process_identifier",
+                self.generating_process_identifier().to_string(),
+            ),
+            (
+                "hours_after_data_cutoff",
+                self.hours_after_data_cutoff().to_string(),
+            ),
+            (
+                "minutes_after_data_cutoff",
+                self.minutes_after_data_cutoff().to_string(),
+            ),
+            (
+                "indicator_of_unit_of_time_range",
+                self.indicator_of_unit_of_time_range().to_string(),
+            ),
+            ("forecast_time", self.forecast_time().to_string()),
+            (
+                "type_of_first_fixed_surface",
+                self.type_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_first_fixed_surface",
+                self.scale_factor_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_first_fixed_surface",
+                self.scaled_value_of_first_fixed_surface().to_string(),
+            ),
+            (
+                "type_of_second_fixed_surface",
+                self.type_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scale_factor_of_second_fixed_surface",
+                self.scale_factor_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "scaled_value_of_second_fixed_surface",
+                self.scaled_value_of_second_fixed_surface().to_string(),
+            ),
+            (
+                "end_of_all_time_intervals",
+                self.end_of_all_time_intervals().to_string(),
+            ),
+            (
+                "number_of_time_range_specs",
+                self.number_of_time_range_specs().to_string(),
+            ),
+            (
+                "number_of_missing_values",
+                self.number_of_missing_values().to_string(),
+            ),
+            ("type_of_stat_proc", self.type_of_stat_proc().to_string()),
+            (
+                "type_of_stat_proc_time_increment",
+                self.type_of_stat_proc_time_increment().to_string(),
+            ),
+            (
+                "stat_proc_time_unit",
+                self.stat_proc_time_unit().to_string(),
+            ),
+            (
+                "stat_proc_time_length",
+                self.stat_proc_time_length().to_string(),
+            ),
+            (
+                "successive_time_unit",
+                self.successive_time_unit().to_string(),
+            ),
+            (
+                "successive_time_increment",
+                self.successive_time_increment().to_string(),
+            ),
+            ("radar_info1", self.radar_info1().to_string()),
+            ("radar_info2", self.radar_info2().to_string()),
+            ("rain_gauge_info", self.rain_gauge_info().to_string()),
+            (
+                "number_of_calculation_areas",
+                self.number_of_calculation_areas().to_string(),
+            ),
+            (
+                "scale_factor_of_combined_ratio",
+                self.scale_factor_of_combined_ratio().to_string(),
+            ),
+            (
+                "combined_ratios_of_forecast_areas",
+                format!("{:?}", self.combined_ratios_of_forecast_areas()),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor, Seek};
+
+    use time::Duration;
+
+    use super::{forecast_duration, parameter_name, Section4_50008, TimeRangeSpec};
+    use crate::readers::{
+        FPrrReader, ForecastHour, LwjmHour, LwjmReader, PrrReader, PswReader, PswTank,
+    };
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    const FPRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807001000_SRF_GPV_Ggis1km_Prr60lv_Fper10min_FH01-06_grib2.bin"
+    );
+
+    const PSW_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20170807170000_SRF_GPV_Ggis1km_Psw_Aper10min_ANAL_grib2.bin"
+    );
+
+    const LWJM_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20180706095000_MET_INF_Jdosha_Ggis1km_ANAL_grib2.bin"
+    );
+
+    #[test]
+    fn parameter_name_of_prr_ok() {
+        assert_eq!(Some("Hourly precipitation amount"), parameter_name(1, 200));
+    }
+
+    #[test]
+    fn parameter_name_of_fixtures_ok() {
+        let prr = PrrReader::new(PRR_FILE).unwrap();
+        assert_eq!(
+            Some("Hourly precipitation amount"),
+            prr.section4().parameter_name()
+        );
+        let fprr = FPrrReader::new(FPRR_FILE).unwrap();
+        assert_eq!(
+            Some("Hourly precipitation amount"),
+            fprr.fprr_sections(ForecastHour::Hour1)
+                .section4
+                .parameter_name()
+        );
+        let psw = PswReader::new(PSW_FILE).unwrap();
+        assert_eq!(
+            Some("Soil water index"),
+            psw.psw_sections(PswTank::All).section4.parameter_name()
+        );
+        let lwjm = LwjmReader::new(LWJM_FILE, false).unwrap();
+        assert_eq!(
+            Some("Landslide alert judgment"),
+            lwjm.lwjm_sections(LwjmHour::Live)
+                .unwrap()
+                .section4
+                .parameter_name()
+        );
+    }
+
+    #[test]
+    fn parameter_name_of_unknown_pair_is_none() {
+        assert_eq!(None, parameter_name(1, 255));
+    }
+
+    #[test]
+    fn forecast_duration_in_minutes_ok() {
+        assert_eq!(Duration::minutes(30), forecast_duration(0, 30).unwrap());
+    }
+
+    #[test]
+    fn forecast_duration_in_hours_ok() {
+        assert_eq!(Duration::hours(6), forecast_duration(1, 6).unwrap());
+    }
+
+    #[test]
+    fn forecast_duration_in_seconds_ok() {
+        assert_eq!(Duration::seconds(90), forecast_duration(13, 90).unwrap());
+    }
+
+    #[test]
+    fn forecast_duration_in_composite_hours_ok() {
+        assert_eq!(Duration::hours(6), forecast_duration(10, 2).unwrap());
+        assert_eq!(Duration::hours(18), forecast_duration(11, 3).unwrap());
+        assert_eq!(Duration::hours(-12), forecast_duration(12, -1).unwrap());
+    }
+
+    #[test]
+    fn negative_forecast_duration_ok() {
+        assert_eq!(Duration::minutes(-10), forecast_duration(0, -10).unwrap());
+        assert_eq!(Duration::hours(-3), forecast_duration(1, -3).unwrap());
+    }
+
+    #[test]
+    fn forecast_duration_of_unknown_unit_err() {
+        assert!(forecast_duration(255, 1).is_err());
+    }
+
+    #[test]
+    fn prr_is_accumulation() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        assert!(reader.section4().is_accumulation());
+    }
+
+    #[test]
+    fn psw_is_not_accumulation() {
+        let reader = PswReader::new(PSW_FILE).unwrap();
+        assert!(!reader.psw_sections(PswTank::All).section4.is_accumulation());
+    }
+
+    /// 解析雨量ファイルの第4節に、2つ目の期間の仕様を挿入したバイト列を返す。
+    fn section4_with_two_time_range_specs() -> Vec<u8> {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        // 第0節は16バイト、第2節は記録されていない
+        let start = 16 + reader.section1().section_bytes() + reader.section3().section_bytes();
+        let bytes = std::fs::read(PRR_FILE).unwrap();
+        let mut section4 = bytes[start..start + reader.section4().section_bytes()].to_vec();
+        // 節の長さ及び期間の仕様の数を更新して、最初の期間の仕様（47バイト目から12バイト）の後に
+        // 2つ目の期間の仕様を挿入
+        let section_bytes = section4.len() as u32 + 12;
+        section4[..4].copy_from_slice(&section_bytes.to_be_bytes());
+        assert_eq!(1, section4[41]);
+        section4[41] = 2;
+        let mut second = vec![0, 2, 0];
+        second.extend(30u32.to_be_bytes());
+        second.push(0);
+        second.extend(10u32.to_be_bytes());
+        section4.splice(58..58, second);
+
+        section4
+    }
+
+    #[test]
+    fn read_two_time_range_specs_ok() {
+        let expected = PrrReader::new(PRR_FILE).unwrap().section4().radar_info1();
+        let mut reader = BufReader::new(Cursor::new(section4_with_two_time_range_specs()));
+        let section4 = Section4_50008::from_reader(&mut reader).unwrap();
+
+        assert_eq!(2, section4.number_of_time_range_specs());
+        assert_eq!(2, section4.time_range_specs().len());
+        assert_eq!(
+            TimeRangeSpec {
+                type_of_stat_proc: 0,
+                type_of_stat_proc_time_increment: 2,
+                stat_proc_time_unit: 0,
+                stat_proc_time_length: 30,
+                successive_time_unit: 0,
+                successive_time_increment: 10,
+            },
+            section4.time_range_specs()[1]
+        );
+        // 最初の期間の仕様は積算
+        assert!(section4.is_accumulation());
+        // 2つ目の期間の仕様の後に記録されている値の位置がずれていないことを確認
+        assert_eq!(expected, section4.radar_info1());
+    }
+
+    #[test]
+    fn read_after_template_coordinates_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let start = 16 + reader.section1().section_bytes() + reader.section3().section_bytes();
+        let bytes = std::fs::read(PRR_FILE).unwrap();
+        let mut section4 = bytes[start..start + reader.section4().section_bytes()].to_vec();
+        // テンプレート直後の座標値の数を2に更新して、節の末尾に2つの座標値を追加
+        section4[5..7].copy_from_slice(&2u16.to_be_bytes());
+        section4.extend([0x3F, 0xC0, 0x00, 0x00]);
+        section4.extend([0xC3, 0x7A, 0x00, 0x00]);
+        let section_bytes = section4.len() as u32;
+        section4[..4].copy_from_slice(&section_bytes.to_be_bytes());
+        let mut reader = BufReader::new(Cursor::new(section4));
+        let section4 = Section4_50008::from_reader(&mut reader).unwrap();
+
+        assert_eq!(2, section4.number_of_after_template_points());
+        assert_eq!(&[1.5, -250.0], section4.after_template_coordinates());
+        assert_eq!(section_bytes as u64, reader.stream_position().unwrap());
+    }
+
+    #[test]
+    fn accumulation_window_of_prr_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let section4 = reader.section4();
+        // 解析雨量は1時間（60分）の積算値
+        assert!(section4.is_accumulation());
+        let (start, end) = section4.accumulation_window().unwrap();
+        assert_eq!(section4.end_of_all_time_intervals(), end);
+        assert_eq!(Duration::hours(1), end - start);
+    }
 }
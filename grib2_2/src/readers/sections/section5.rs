@@ -1,8 +1,9 @@
 use std::io::{BufReader, Read, Seek};
 
-use crate::readers::sections::TemplateReaderWithBytes;
-use crate::readers::utils::{read_i16, read_u16, read_u32, read_u8, validate_u8};
-use crate::Grib2Result;
+use crate::readers::records::LevelValue as _;
+use crate::readers::sections::{Describe, TemplateReaderWithBytes};
+use crate::readers::utils::{read_i16, read_i8, read_u16, read_u32, read_u8, validate_u8};
+use crate::{Grib2Error, Grib2Result};
 
 /// 第5節:資料表現節
 #[derive(Debug, Clone)]
@@ -82,8 +83,8 @@ where
     max_level_value: u16,
     /// データの取り得るレベルの最大値
     number_of_level_values: u16,
-    /// データ代表値の尺度因子
-    decimal_scale_factor: u8,
+    /// データ代表値の尺度因子（符号付き）
+    decimal_scale_factor: i8,
     /// レベル値と物理値(mm/h)の対応を格納するコレクション
     level_values: Vec<V>,
 }
@@ -117,21 +118,41 @@ macro_rules! template5_200 {
                 // データの取り得るレベルの最大値: 2バイト
                 let number_of_level_values = read_u16(reader, "第5節:レベルの最大値")?;
                 // データ代表値の尺度因子: 1バイト
-                let decimal_scale_factor = read_u8(reader, "第5節:データ代表値の尺度因子")?;
+                let decimal_scale_factor = read_i8(reader, "第5節:データ代表値の尺度因子")?;
                 // テンプレート5.200のバイト数を計算
                 // 4byte: 節の長さ
                 // 1byte: 節番号
                 // 4byte: 全資料点の数
-                // 1byte: 資料表現テンプレート番号
-                // よって、テンプレート5.200のバイト数は、section_bytes - 4 - 1 - 4 - 1 = section_bytes - 10
-                let template_bytes = section_bytes - 10;
+                // 2byte: 資料表現テンプレート番号
+                // よって、テンプレート5.200のバイト数は、section_bytes - 4 - 1 - 4 - 2 = section_bytes - 11
+                let template_bytes = section_bytes.checked_sub(11).ok_or_else(|| {
+                    Grib2Error::ReadError(
+                        format!("第5節:節の長さ({section_bytes})が短すぎます。").into(),
+                    )
+                })?;
                 // レベルmに対応するデータ代表値の数を計算
                 // 1byte: 1データのビット数
                 // 2byte: 今回の圧縮に用いたレベルの最大値
                 // 2byte: レベルの最大値
                 // 1byte: データ代表値の尺度因子
-                // よって、レベルmに対応するデータ代表値の数は、(template_bytes - 1 - 2 - 2 - 1) / 2 = (template_bytes - 6) / 2
-                let number_of_levels = (template_bytes - 6) / 2;
+                // よって、レベルmに対応するデータ代表値を記録しているバイト数は、template_bytes - 1 - 2 - 2 - 1 = template_bytes - 6
+                let level_bytes = template_bytes.checked_sub(6).ok_or_else(|| {
+                    Grib2Error::ReadError(
+                        format!("第5節:節の長さ({section_bytes})が短すぎます。").into(),
+                    )
+                })?;
+                // レベルmに対応するデータ代表値の数は、level_bytes / 1データ代表値のバイト数
+                let bytes_per_level = std::mem::size_of::<$type>();
+                if level_bytes % bytes_per_level != 0 {
+                    return Err(Grib2Error::ReadError(
+                        format!(
+                            "第5節:レベルmに対応するデータ代表値のバイト数({level_bytes})が、\
+                            1データ代表値のバイト数({bytes_per_level})の倍数ではありません。"
+                        )
+                        .into(),
+                    ));
+                }
+                let number_of_levels = level_bytes / bytes_per_level;
                 // レベルmに対応するデータ代表値
                 let mut level_values = Vec::with_capacity(number_of_levels);
                 for _ in 0..number_of_levels {
@@ -170,15 +191,129 @@ macro_rules! section5_200 {
                 self.template5.number_of_level_values
             }
 
+            /// ランレングス圧縮の展開に用いるレベルの最大値（第5節 13-14オクテット）を返す。
+            ///
+            /// [`Self::max_level_value`]の別名である。今回の圧縮で実際に出現したレベルの最大値で
+            /// あり、この値を超える符号はランレングス値として扱われる。凡例の作成には
+            /// [`Self::table_max_level`]を使用すること。
+            pub fn compression_max_level(&self) -> u16 {
+                self.max_level_value()
+            }
+
+            /// データの取り得るレベルの最大値（第5節 15-16オクテット）を返す。
+            ///
+            /// [`Self::number_of_level_values`]の別名である。レベル値と物理値の対応表に記録されて
+            /// いるレベルの最大値であり、凡例を作成する場合はこの値を使用する。ランレングス圧縮の
+            /// 展開には使用しないこと。
+            pub fn table_max_level(&self) -> u16 {
+                self.number_of_level_values()
+            }
+
             /// データ代表値の尺度因子を返す。
-            pub fn decimal_scale_factor(&self) -> u8 {
+            pub fn decimal_scale_factor(&self) -> i8 {
                 self.template5.decimal_scale_factor
             }
 
+            /// データ代表値に乗じて物理値に変換する倍率`10^(-尺度因子)`を返す。
+            ///
+            /// 尺度因子が負の場合、データ代表値は10の累乗倍される。
+            pub fn decimal_scale(&self) -> f64 {
+                10f64.powi(-(self.template5.decimal_scale_factor as i32))
+            }
+
+            /// データ代表値を、尺度因子を適用した物理値に変換する。
+            ///
+            /// # 引数
+            ///
+            /// * `value` - データ代表値
+            ///
+            /// # 戻り値
+            ///
+            /// * 物理値
+            pub fn scaled_value(&self, value: $type) -> f64 {
+                value as f64 * self.decimal_scale()
+            }
+
+            /// データ代表値を、尺度因子を適用した物理値に、丸め誤差なしで変換する。
+            ///
+            /// 浮動小数点数を経由せずに、整数のデータ代表値と尺度因子から10進数の物理値を
+            /// 計算する。
+            ///
+            /// # 引数
+            ///
+            /// * `value` - データ代表値
+            ///
+            /// # 戻り値
+            ///
+            /// * 物理値
+            #[cfg(feature = "rust_decimal")]
+            pub fn scaled_decimal(&self, value: $type) -> rust_decimal::Decimal {
+                let factor = self.template5.decimal_scale_factor as i32;
+                if 0 <= factor {
+                    rust_decimal::Decimal::new(value as i64, factor as u32)
+                } else {
+                    rust_decimal::Decimal::from(value as i64 * 10i64.pow(factor.unsigned_abs()))
+                }
+            }
+
             /// レベルmに対応するデータ代表値を返す。
             pub fn level_values(&self) -> &[$type] {
                 &self.template5.level_values
             }
+
+            /// レベル別物理値の最小値と最大値を、尺度因子を適用した物理値で返す。
+            ///
+            /// 格子に出現するレベルによらず、レベル値と物理値の対応表全体から計算するため、
+            /// 複数のファイルで同じ色の割り当てを使用する場合に利用できる。欠測を示す値が
+            /// 記録されたレベルは除外する。
+            ///
+            /// # 戻り値
+            ///
+            /// * 物理値の最小値と最大値の組、物理値が記録されていない場合は`(NaN, NaN)`
+            pub fn value_range(&self) -> (f64, f64) {
+                let (min, max) = self
+                    .level_values()
+                    .iter()
+                    .filter(|v| !v.is_missing())
+                    .map(|&v| self.scaled_value(v))
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                        (min.min(v), max.max(v))
+                    });
+                if min <= max {
+                    (min, max)
+                } else {
+                    (f64::NAN, f64::NAN)
+                }
+            }
+
+            /// 1データ代表値のバイト数を返す。
+            pub fn bytes_per_level(&self) -> usize {
+                std::mem::size_of::<$type>()
+            }
+        }
+
+        impl Describe for $struct_name {
+            fn fields(&self) -> Vec<(&'static str, String)> {
+                vec![
+                    ("section_bytes", self.section_bytes().to_string()),
+                    ("number_of_values", self.number_of_values().to_string()),
+                    (
+                        "data_representation_template_number",
+                        self.data_representation_template_number().to_string(),
+                    ),
+                    ("bits_per_value", self.bits_per_value().to_string()),
+                    ("max_level_value", self.max_level_value().to_string()),
+                    (
+                        "number_of_level_values",
+                        self.number_of_level_values().to_string(),
+                    ),
+                    (
+                        "decimal_scale_factor",
+                        self.decimal_scale_factor().to_string(),
+                    ),
+                    ("level_values", format!("{:?}", self.level_values())),
+                ]
+            }
         }
     };
 }
@@ -188,3 +323,128 @@ section5_200!(Section5_200i16, Template5_200i16, i16);
 
 template5_200!(Template5_200u16, u16, read_u16);
 section5_200!(Section5_200u16, Template5_200u16, u16);
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::Section5_200u16;
+    use crate::readers::PrrReader;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    /// 3つのレベル値を記録したテンプレート5.200の第5節を返す。
+    ///
+    /// `extra_bytes`には、レベルmに対応するデータ代表値の後ろに付加するバイト数を指定する。
+    ///
+    /// `scale_factor`には、符号を最上位ビットで表現したデータ代表値の尺度因子を指定する。
+    fn section5_200_bytes(extra_bytes: usize, scale_factor: u8) -> Vec<u8> {
+        let level_values = [0u16, 10, 20];
+        let section_bytes = 4 + 1 + 4 + 2 + 1 + 2 + 2 + 1 + level_values.len() * 2 + extra_bytes;
+        let mut bytes = vec![];
+        // 節の長さ、節番号、全資料点の数、資料表現テンプレート番号
+        bytes.extend((section_bytes as u32).to_be_bytes());
+        bytes.push(5);
+        bytes.extend(100u32.to_be_bytes());
+        bytes.extend(200u16.to_be_bytes());
+        // 1データのビット数、今回の圧縮に用いたレベルの最大値、レベルの最大値、尺度因子
+        bytes.push(8);
+        bytes.extend(3u16.to_be_bytes());
+        bytes.extend(3u16.to_be_bytes());
+        bytes.push(scale_factor);
+        // レベルmに対応するデータ代表値
+        for value in level_values {
+            bytes.extend(value.to_be_bytes());
+        }
+        bytes.extend(vec![0; extra_bytes]);
+
+        bytes
+    }
+
+    #[test]
+    fn read_section5_200u16_ok() {
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(0, 0)));
+        let section5 = Section5_200u16::from_reader(&mut reader).unwrap();
+
+        assert_eq!(2, section5.bytes_per_level());
+        assert_eq!(&[0, 10, 20], section5.level_values());
+    }
+
+    #[test]
+    fn read_misaligned_section5_200u16_err() {
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(1, 0)));
+        assert!(Section5_200u16::from_reader(&mut reader).is_err());
+    }
+
+    #[test]
+    fn positive_decimal_scale_factor_ok() {
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(0, 0x01)));
+        let section5 = Section5_200u16::from_reader(&mut reader).unwrap();
+
+        assert_eq!(1, section5.decimal_scale_factor());
+        assert_eq!(2.0, section5.scaled_value(20));
+    }
+
+    #[test]
+    fn negative_decimal_scale_factor_ok() {
+        // 最上位ビットが立っているため、尺度因子は-2
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(0, 0x82)));
+        let section5 = Section5_200u16::from_reader(&mut reader).unwrap();
+
+        assert_eq!(-2, section5.decimal_scale_factor());
+        assert_eq!(100.0, section5.decimal_scale());
+        assert_eq!(2000.0, section5.scaled_value(20));
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn scaled_decimal_ok() {
+        use rust_decimal::Decimal;
+
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(0, 0x01)));
+        let section5 = Section5_200u16::from_reader(&mut reader).unwrap();
+        assert_eq!(Decimal::new(35, 1), section5.scaled_decimal(35));
+        assert_eq!("3.5", section5.scaled_decimal(35).to_string());
+
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(0, 0x82)));
+        let section5 = Section5_200u16::from_reader(&mut reader).unwrap();
+        assert_eq!(Decimal::from(2000), section5.scaled_decimal(20));
+    }
+
+    #[test]
+    fn value_range_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let section5 = reader.section5();
+        let level_values = section5.level_values();
+        // 解析雨量のレベル別物理値は単調増加
+        assert!(level_values.windows(2).all(|w| w[0] < w[1]));
+
+        let (min, max) = section5.value_range();
+        assert_eq!(section5.scaled_value(level_values[0]), min);
+        assert_eq!(section5.scaled_value(*level_values.last().unwrap()), max);
+    }
+
+    #[test]
+    fn value_range_of_synthetic_table_ok() {
+        let mut reader = BufReader::new(Cursor::new(section5_200_bytes(0, 0x01)));
+        let section5 = Section5_200u16::from_reader(&mut reader).unwrap();
+        assert_eq!((0.0, 2.0), section5.value_range());
+    }
+
+    #[test]
+    fn compression_and_table_max_levels_differ() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let section5 = reader.section5();
+
+        assert_eq!(section5.max_level_value(), section5.compression_max_level());
+        assert_eq!(
+            section5.number_of_level_values(),
+            section5.table_max_level()
+        );
+        // 解析雨量は、すべてのレベルが出現しているわけではない
+        assert!(section5.compression_max_level() < section5.table_max_level());
+    }
+}
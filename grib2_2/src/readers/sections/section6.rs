@@ -1,5 +1,6 @@
 use std::io::{BufReader, Read};
 
+use crate::readers::sections::Describe;
 use crate::readers::utils::{read_u8, validate_u32, validate_u8};
 use crate::Grib2Result;
 
@@ -48,3 +49,12 @@ impl Section6 {
         self.bitmap_indicator
     }
 }
+
+impl Describe for Section6 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            ("bitmap_indicator", self.bitmap_indicator().to_string()),
+        ]
+    }
+}
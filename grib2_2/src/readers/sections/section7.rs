@@ -1,6 +1,6 @@
 use std::io::{BufReader, Read, Seek};
 
-use crate::readers::sections::TemplateReaderWithBytes;
+use crate::readers::sections::{Describe, TemplateReaderWithBytes};
 use crate::readers::utils::{read_u32, validate_u8};
 use crate::{Grib2Error, Grib2Result};
 
@@ -112,4 +112,60 @@ impl Section7_200 {
     pub fn run_length_bytes(&self) -> usize {
         self.template7.run_length_bytes
     }
+
+    /// ランレングス圧縮の圧縮率を返す。
+    ///
+    /// すべての格子点のレベル値を`nbit`ビットで記録した場合のバイト数を、ランレングス
+    /// 圧縮符号のバイト数で割った値を返す。値が大きいほど、よく圧縮されていることを示す。
+    ///
+    /// # 引数
+    ///
+    /// * `number_of_points` - 第3節に記録されている資料点数
+    /// * `nbit` - 第5節に記録されている1データのビット数
+    ///
+    /// # 戻り値
+    ///
+    /// * 圧縮率
+    pub fn compression_ratio(&self, number_of_points: u32, nbit: u8) -> f64 {
+        let uncompressed_bytes = number_of_points as f64 * nbit as f64 / 8.0;
+
+        uncompressed_bytes / self.template7.run_length_bytes as f64
+    }
+}
+
+impl Describe for Section7_200 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("section_bytes", self.section_bytes().to_string()),
+            (
+                "run_length_position",
+                self.run_length_position().to_string(),
+            ),
+            ("run_length_bytes", self.run_length_bytes().to_string()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::readers::PrrReader;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    #[test]
+    fn compression_ratio_ok() {
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        let number_of_points = reader.section3().number_of_data_points();
+        let nbit = reader.section5().bits_per_value();
+        let ratio = reader.section7().compression_ratio(number_of_points, nbit);
+
+        let expected = (number_of_points as f64 * nbit as f64 / 8.0)
+            / reader.section7().run_length_bytes() as f64;
+        assert_eq!(expected, ratio);
+        // 解析雨量は降水のない格子点が多いため、36倍程度に圧縮されている
+        assert!((30.0..40.0).contains(&ratio), "ratio: {ratio}");
+    }
 }
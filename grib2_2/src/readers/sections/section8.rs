@@ -1,6 +1,7 @@
 use std::io::{BufReader, Read};
 
-use crate::readers::utils::read_bytes;
+use crate::readers::sections::Describe;
+use crate::readers::utils::{read_available_bytes, read_bytes};
 use crate::{Grib2Error, Grib2Result};
 
 /// 第8節:終端のマーカー
@@ -16,14 +17,28 @@ pub struct Section8 {
 impl Section8 {
     /// 第8節:終端節を読み込む。
     ///
+    /// `allow_missing_end_marker`が`true`の場合、ダウンロードが途中で中断されたファイルなど、
+    /// 終端マーカーが記録されていないファイルでもエラーを返さない。この場合、終端マーカーとして
+    /// 読み込めたバイト列を記録し、`has_end_marker`は`false`を返す。
+    ///
     /// # 引数
     ///
     /// * `reader` - GRIB2リーダー
+    /// * `allow_missing_end_marker` - 終端マーカーが記録されていなくてもエラーにしない場合は`true`
     ///
     /// # 戻り値
     ///
     /// * 第8節:終端節
-    pub(crate) fn from_reader<R: Read>(reader: &mut BufReader<R>) -> Grib2Result<Self> {
+    pub(crate) fn from_reader<R: Read>(
+        reader: &mut BufReader<R>,
+        allow_missing_end_marker: bool,
+    ) -> Grib2Result<Self> {
+        if allow_missing_end_marker {
+            // 読み込めたバイト列のみを終端マーカーとして記録
+            let mut end_marker = [0u8; 4];
+            read_available_bytes(reader, "第8節:終端マーカー", &mut end_marker)?;
+            return Ok(Self { end_marker });
+        }
         // 第8節:終端マーカー
         let end_marker = read_bytes(reader, "第8節:終端マーカー", 4)?;
         if end_marker != SECTION8_END_MARKER.as_bytes() {
@@ -45,4 +60,15 @@ impl Section8 {
     pub fn end_marker(&self) -> &[u8; 4] {
         &self.end_marker
     }
+
+    /// 終端のマーカーが正しく記録されているかを返す。
+    pub fn has_end_marker(&self) -> bool {
+        self.end_marker == SECTION8_END_MARKER.as_bytes()
+    }
+}
+
+impl Describe for Section8 {
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        vec![("end_marker", format!("{:?}", self.end_marker()))]
+    }
 }
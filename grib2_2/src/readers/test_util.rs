@@ -0,0 +1,170 @@
+//! テスト支援
+//!
+//! GRIB2ファイルを用意せずに、手作業で作成したランレングス圧縮符号列から
+//! `Grib2RecordIter`を構築する。`test-util`フィーチャーを有効にした場合に利用できる。
+
+use std::io::{BufReader, Cursor};
+
+use crate::readers::records::{Grib2RecordIter, Grib2RecordIterBuilder};
+use crate::readers::sections::Section3_0;
+use crate::Grib2Result;
+
+/// 格子の北西端の格子点の緯度（1e-6度単位）
+const LAT_MAX: u32 = 35_000_000;
+/// 格子の北西端の格子点の経度（1e-6度単位）
+const LON_MIN: u32 = 139_000_000;
+/// 緯度及び経度方向の増分（1e-6度単位）
+const INC: u32 = 1_000;
+
+/// メモリ上に記録したランレングス圧縮符号列
+///
+/// `Grib2RecordIter`はリーダーを借用するため、ランレングス圧縮符号列とレベル別物理値を
+/// この構造体が所有して、イテレーターに貸し出す。
+pub struct InMemoryRunLength {
+    /// ランレングス圧縮符号列を読み込むリーダー
+    reader: BufReader<Cursor<Vec<u8>>>,
+    /// ランレングス圧縮符号列のバイト数
+    total_bytes: usize,
+    /// 1データのビット数
+    nbit: u16,
+    /// 今回の圧縮に用いたレベルの最大値
+    maxv: u16,
+    /// レベル別物理値
+    level_values: Vec<u16>,
+}
+
+impl InMemoryRunLength {
+    /// メモリ上に記録したランレングス圧縮符号列を構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `run_length` - ランレングス圧縮符号列（1バイトに1つの符号）
+    /// * `nbit` - 1データのビット数
+    /// * `maxv` - 今回の圧縮に用いたレベルの最大値
+    /// * `level_values` - レベル1から順に格納したレベル別物理値
+    ///
+    /// # 戻り値
+    ///
+    /// * メモリ上に記録したランレングス圧縮符号列
+    pub fn new(run_length: Vec<u8>, nbit: u16, maxv: u16, level_values: Vec<u16>) -> Self {
+        Self {
+            total_bytes: run_length.len(),
+            reader: BufReader::new(Cursor::new(run_length)),
+            nbit,
+            maxv,
+            level_values,
+        }
+    }
+
+    /// ランレングス圧縮符号列、1データのビット数、レベルの最大値及びレベル別物理値を設定した
+    /// ビルダーを返す。
+    ///
+    /// 格子の範囲と資料点数は、呼び出し側で設定する。
+    ///
+    /// # 戻り値
+    ///
+    /// * レコードイテレーターのビルダー
+    pub fn builder(&mut self) -> Grib2RecordIterBuilder<'_, Cursor<Vec<u8>>, u16> {
+        self.reader.get_mut().set_position(0);
+        Grib2RecordIterBuilder::new()
+            .reader(&mut self.reader)
+            .total_bytes(self.total_bytes)
+            .nbit(self.nbit)
+            .maxv(self.maxv)
+            .level_values(&self.level_values)
+    }
+
+    /// 北緯35度、東経139度を北西端として、緯度及び経度方向に0.001度間隔で格子点が並ぶ格子の
+    /// レコードを反復処理するイテレーターを返す。
+    ///
+    /// # 引数
+    ///
+    /// * `columns` - 経度方向の格子数
+    /// * `rows` - 緯度方向の格子数
+    ///
+    /// # 戻り値
+    ///
+    /// * レコードを反復処理するイテレーター
+    pub fn record_iter(
+        &mut self,
+        columns: u32,
+        rows: u32,
+    ) -> Grib2Result<Grib2RecordIter<'_, Cursor<Vec<u8>>, u16>> {
+        self.builder()
+            .number_of_points(columns * rows)
+            .grid_shape(columns, rows)
+            .lat_max(LAT_MAX)
+            .lon_min(LON_MIN)
+            .lon_max(LON_MIN + (columns - 1) * INC)
+            .lat_inc(INC)
+            .lon_inc(INC)
+            .build()
+    }
+}
+
+/// `InMemoryRunLength::record_iter`が返すイテレーターと同じ格子を定義した第3節を返す。
+///
+/// # 引数
+///
+/// * `columns` - 経度方向の格子数
+/// * `rows` - 緯度方向の格子数
+///
+/// # 戻り値
+///
+/// * 第3節:格子系定義節
+pub fn section3(columns: u32, rows: u32) -> Section3_0 {
+    let mut bytes = 72u32.to_be_bytes().to_vec();
+    // 節番号、格子系定義の出典
+    bytes.extend([3, 0]);
+    // 資料点数
+    bytes.extend((columns * rows).to_be_bytes());
+    // 格子点数を定義するリストのオクテット数及び説明、格子系定義テンプレート番号
+    bytes.extend([0, 0, 0, 0]);
+    // 地球の形状から地球回転楕円体の短軸の尺度付きの長さまで
+    bytes.extend([0u8; 16]);
+    bytes.extend(columns.to_be_bytes());
+    bytes.extend(rows.to_be_bytes());
+    // 原作成領域の基本角及び基本角の細分
+    bytes.extend([0u8; 8]);
+    bytes.extend(LAT_MAX.to_be_bytes());
+    bytes.extend(LON_MIN.to_be_bytes());
+    bytes.push(0);
+    bytes.extend((LAT_MAX - (rows - 1) * INC).to_be_bytes());
+    bytes.extend((LON_MIN + (columns - 1) * INC).to_be_bytes());
+    bytes.extend(INC.to_be_bytes());
+    bytes.extend(INC.to_be_bytes());
+    bytes.push(0);
+
+    Section3_0::from_reader(&mut BufReader::new(Cursor::new(bytes))).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryRunLength;
+
+    #[test]
+    fn record_iter_of_hand_authored_run_length_ok() {
+        // nbit = 4、maxv = 3、LNGU = 12
+        // {1}: レベル1が1個
+        // {2, 4 + 1}: レベル2が1 + (5 - 4) = 2個
+        // {0, 4 + 2}: レベル0（欠測）が1 + (6 - 4) = 3個
+        let run_length = vec![1, 2, 5, 0, 6];
+        let mut fixture = InMemoryRunLength::new(run_length, 4, 3, vec![100, 200, 300]);
+        let records = fixture
+            .record_iter(3, 2)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let values = records.iter().map(|r| r.value).collect::<Vec<_>>();
+        assert_eq!(
+            vec![Some(100), Some(200), Some(200), None, None, None],
+            values
+        );
+        assert_eq!((35_000_000, 139_000_000), (records[0].lat, records[0].lon));
+        assert_eq!((34_999_000, 139_002_000), (records[5].lat, records[5].lon));
+
+        // 同じランレングス圧縮符号列から、繰り返しイテレーターを構築できる
+        assert_eq!(6, fixture.record_iter(3, 2).unwrap().count());
+    }
+}
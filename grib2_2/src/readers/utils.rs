@@ -1,7 +1,14 @@
-use std::io::{BufReader, Read};
+#[cfg(unix)]
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::{BufReader, Read, Seek};
+#[cfg(unix)]
+use std::os::unix::fs::FileExt;
+use std::path::Path;
 
 use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
 
+use crate::readers::sections::{Section0, Section1};
 use crate::{Grib2Error, Grib2Result};
 
 /// バイト列を読み込む。
@@ -27,6 +34,73 @@ where
     Ok(buf)
 }
 
+/// ファイルの終端に達するまで、最大`buf.len()`バイトを読み込む。
+///
+/// # 引数
+///
+/// * `reader` - リーダー
+/// * `name` - 読み込むデータの名前
+/// * `buf` - 読み込んだバイト列を格納するバッファー
+///
+/// # 戻り値
+///
+/// * 読み込めたバイト数
+pub(crate) fn read_available_bytes<R: Read>(
+    reader: &mut BufReader<R>,
+    name: &str,
+    buf: &mut [u8],
+) -> Grib2Result<usize> {
+    let mut read_bytes = 0;
+    while read_bytes < buf.len() {
+        match reader.read(&mut buf[read_bytes..]) {
+            Ok(0) => break,
+            Ok(n) => read_bytes += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                return Err(Grib2Error::ReadError(
+                    format!("{name}の読み込みに失敗しました。{e}").into(),
+                ))
+            }
+        }
+    }
+
+    Ok(read_bytes)
+}
+
+/// 読み込みを開始した位置から最大`max_offset`バイトまで`GRIB`を探し、`GRIB`の直後まで読み込む。
+///
+/// # 引数
+///
+/// * `reader` - リーダー
+/// * `max_offset` - `GRIB`を探す最大のバイト数
+///
+/// # 戻り値
+///
+/// * 読み込みを開始した位置から`GRIB`が記録されている位置までのバイト数
+pub(crate) fn skip_to_grib<R: Read>(
+    reader: &mut BufReader<R>,
+    max_offset: usize,
+) -> Grib2Result<usize> {
+    let mut grib = read_bytes(reader, "第0節:GRIB", 4)?;
+    let mut offset = 0;
+    while grib != b"GRIB" {
+        if offset == max_offset {
+            return Err(Grib2Error::ReadError(
+                format!(
+                    "第0節:GRIBは、ASCIIバイト表現で`GRIB`を記録していなければなりません。\
+                    読み込みを開始した位置から{max_offset}バイト以内に`GRIB`が見つかりませんでした。"
+                )
+                .into(),
+            ));
+        }
+        grib.remove(0);
+        grib.push(read_u8(reader, "第0節:GRIB")?);
+        offset += 1;
+    }
+
+    Ok(offset)
+}
+
 /// 符号なし整数を読み込む関数を生成するマクロ
 ///
 /// * `$fname` - 関数名
@@ -63,6 +137,47 @@ impl_read_uint!(read_u16, u16);
 impl_read_uint!(read_u32, u32);
 impl_read_uint!(read_u64, u64);
 
+/// 浮動小数点数を読み込む関数を生成するマクロ
+///
+/// * `$fname` - 関数名
+/// * `$type` - 読み込む浮動小数点数の型
+macro_rules! impl_read_float {
+    ($(#[$attr:meta])* $fname:ident, $type:ty) => {
+        /// ビッグエンディアンで記録されたIEEE 754形式の浮動小数点数を読み込む。
+        ///
+        /// テンプレート直後の座標値や、単純圧縮の参照値などの読み込みに使用する。
+        ///
+        /// # 引数
+        ///
+        /// * `reader` - リーダー
+        /// * `name` - 読み込むデータの名前
+        ///
+        /// # 戻り値
+        ///
+        /// * 浮動小数点数の値
+        $(#[$attr])*
+        pub(crate) fn $fname<R>(reader: &mut BufReader<R>, name: &str) -> Grib2Result<$type>
+        where
+            R: Read,
+        {
+            let mut buf = [0_u8; std::mem::size_of::<$type>()];
+            reader.read_exact(&mut buf).map_err(|e| {
+                Grib2Error::ReadError(format!("{name}の読み込みに失敗しました。{e}").into())
+            })?;
+
+            Ok(<$type>::from_be_bytes(buf))
+        }
+    };
+}
+
+impl_read_float!(read_f32, f32);
+// 単純圧縮（テンプレート5.0）などの参照値の読み込みに使用する予定
+impl_read_float!(
+    #[allow(dead_code)]
+    read_f64,
+    f64
+);
+
 /// 符号なし整数を読み込み検証する関数を生成するマクロ
 macro_rules! validate_uint {
     ($fname:ident, $read_fn:ident, $type:ty) => {
@@ -138,11 +253,46 @@ macro_rules! impl_read_int {
     };
 }
 
-//impl_read_int!(read_i8, i8);
+impl_read_int!(read_i8, i8);
 impl_read_int!(read_i16, i16);
 impl_read_int!(read_i32, i32);
 //impl_read_int!(read_i64, i64);
 
+/// 符号と絶対値で表現された32ビットの値を、符号付き整数に変換する。
+///
+/// # 引数
+///
+/// * `value` - 最上位ビットを符号とする符号と絶対値で表現された値
+///
+/// # 戻り値
+///
+/// * 符号付き整数
+pub(crate) fn sign_magnitude_to_i32(value: u32) -> i32 {
+    let magnitude = (value & 0x7FFF_FFFF) as i32;
+    if value & 0x8000_0000 == 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+/// 符号付き整数を、符号と絶対値で表現された32ビットの値に変換する。
+///
+/// # 引数
+///
+/// * `value` - 符号付き整数
+///
+/// # 戻り値
+///
+/// * 最上位ビットを符号とする符号と絶対値で表現された値
+pub(crate) fn i32_to_sign_magnitude(value: i32) -> u32 {
+    if value < 0 {
+        0x8000_0000 | value.unsigned_abs()
+    } else {
+        value as u32
+    }
+}
+
 pub(crate) fn read_date_time<R>(
     reader: &mut BufReader<R>,
     name: &str,
@@ -186,3 +336,288 @@ where
 
     Ok(PrimitiveDateTime::new(date, time).assume_utc())
 }
+
+/// 次に記録されている節の節番号を返す。
+///
+/// ファイルポインターの位置は変更しない。
+///
+/// # 引数
+///
+/// * `reader` - GRIB2ファイルリーダー
+///
+/// # 戻り値
+///
+/// * 次に記録されている節の節番号
+pub(crate) fn peek_section_number<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<u8> {
+    // 節の長さ: 4バイト、節番号: 1バイト
+    let bytes = read_bytes(reader, "節の長さ及び節番号", 5)?;
+    reader
+        .seek_relative(-5)
+        .map_err(|_| Grib2Error::ReadError("節番号の読み戻しに失敗しました。".into()))?;
+
+    Ok(bytes[4])
+}
+
+/// 第1節以降の節が、正しい順序で記録されていることを確認する。
+///
+/// 節の長さと節番号のみを読み込み、節の長さに従って読み飛ばしながら、プロダクトの組の中で
+/// 節番号が減少していないことを確認する。第7節の後には、次の組として第2節、第3節または
+/// 第4節が記録されていてもよい。第8節の終端マーカーまたはファイルの終端に達した場合は、
+/// 確認を終了する。
+///
+/// 確認した後、ファイルポインターを呼び出し前の位置に戻す。
+///
+/// # 引数
+///
+/// * `reader` - 第1節の先頭にファイルポインターが位置するリーダー
+pub(crate) fn validate_section_order<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<()> {
+    let start = reader.stream_position().map_err(|_| {
+        Grib2Error::ReadError("ファイルの読み込み位置の取得に失敗しました。".into())
+    })?;
+    let result = walk_section_numbers(reader);
+    reader.seek(std::io::SeekFrom::Start(start)).map_err(|_| {
+        Grib2Error::ReadError("ファイルの読み込み位置の復元に失敗しました。".into())
+    })?;
+
+    result
+}
+
+/// 節の長さに従って節を読み飛ばしながら、節番号の順序を確認する。
+///
+/// # 引数
+///
+/// * `reader` - 第1節の先頭にファイルポインターが位置するリーダー
+fn walk_section_numbers<R: Read + Seek>(reader: &mut BufReader<R>) -> Grib2Result<()> {
+    let mut previous = 0u8;
+    let mut buf = [0u8; 5];
+    loop {
+        // 節の長さ: 4バイト、節番号: 1バイト
+        // 読み込めない場合は、各節の読み込みでエラーを報告する
+        if reader.read_exact(&mut buf[..4]).is_err() || &buf[..4] == b"7777" {
+            return Ok(());
+        }
+        if reader.read_exact(&mut buf[4..]).is_err() {
+            return Ok(());
+        }
+        let section_bytes = u32::from_be_bytes(buf[..4].try_into().unwrap()) as i64;
+        let section_number = buf[4];
+        let next_group = previous == 7 && (2..=4).contains(&section_number);
+        if section_number < previous && !next_group {
+            return Err(Grib2Error::ReadError(
+                format!(
+                    "節が正しい順序で記録されていません。第{previous}節の後に第{section_number}節が記録されています。"
+                )
+                .into(),
+            ));
+        }
+        if section_bytes < 5 || reader.seek_relative(section_bytes - 5).is_err() {
+            return Ok(());
+        }
+        previous = section_number;
+    }
+}
+
+/// ファイルの指定された位置から、ファイルポインターを移動せずに読み込むリーダー
+///
+/// `FileExt::read_at`で読み込むため、複数のスレッドが1つのファイルを共有して、
+/// それぞれ異なる位置から同時に読み込める。
+#[cfg(unix)]
+pub struct PositionedReader<'a> {
+    /// ファイル
+    file: &'a File,
+    /// 次に読み込む位置
+    position: u64,
+}
+
+#[cfg(unix)]
+impl<'a> PositionedReader<'a> {
+    /// ファイルの指定された位置から読み込むリーダーを構築する。
+    ///
+    /// # 引数
+    ///
+    /// * `file` - ファイル
+    /// * `position` - 読み込みを開始する位置
+    ///
+    /// # 戻り値
+    ///
+    /// * リーダー
+    pub(crate) fn new(file: &'a File, position: u64) -> Self {
+        Self { file, position }
+    }
+}
+
+#[cfg(unix)]
+impl Read for PositionedReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.file.read_at(buf, self.position)?;
+        self.position += n as u64;
+
+        Ok(n)
+    }
+}
+
+/// GRIB2ファイルの第8節の後にデータが存在しないことを確認する。
+///
+/// `GRIB`の前に記録されている余分なバイト数と、第0節に記録されているGRIB報全体の長さの和を
+/// ファイルのバイト数と比較する。第8節の後にデータが存在する場合、そのファイルは複数のGRIB報を
+/// 連結している可能性がある。ファイルのバイト数が少ない場合は、ファイルが途中で切れているとして
+/// エラーを返す。
+///
+/// # 引数
+///
+/// * `path` - GRIB2ファイルのパス
+/// * `section0` - GRIB2ファイルから読み込んだ第0節
+pub(crate) fn validate_no_trailing_data(path: &Path, section0: &Section0) -> Grib2Result<()> {
+    let total_bytes = section0.offset() + section0.total_bytes();
+    let file_bytes = path
+        .metadata()
+        .map_err(|e| Grib2Error::Unexpected(e.into()))?
+        .len() as usize;
+    if file_bytes < total_bytes {
+        return Err(Grib2Error::ReadError(
+            format!(
+                "ファイルのバイト数({file_bytes})が第0節に記録されているGRIB報全体の長さから求めた\
+                バイト数({total_bytes})より{}バイト短いため、ファイルが途中で切れている可能性があります。",
+                total_bytes - file_bytes
+            )
+            .into(),
+        ));
+    }
+    if total_bytes < file_bytes {
+        return Err(Grib2Error::ReadError(
+            format!(
+                "第8節の後に{}バイトのデータが存在します。ファイルに複数のGRIB報が記録されている可能性があります。",
+                file_bytes - total_bytes
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// GRIB2ファイルの第0節及び第1節のみを読み込み、資料の参照時刻を返す。
+///
+/// 第2節以降を読み込まないため、ファイルの索引を作成する場合など、資料の参照時刻のみが
+/// 必要な場合に利用する。
+///
+/// # 引数
+///
+/// * `path` - GRIB2ファイルのパス
+///
+/// # 戻り値
+///
+/// * 資料の参照時刻（世界標準時）
+pub fn peek_reference_time<P: AsRef<Path>>(path: P) -> Grib2Result<OffsetDateTime> {
+    let path = path.as_ref();
+    if !path.is_file() {
+        return Err(Grib2Error::FileDoesNotExist);
+    }
+    let file = OpenOptions::new()
+        .read(true)
+        .open(path)
+        .map_err(|e| Grib2Error::Unexpected(e.into()))?;
+    let mut reader = BufReader::new(file);
+    Section0::from_reader(&mut reader)?;
+    let section1 = Section1::from_reader(&mut reader)?;
+
+    Ok(section1.referenced_at())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use super::{peek_reference_time, read_f32, read_f64, validate_no_trailing_data};
+    use crate::readers::sections::Section0;
+    use crate::readers::{PrrReader, ReaderOptions};
+    use crate::Grib2Error;
+
+    const PRR_FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../resources/Z__C_RJTD_20161121010000_SRF_GPV_Ggis1km_Prr60lv_Aper10min_ANAL_grib2.bin"
+    );
+
+    #[test]
+    fn read_f32_ok() {
+        let bytes = vec![
+            0x3F, 0x80, 0x00, 0x00, 0xC0, 0x20, 0x00, 0x00, 0x7F, 0x80, 0x00, 0x00,
+        ];
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        assert_eq!(1.0, read_f32(&mut reader, "1.0").unwrap());
+        assert_eq!(-2.5, read_f32(&mut reader, "-2.5").unwrap());
+        assert_eq!(f32::INFINITY, read_f32(&mut reader, "無限大").unwrap());
+        assert!(read_f32(&mut reader, "終端").is_err());
+    }
+
+    #[test]
+    fn read_f64_ok() {
+        let mut bytes = vec![0x3F, 0xF0, 0, 0, 0, 0, 0, 0];
+        bytes.extend([0x40, 0x09, 0x21, 0xFB, 0x54, 0x44, 0x2D, 0x18]);
+        let mut reader = BufReader::new(Cursor::new(bytes));
+        assert_eq!(1.0, read_f64(&mut reader, "1.0").unwrap());
+        assert_eq!(
+            std::f64::consts::PI,
+            read_f64(&mut reader, "円周率").unwrap()
+        );
+        assert!(read_f64(&mut reader, "終端").is_err());
+    }
+
+    #[test]
+    fn validate_no_trailing_data_ok() {
+        let path = std::path::Path::new(PRR_FILE);
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        assert!(validate_no_trailing_data(path, reader.section0()).is_ok());
+
+        // `GRIB`の前に余分なバイトが記録されている場合は、そのバイト数を加えて比較する
+        let junk_path =
+            std::env::temp_dir().join(format!("grib2_2_utils_junk_{}", std::process::id()));
+        let mut bytes = b"junk".to_vec();
+        bytes.extend(std::fs::read(PRR_FILE).unwrap());
+        std::fs::write(&junk_path, bytes).unwrap();
+        let options = ReaderOptions::new().max_leading_bytes(4);
+        let junk_reader = PrrReader::with_options(&junk_path, options).unwrap();
+        let junk = validate_no_trailing_data(&junk_path, junk_reader.section0());
+        let without_offset = validate_no_trailing_data(&junk_path, reader.section0());
+        std::fs::remove_file(&junk_path).unwrap();
+        assert!(junk.is_ok());
+        assert!(without_offset.is_err());
+
+        // GRIB報全体の長さよりファイルが短い場合は、途中で切れていると報告する
+        let Err(Grib2Error::ReadError(message)) = validate_no_trailing_data(
+            path,
+            &with_total_bytes(reader.section0().total_bytes() + 10),
+        ) else {
+            panic!("途中で切れたファイルがエラーになりませんでした。");
+        };
+        assert!(message.contains("10バイト短い"), "{message}");
+
+        // GRIB報全体の長さよりファイルが長い場合は、第8節の後にデータが存在すると報告する
+        let Err(Grib2Error::ReadError(message)) =
+            validate_no_trailing_data(path, &with_total_bytes(reader.section0().total_bytes() - 4))
+        else {
+            panic!("第8節の後のデータがエラーになりませんでした。");
+        };
+        assert!(message.contains("第8節の後に4バイト"), "{message}");
+    }
+
+    /// GRIB報全体の長さを`total_bytes`にした第0節を返す。
+    fn with_total_bytes(total_bytes: usize) -> Section0 {
+        let mut bytes = std::fs::read(PRR_FILE).unwrap()[..16].to_vec();
+        bytes[8..16].copy_from_slice(&(total_bytes as u64).to_be_bytes());
+        Section0::from_reader(&mut BufReader::new(Cursor::new(bytes))).unwrap()
+    }
+
+    #[test]
+    fn peek_reference_time_ok() {
+        let referenced_at = peek_reference_time(PRR_FILE).unwrap();
+        let reader = PrrReader::new(PRR_FILE).unwrap();
+        assert_eq!(reader.section1().referenced_at(), referenced_at);
+    }
+
+    #[test]
+    fn peek_reference_time_of_missing_file_err() {
+        let result = peek_reference_time("does-not-exist.bin");
+        assert!(matches!(result, Err(Grib2Error::FileDoesNotExist)));
+    }
+}